@@ -1,9 +1,91 @@
 #![allow(clippy::unwrap_used)]
-use std::path::PathBuf;
+use std::fs::{create_dir_all, write};
+use std::path::{Path, PathBuf};
 
-use criterion::{Criterion, criterion_group, criterion_main};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use snakedown::config::ConfigBuilder;
-use snakedown::render_docs;
+use snakedown::{load_index, render_docs, render_pages, thread_pool, write_pages};
+
+const SYNTHETIC_SUB_PACKAGES: usize = 10;
+const SYNTHETIC_MODULES: usize = 20;
+
+/// Writes a package with a few hundred modules full of documented
+/// classes and functions that refer to each other
+fn create_synthetic_package(root: &Path) -> PathBuf {
+    let pkg = root.join("synthetic");
+    create_dir_all(&pkg).unwrap();
+    write(
+        pkg.join("__init__.py"),
+        "\"\"\"A large synthetic package.\"\"\"\n",
+    )
+    .unwrap();
+
+    for p in 0..SYNTHETIC_SUB_PACKAGES {
+        let sub_pkg = pkg.join(format!("sub{p}"));
+        create_dir_all(&sub_pkg).unwrap();
+        write(
+            sub_pkg.join("__init__.py"),
+            format!("\"\"\"Sub package number {p}.\"\"\"\n"),
+        )
+        .unwrap();
+
+        for m in 0..SYNTHETIC_MODULES {
+            let mut module = format!("\"\"\"Module {m} of sub package {p}.\"\"\"\n\n");
+            for c in 0..5 {
+                module.push_str(&format!(
+                    r#"
+class Class{c}:
+    """A class that refers to [[synthetic.sub{p}.mod{m}.function{c}]].
+
+    Args:
+        value (int): the value to store
+
+    Attributes:
+        value (int): the stored value
+    """
+
+    def __init__(self, value: int):
+        self.value = value
+
+    def method(self, other: "Class{c}", *, scale: float = 1.0) -> float:
+        """Combines two instances.
+
+        Args:
+            other (Class{c}): the instance to combine with
+            scale (float): how much to scale the result by
+
+        Returns:
+            float: the combined value
+        """
+        return (self.value + other.value) * scale
+"#
+                ));
+            }
+            for f in 0..10 {
+                module.push_str(&format!(
+                    r#"
+def function{f}(a: int, b: str = "b", *args, **kwargs) -> list[str]:
+    """Does something with its arguments.
+
+    Args:
+        a (int): the first argument
+        b (str): the second argument
+
+    Returns:
+        list[str]: the result
+
+    Raises:
+        ValueError: if `a` is negative
+    """
+    return [b] * a
+"#
+                ));
+            }
+            write(sub_pkg.join(format!("mod{m}.py")), module).unwrap();
+        }
+    }
+    pkg
+}
 
 fn criterion_benchmark(c: &mut Criterion) {
     let bench_config_path = PathBuf::from("benchmark-config.toml");
@@ -20,6 +102,36 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.to_async(&runtime)
             .iter(|| render_docs(config_builder.clone()));
     });
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let pkg_path = create_synthetic_package(temp_dir.path());
+    let mut group = c.benchmark_group("synthetic");
+    group.sample_size(10);
+    // 0 lets rayon use all cores
+    for jobs in [1, 0] {
+        let config = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg_path.clone()))
+            .with_site_root(Some(temp_dir.path().join("site")))
+            .with_externals(Some(Default::default()))
+            .with_offline(Some(true))
+            .with_jobs(Some(jobs))
+            .build()
+            .unwrap();
+        let pool = thread_pool(&config).unwrap();
+
+        // we skip render_docs here, as the build manifest would
+        // make every run after the first one a no-op
+        group.bench_function(BenchmarkId::from_parameter(jobs), |b| {
+            b.iter(|| {
+                pool.install(|| {
+                    let pages = render_pages(&config, load_index(&config).unwrap()).unwrap();
+                    write_pages(&pages).unwrap();
+                })
+            });
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
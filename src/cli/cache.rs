@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use clap::Subcommand;
+use color_eyre::Result;
+use snakedown::config::ConfigBuilder;
+use snakedown::indexing::external::cache::{
+    CachedInventory, clear_cache, get_cache_path, init_cache, list_cache,
+};
+use snakedown::indexing::external::fetch::{FetchOptions, ReqwestClient, refresh_cache};
+use std::sync::Arc;
+
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum CacheCommand {
+    /// List the cached inventories and when they were downloaded
+    List,
+    /// Download the inventories of the externals in the config again
+    Refresh {
+        /// Only refresh the externals with these keys
+        keys: Vec<String>,
+    },
+    /// Remove inventories from the cache
+    Clear {
+        /// Only remove the externals with these keys
+        keys: Vec<String>,
+    },
+    /// Print the location of the cache
+    Path,
+}
+
+pub async fn run(command: CacheCommand, config_builder: ConfigBuilder) -> Result<()> {
+    match command {
+        CacheCommand::Path => println!("{}", get_cache_path().display()),
+        CacheCommand::List => {
+            let ttl = config_builder.build()?.cache_ttl;
+            let inventories = list_cache(&get_cache_path())?;
+            if inventories.is_empty() {
+                println!("the cache is empty");
+            }
+            for inventory in inventories {
+                println!("{}", describe(&inventory, ttl));
+            }
+        }
+        CacheCommand::Refresh { keys } => {
+            let config = config_builder.build()?;
+            let cache_path = init_cache(None)?;
+            let client = Arc::new(ReqwestClient::new(&config.fetch)?);
+            let options = FetchOptions::new(&config.fetch, config.cache_ttl);
+            for key in
+                refresh_cache(client, &config.externals, &keys, &cache_path, &options).await?
+            {
+                println!("refreshed {key}");
+            }
+        }
+        CacheCommand::Clear { keys } => {
+            for key in clear_cache(&get_cache_path(), &keys)? {
+                println!("removed {key}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn describe(inventory: &CachedInventory, ttl: Duration) -> String {
+    let size = format!("{:.1} KiB", inventory.size as f64 / 1024.0);
+    match &inventory.entry {
+        Some(entry) => {
+            let state = if entry.is_fresh(ttl) {
+                "fresh"
+            } else {
+                "stale"
+            };
+            let url = if entry.url.is_empty() {
+                String::new()
+            } else {
+                format!(" from {}", entry.url)
+            };
+            format!(
+                "{}: {size}{url}, fetched {} ago ({state})",
+                inventory.key,
+                format_age(entry.age())
+            )
+        }
+        None => format!("{}: {size}", inventory.key),
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+    if days >= 2 {
+        format!("{days} days")
+    } else if hours >= 2 {
+        format!("{hours} hours")
+    } else {
+        format!("{minutes} minutes")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, time::Duration};
+
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use snakedown::indexing::external::cache::{CacheEntry, CachedInventory};
+
+    use super::{describe, format_age};
+
+    #[test]
+    fn ages_are_rounded_down() -> Result<()> {
+        assert_eq!(format_age(Duration::from_secs(59)), "0 minutes");
+        assert_eq!(format_age(Duration::from_secs(90 * 60)), "90 minutes");
+        assert_eq!(format_age(Duration::from_secs(47 * 60 * 60)), "47 hours");
+        assert_eq!(format_age(Duration::from_secs(50 * 60 * 60)), "2 days");
+        Ok(())
+    }
+
+    #[test]
+    fn describe_cached_inventory() -> Result<()> {
+        let mut inventory = CachedInventory {
+            key: "numpy".to_string(),
+            path: PathBuf::from("sphinx/numpy.inv"),
+            size: 2048,
+            entry: Some(CacheEntry::new(
+                "https://numpy.org/doc/stable/objects.inv".to_string(),
+                None,
+                None,
+            )),
+        };
+        let hour = Duration::from_secs(60 * 60);
+        assert_eq!(
+            describe(&inventory, hour),
+            "numpy: 2.0 KiB from https://numpy.org/doc/stable/objects.inv, fetched 0 minutes ago (fresh)"
+        );
+        assert!(describe(&inventory, Duration::ZERO).ends_with("(stale)"));
+
+        inventory.entry = None;
+        assert_eq!(describe(&inventory, hour), "numpy: 2.0 KiB");
+        Ok(())
+    }
+}
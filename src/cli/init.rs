@@ -2,9 +2,10 @@
 use dialoguer::Input;
 use snakedown::config::ExternalIndex;
 use snakedown::config::{ConfigBuilder, predefined_externals};
+use snakedown::parsing::sphinx::conf::{SphinxConf, discover_sphinx_conf};
 use snakedown::render::SSG;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use console;
 
@@ -23,7 +24,30 @@ pub fn wizard() -> Result<ConfigBuilder> {
 
     let mut out = ConfigBuilder::default();
 
-    let suggestion = {
+    // answers from an existing Sphinx setup are used as the defaults
+    let mut sphinx_conf = None;
+    if let Some(conf_path) = discover_sphinx_conf(Path::new("")) {
+        let import = Confirm::with_theme(&theme)
+            .with_prompt(format!(
+                "Found a Sphinx config at `{}`, do you want to import its settings?",
+                conf_path.display()
+            ))
+            .default(true)
+            .interact()?;
+        if import {
+            let conf_dir = conf_path.parent().unwrap_or(Path::new(""));
+            let conf = SphinxConf::from_path(&conf_path)?;
+            out = conf.to_config_builder(conf_dir, Path::new(""));
+            sphinx_conf = Some((conf, conf_dir.to_path_buf()));
+        }
+    }
+
+    let guessed_pkg_path = sphinx_conf
+        .as_ref()
+        .and_then(|(conf, _)| conf.guess_pkg_path(Path::new("")));
+    let suggestion = if let Some(pkg_path) = guessed_pkg_path {
+        pkg_path.display().to_string()
+    } else {
         let current_dir = std::env::current_dir().unwrap_or_default();
         let dirname = current_dir
             .components()
@@ -62,28 +86,33 @@ pub fn wizard() -> Result<ConfigBuilder> {
 
     out = out.with_api_content_path(Some(api_content_path));
 
+    let imported_skip_private = sphinx_conf
+        .as_ref()
+        .and_then(|(conf, _)| conf.skip_private());
     let skip_private_input = Confirm::with_theme(&theme)
         .with_prompt("Do you want to skip private objects?")
+        .default(imported_skip_private.unwrap_or(false))
         .interact()?;
 
-    if skip_private_input {
-        out = out.with_skip_private(Some(true));
+    if skip_private_input || imported_skip_private.is_some() {
+        out = out.with_skip_private(Some(skip_private_input));
     }
 
+    let imported_skip_undoc = sphinx_conf.as_ref().and_then(|(conf, _)| conf.skip_undoc());
     let skip_undoc_choice = Confirm::with_theme(&theme)
         .with_prompt("Do you want to skip undocumented objects?")
+        .default(imported_skip_undoc.unwrap_or(false))
         .interact()?;
 
-    if skip_undoc_choice {
-        out = out.with_skip_undoc(Some(true));
+    if skip_undoc_choice || imported_skip_undoc.is_some() {
+        out = out.with_skip_undoc(Some(skip_undoc_choice));
     }
 
-    let ssg_choices = [SSG::Markdown, SSG::Zola];
+    let ssg_choices = [SSG::Markdown, SSG::Zola, SSG::Hugo];
     let ssg_choice_index = Select::with_theme(&theme)
         .with_prompt("What SSG would you like to use?")
         .default(0)
-        .item(SSG::Zola)
-        .item(SSG::Markdown)
+        .items(ssg_choices)
         .interact()?;
 
     // we only allow options that we know so this get is guaranteed to
@@ -94,6 +123,10 @@ pub fn wizard() -> Result<ConfigBuilder> {
     out = out.with_ssg(Some(*ssg_choice));
 
     let mut pre_defined_externals = predefined_externals();
+    let imported_externals = sphinx_conf
+        .map(|(conf, conf_dir)| conf.resolved_externals(&conf_dir))
+        .unwrap_or_default();
+    pre_defined_externals.extend(imported_externals.clone());
     let mut key_list = Vec::new();
     let mut selector = MultiSelect::with_theme(&theme);
     selector = selector.with_prompt("Would you like to reference any of these libraries?");
@@ -102,7 +135,7 @@ pub fn wizard() -> Result<ConfigBuilder> {
         key_list.push(key);
         selector = selector.item_checked(
             val.name.clone().unwrap_or_else(|| key.to_string()),
-            key == "builtins",
+            key == "builtins" || imported_externals.contains_key(key),
         );
     }
 
@@ -1,3 +1,4 @@
+pub mod cache;
 pub mod init;
 mod verbosity;
 
@@ -6,10 +7,17 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
-use clap_verbosity_flag::Verbosity;
-use snakedown::{config::ConfigBuilder, render::SSG};
 
-pub fn resolve_runtime_config(args: CliArgs) -> Result<ConfigBuilder> {
+use crate::cli::cache::CacheCommand;
+use clap_verbosity_flag::Verbosity;
+use snakedown::{
+    config::ConfigBuilder,
+    indexing::object_ref::{ReferencePolicy, ReferenceSyntax},
+    parsing::docstring::DocstringStyle,
+    render::SSG,
+};
+
+pub fn resolve_runtime_config(args: &CliArgs) -> Result<ConfigBuilder> {
     let mut config_builder = ConfigBuilder::default();
 
     let pyproject_path = PathBuf::from("pyproject.toml");
@@ -19,12 +27,12 @@ pub fn resolve_runtime_config(args: CliArgs) -> Result<ConfigBuilder> {
         config_builder = config_builder.merge(pyproject_config);
     }
 
-    if let Some(config_file_path) = discover_config_file(args.config_file) {
+    if let Some(config_file_path) = discover_config_file(args.config_file.clone()) {
         let file_config_builder = ConfigBuilder::from_path(&config_file_path)?;
         config_builder = config_builder.merge(file_config_builder);
     }
 
-    let skip_write = match args.skip_write {
+    let skip_write = match &args.skip_write {
         Some(sp) => {
             if sp.skip_write {
                 Some(true)
@@ -37,7 +45,7 @@ pub fn resolve_runtime_config(args: CliArgs) -> Result<ConfigBuilder> {
         None => None,
     };
 
-    let skip_private = match args.skip_private {
+    let skip_private = match &args.skip_private {
         Some(sp) => {
             if sp.skip_private {
                 Some(true)
@@ -50,7 +58,7 @@ pub fn resolve_runtime_config(args: CliArgs) -> Result<ConfigBuilder> {
         None => None,
     };
 
-    let skip_undoc = match args.skip_undoc {
+    let skip_undoc = match &args.skip_undoc {
         Some(su) => {
             if su.skip_undoc {
                 Some(true)
@@ -63,17 +71,67 @@ pub fn resolve_runtime_config(args: CliArgs) -> Result<ConfigBuilder> {
         None => None,
     };
 
+    let inherited_members = match &args.inherited_members {
+        Some(im) => {
+            if im.inherited_members {
+                Some(true)
+            } else if im.no_inherited_members {
+                Some(false)
+            } else {
+                unreachable!()
+            }
+        }
+        None => None,
+    };
+
+    let class_hierarchy = match &args.class_hierarchy {
+        Some(ch) => {
+            if ch.class_hierarchy {
+                Some(true)
+            } else if ch.no_class_hierarchy {
+                Some(false)
+            } else {
+                unreachable!()
+            }
+        }
+        None => None,
+    };
+
+    let public_api = match &args.public_api {
+        Some(pa) => {
+            if pa.public_api {
+                Some(true)
+            } else if pa.no_public_api {
+                Some(false)
+            } else {
+                unreachable!()
+            }
+        }
+        None => None,
+    };
+
     let cli_args_builder = ConfigBuilder::default()
-        .with_api_content_path(args.api_content_path)
-        .with_site_root(args.site_root)
-        .with_pkg_path(args.pkg_path)
+        .with_api_content_path(args.api_content_path.clone())
+        .with_site_root(args.site_root.clone())
+        .with_pkg_path(args.pkg_path.clone())
         .with_offline(args.offline)
         .with_skip_write(skip_write)
         .with_skip_undoc(skip_undoc)
         .with_skip_private(skip_private)
-        .with_exclude(args.exclude)
-        .with_notebook_content_path(args.notebooks_content_path)
-        .with_notebook_path(args.notebooks_path)
+        .with_exclude(args.exclude.clone())
+        .with_notebook_content_path(args.notebooks_content_path.clone())
+        .with_notebook_path(args.notebooks_path.clone())
+        .with_docstring_style(args.docstring_style)
+        .with_inherited_members(inherited_members)
+        .with_class_hierarchy(class_hierarchy)
+        .with_public_api(public_api)
+        .with_jobs(args.jobs)
+        .with_signature_width(args.signature_width)
+        .with_templates_dir(args.templates_dir.clone())
+        .with_reference_policy(args.reference_policy)
+        .with_reference_syntax(args.reference_syntax.clone())
+        .with_cache_ttl(args.cache_ttl)
+        .with_manifest_dir(args.manifest_dir.clone())
         .with_ssg(args.ssg);
 
     config_builder = config_builder.merge(cli_args_builder);
@@ -136,10 +194,65 @@ pub struct SkipUndoc {
     no_skip_undoc: bool,
 }
 
+#[derive(Args, PartialEq, Eq, Debug)]
+#[group(multiple = false)]
+pub struct InheritedMembers {
+    /// List the methods classes inherit from other classes in the package
+    /// conflicts with --no-inherited-members
+    #[arg(long)]
+    inherited_members: bool,
+
+    /// Don't list inherited methods on class pages
+    /// conflicts with --inherited-members
+    #[arg(long)]
+    no_inherited_members: bool,
+}
+
+#[derive(Args, PartialEq, Eq, Debug)]
+#[group(multiple = false)]
+pub struct ClassHierarchy {
+    /// Generate a page with the class hierarchy of the package
+    /// conflicts with --no-class-hierarchy
+    #[arg(long)]
+    class_hierarchy: bool,
+
+    /// Don't generate a class hierarchy page
+    /// conflicts with --class-hierarchy
+    #[arg(long)]
+    no_class_hierarchy: bool,
+}
+
+#[derive(Args, PartialEq, Eq, Debug)]
+#[group(multiple = false)]
+pub struct PublicApi {
+    /// Document objects re-exported by a package at their public path
+    /// conflicts with --no-public-api
+    #[arg(long)]
+    public_api: bool,
+
+    /// Document objects where they are defined
+    /// conflicts with --public-api
+    #[arg(long)]
+    no_public_api: bool,
+}
+
 #[derive(Subcommand, Clone)]
 pub enum SubCommand {
     /// Interactively generate a new config
     Init,
+    /// Generate a new config from the settings in a Sphinx `conf.py`
+    ImportSphinx {
+        /// The `conf.py` to import, by default it is looked for in `docs/` and `doc/`
+        conf_path: Option<PathBuf>,
+    },
+    /// Render the documentation and re-render it whenever the package,
+    /// the notebooks or the config change
+    Watch,
+    /// Inspect and manage the cache of downloaded inventories
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
 }
 
 #[derive(Parser)]
@@ -185,6 +298,15 @@ pub struct CliArgs {
     #[command(flatten)]
     pub skip_write: Option<SkipWrite>,
 
+    #[command(flatten)]
+    pub inherited_members: Option<InheritedMembers>,
+
+    #[command(flatten)]
+    pub class_hierarchy: Option<ClassHierarchy>,
+
+    #[command(flatten)]
+    pub public_api: Option<PublicApi>,
+
     /// Any files that should be excluded, can be file or directories and specific multiple times but currently globs are not supported
     #[arg(short, long)]
     pub exclude: Option<Vec<PathBuf>>,
@@ -197,6 +319,38 @@ pub struct CliArgs {
     #[arg(long, value_enum)]
     pub ssg: Option<SSG>,
 
+    /// Which docstring style to parse docstrings as. By default it is detected per docstring
+    #[arg(long, value_enum)]
+    pub docstring_style: Option<DocstringStyle>,
+
+    /// How many threads to use for parsing and rendering. 0 means one per core
+    #[arg(long, short)]
+    pub jobs: Option<usize>,
+
+    /// How long a function signature can get before it is split over multiple lines
+    #[arg(long)]
+    pub signature_width: Option<usize>,
+
+    /// Directory with templates that replace the built-in ones of the same name
+    #[arg(long)]
+    pub templates_dir: Option<PathBuf>,
+
+    /// What to do with references that can't be resolved
+    #[arg(long, value_enum)]
+    pub reference_policy: Option<ReferencePolicy>,
+
+    /// Which ways of writing references in docstrings to recognise, separated by commas
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub reference_syntax: Option<Vec<ReferenceSyntax>>,
+
+    /// How many hours downloaded inventories are used before checking whether they changed
+    #[arg(long)]
+    pub cache_ttl: Option<u64>,
+
+    /// Directory to keep the build manifests in, by default `manifests` in the cache
+    #[arg(long)]
+    pub manifest_dir: Option<PathBuf>,
+
     #[command(flatten)]
     pub verbose: Verbosity,
 }
@@ -209,6 +363,7 @@ mod tests {
     use clap_verbosity_flag::log::Level;
     use clap_verbosity_flag::{LogLevel, VerbosityFilter};
     use color_eyre::Result;
+    use std::path::Path;
 
     #[test]
     fn test_custom_log_level_interface() -> Result<()> {
@@ -330,10 +485,114 @@ fn test_args_all_flags() -> Result<()> {
         Ok(())
     }
 
+    #[test]
+    fn test_args_class_flags() -> Result<()> {
+        let args =
+            CliArgs::parse_from(["snakedown", "--inherited-members", "--no-class-hierarchy"]);
+        assert_eq!(
+            args.inherited_members,
+            Some(InheritedMembers {
+                inherited_members: true,
+                no_inherited_members: false
+            })
+        );
+        assert_eq!(
+            args.class_hierarchy,
+            Some(ClassHierarchy {
+                class_hierarchy: false,
+                no_class_hierarchy: true
+            })
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_args_exclude_short_flag() -> Result<()> {
         let args = CliArgs::parse_from(["mybin", "-e", "excluded"]);
         assert_eq!(args.exclude, Some(vec![PathBuf::from("excluded")]));
         Ok(())
     }
+
+    #[test]
+    fn test_args_jobs() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "-j", "4"]);
+        assert_eq!(args.jobs, Some(4));
+        let args = CliArgs::parse_from(["mybin", "--jobs", "1"]);
+        assert_eq!(args.jobs, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_signature_width() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "--signature-width", "100"]);
+        assert_eq!(args.signature_width, Some(100));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_templates_dir() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "--templates-dir", "templates"]);
+        assert_eq!(args.templates_dir, Some(PathBuf::from("templates")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_reference_policy() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "--reference-policy", "warn"]);
+        assert_eq!(args.reference_policy, Some(ReferencePolicy::Warn));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_reference_syntax() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "--reference-syntax", "wiki,autorefs"]);
+        assert_eq!(
+            args.reference_syntax,
+            Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Autorefs])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_cache_ttl() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "--cache-ttl", "12"]);
+        assert_eq!(args.cache_ttl, Some(12));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_manifest_dir() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "--manifest-dir", "manifests"]);
+        assert_eq!(args.manifest_dir, Some(PathBuf::from("manifests")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_cache_subcommand() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "cache", "clear", "numpy", "scipy"]);
+        assert!(matches!(
+            args.subcommand,
+            Some(SubCommand::Cache {
+                command: CacheCommand::Clear { keys }
+            }) if keys == ["numpy", "scipy"]
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_import_sphinx_subcommand() -> Result<()> {
+        let args = CliArgs::parse_from(["mybin", "import-sphinx", "docs/source/conf.py"]);
+        assert!(matches!(
+            args.subcommand,
+            Some(SubCommand::ImportSphinx { conf_path: Some(path) })
+                if path == Path::new("docs/source/conf.py")
+        ));
+
+        let args = CliArgs::parse_from(["mybin", "import-sphinx"]);
+        assert!(matches!(
+            args.subcommand,
+            Some(SubCommand::ImportSphinx { conf_path: None })
+        ));
+        Ok(())
+    }
 }
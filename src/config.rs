@@ -1,23 +1,44 @@
-use color_eyre::{Result, eyre::OptionExt};
+use color_eyre::{
+    Result,
+    eyre::{OptionExt, eyre},
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fs::File,
     io::{Read, Write},
     path::{Path, PathBuf},
+    time::Duration,
 };
 use toml;
 use url::Url;
 
+use crate::indexing::external::cache::get_cache_path;
+use crate::indexing::object_ref::{ReferencePolicy, ReferenceSyntax, default_reference_syntaxes};
+use crate::parsing::docstring::DocstringStyle;
+use crate::parsing::sphinx::types::SphinxType;
 use crate::render::{
     SSG,
-    formats::{Renderer, md::MdRenderer, zola::ZolaRenderer},
+    formats::{
+        Renderer,
+        hugo::{HugoFrontMatterFormat, HugoRenderer},
+        md::MdRenderer,
+        zola::ZolaRenderer,
+    },
 };
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct ExternalIndex {
     pub name: Option<String>,
     pub url: String,
+    /// A local path or `file://` url of the inventory, for when it can't or
+    /// shouldn't be downloaded from `url`. Links still point to `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inventory: Option<String>,
+    /// Domains (`c`) or roles (`std:term`) to include besides python objects
+    /// and documents, which are always included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
 }
 
 impl ExternalIndex {
@@ -27,10 +48,55 @@ pub fn new(name: Option<String>, url: String) -> Self {
         } else {
             format!("{}/", url)
         };
-        Self { name, url }
+        Self {
+            name,
+            url,
+            inventory: None,
+            domains: vec![],
+        }
+    }
+
+    pub fn with_inventory(mut self, inventory: Option<String>) -> Self {
+        self.inventory = inventory;
+        self
+    }
+
+    pub fn with_domains(mut self, domains: Vec<String>) -> Self {
+        self.domains = domains;
+        self
+    }
+
+    /// Whether objects of this type were opted in to with [`ExternalIndex::domains`]
+    pub fn includes(&self, sphinx_type: &SphinxType) -> bool {
+        let sphinx_type = sphinx_type.to_string();
+        let domain = sphinx_type.split_once(':').map(|(domain, _)| domain);
+        self.domains
+            .iter()
+            .any(|d| *d == sphinx_type || Some(d.as_str()) == domain)
+    }
+
+    /// The path of the inventory if it is read from disk instead of downloaded
+    pub fn local_inventory(&self) -> Result<Option<PathBuf>> {
+        let Some(inventory) = &self.inventory else {
+            return Ok(None);
+        };
+        if inventory.starts_with("file://") {
+            let path = Url::parse(inventory)?
+                .to_file_path()
+                .map_err(|_| eyre!("not a valid file url: {inventory}"))?;
+            Ok(Some(path))
+        } else {
+            Ok(Some(PathBuf::from(inventory)))
+        }
     }
 }
 
+/// The line length black uses by default
+pub const DEFAULT_SIGNATURE_WIDTH: usize = 88;
+
+/// How many hours cached inventories are used before checking if they changed
+pub const DEFAULT_CACHE_TTL: u64 = 24 * 7;
+
 pub struct Config {
     pub site_root: PathBuf,
     pub api_content_path: PathBuf,
@@ -44,11 +110,49 @@ pub struct Config {
     pub notebook_path: Option<PathBuf>,
     pub skip_write: bool,
     pub offline: bool,
+    pub docstring_style: DocstringStyle,
+    pub inherited_members: bool,
+    pub class_hierarchy: bool,
+    pub public_api: bool,
+    pub jobs: usize,
+    pub signature_width: usize,
+    pub templates_dir: Option<PathBuf>,
+    pub reference_policy: ReferencePolicy,
+    pub reference_syntax: Vec<ReferenceSyntax>,
+    pub cache_ttl: Duration,
+    pub fetch: FetchConfig,
+    pub manifest_dir: PathBuf,
+}
+
+/// How the inventories of externals are downloaded
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct FetchConfig {
+    /// seconds after which a download is given up on
+    pub timeout: u64,
+    /// how often a download is retried when the server can't be reached
+    /// or has trouble of its own
+    pub retries: u32,
+    /// proxy for all downloads, otherwise the `HTTPS_PROXY` and friends
+    /// environment variables are used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 30,
+            retries: 3,
+            proxy: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
 pub struct RenderConfig {
     zola: Option<ZolaConfig>,
+    hugo: Option<HugoConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
@@ -56,6 +160,11 @@ pub struct ZolaConfig {
     use_shortcodes: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct HugoConfig {
+    front_matter: HugoFrontMatterFormat,
+}
+
 #[derive(Default, Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct ConfigBuilder {
     site_root: Option<PathBuf>,
@@ -66,11 +175,24 @@ pub struct ConfigBuilder {
     skip_private: Option<bool>,
     ssg: Option<SSG>,
     render: Option<RenderConfig>,
+    fetch: Option<FetchConfig>,
     exclude: Option<Vec<PathBuf>>,
     externals: Option<HashMap<String, ExternalIndex>>,
     notebook_path: Option<PathBuf>,
     skip_write: Option<bool>,
     offline: Option<bool>,
+    docstring_style: Option<DocstringStyle>,
+    inherited_members: Option<bool>,
+    class_hierarchy: Option<bool>,
+    public_api: Option<bool>,
+    jobs: Option<usize>,
+    signature_width: Option<usize>,
+    templates_dir: Option<PathBuf>,
+    reference_policy: Option<ReferencePolicy>,
+    reference_syntax: Option<Vec<ReferenceSyntax>>,
+    /// in hours
+    cache_ttl: Option<u64>,
+    manifest_dir: Option<PathBuf>,
 }
 
 impl ConfigBuilder {
@@ -84,11 +206,24 @@ pub fn init_with_defaults(mut self) -> Self {
             .with_pkg_path(Some(PathBuf::from(".")))
             .with_exclude(Some(Vec::new()))
             .with_ssg(Some(SSG::Markdown))
+            .with_docstring_style(Some(DocstringStyle::Auto))
+            .with_inherited_members(Some(false))
+            .with_class_hierarchy(Some(false))
+            .with_public_api(Some(false))
+            .with_jobs(Some(0))
+            .with_signature_width(Some(DEFAULT_SIGNATURE_WIDTH))
+            .with_reference_policy(Some(ReferencePolicy::Error))
+            .with_reference_syntax(Some(default_reference_syntaxes()))
+            .with_cache_ttl(Some(DEFAULT_CACHE_TTL))
+            .with_fetch_config(Some(FetchConfig::default()))
             .with_externals(Some(predefined_externals()))
             .with_render_config(Some(RenderConfig {
                 zola: Some(ZolaConfig {
                     use_shortcodes: true,
                 }),
+                hugo: Some(HugoConfig {
+                    front_matter: HugoFrontMatterFormat::Toml,
+                }),
             }));
 
         self
@@ -99,6 +234,12 @@ pub fn with_render_config(mut self, render_config: Option<RenderConfig>) -> Self
         }
         self
     }
+    pub fn with_fetch_config(mut self, fetch_config: Option<FetchConfig>) -> Self {
+        if fetch_config.is_some() {
+            self.fetch = fetch_config;
+        }
+        self
+    }
     pub fn with_api_content_path(mut self, api_content_path: Option<PathBuf>) -> Self {
         if api_content_path.is_some() {
             self.api_content_path = api_content_path;
@@ -190,6 +331,75 @@ pub fn with_ssg(mut self, ssg: Option<SSG>) -> Self {
         }
         self
     }
+    pub fn with_docstring_style(mut self, docstring_style: Option<DocstringStyle>) -> Self {
+        if docstring_style.is_some() {
+            self.docstring_style = docstring_style;
+        }
+        self
+    }
+    pub fn with_inherited_members(mut self, inherited_members: Option<bool>) -> Self {
+        if inherited_members.is_some() {
+            self.inherited_members = inherited_members;
+        }
+        self
+    }
+    pub fn with_class_hierarchy(mut self, class_hierarchy: Option<bool>) -> Self {
+        if class_hierarchy.is_some() {
+            self.class_hierarchy = class_hierarchy;
+        }
+        self
+    }
+    pub fn with_public_api(mut self, public_api: Option<bool>) -> Self {
+        if public_api.is_some() {
+            self.public_api = public_api;
+        }
+        self
+    }
+    pub fn with_jobs(mut self, jobs: Option<usize>) -> Self {
+        if jobs.is_some() {
+            self.jobs = jobs;
+        }
+        self
+    }
+
+    pub fn with_signature_width(mut self, signature_width: Option<usize>) -> Self {
+        if signature_width.is_some() {
+            self.signature_width = signature_width;
+        }
+        self
+    }
+
+    pub fn with_templates_dir(mut self, templates_dir: Option<PathBuf>) -> Self {
+        if templates_dir.is_some() {
+            self.templates_dir = templates_dir;
+        }
+        self
+    }
+
+    pub fn with_reference_policy(mut self, reference_policy: Option<ReferencePolicy>) -> Self {
+        if reference_policy.is_some() {
+            self.reference_policy = reference_policy;
+        }
+        self
+    }
+    pub fn with_reference_syntax(mut self, reference_syntax: Option<Vec<ReferenceSyntax>>) -> Self {
+        if reference_syntax.is_some() {
+            self.reference_syntax = reference_syntax;
+        }
+        self
+    }
+    pub fn with_cache_ttl(mut self, cache_ttl: Option<u64>) -> Self {
+        if cache_ttl.is_some() {
+            self.cache_ttl = cache_ttl;
+        }
+        self
+    }
+    pub fn with_manifest_dir(mut self, manifest_dir: Option<PathBuf>) -> Self {
+        if manifest_dir.is_some() {
+            self.manifest_dir = manifest_dir;
+        }
+        self
+    }
     pub fn with_offline(mut self, offline: Option<bool>) -> Self {
         if offline.is_some() {
             self.offline = offline;
@@ -200,6 +410,15 @@ pub fn build(self) -> Result<Config> {
         let renderer: Box<dyn Renderer> = match self.ssg {
             Some(SSG::Markdown) | None => Box::new(MdRenderer::new()),
             Some(SSG::Zola) => Box::new(ZolaRenderer {}),
+            Some(SSG::Hugo) => {
+                let front_matter_format = self
+                    .render
+                    .as_ref()
+                    .and_then(|r| r.hugo.as_ref())
+                    .map(|h| h.front_matter)
+                    .unwrap_or_default();
+                Box::new(HugoRenderer::new(front_matter_format))
+            }
         };
 
         let mut external_linkings = HashMap::new();
@@ -210,6 +429,8 @@ pub fn build(self) -> Result<Config> {
                 let external_index = ExternalIndex {
                     name: external_index.name,
                     url: external_index.url,
+                    inventory: external_index.inventory,
+                    domains: external_index.domains,
                 };
                 external_linkings.insert(key, external_index);
             }
@@ -228,9 +449,39 @@ pub fn build(self) -> Result<Config> {
             notebook_path: self.notebook_path,
             skip_write: self.skip_write.unwrap_or(false),
             offline: self.offline.unwrap_or(false),
+            docstring_style: self.docstring_style.unwrap_or_default(),
+            inherited_members: self.inherited_members.unwrap_or(false),
+            class_hierarchy: self.class_hierarchy.unwrap_or(false),
+            public_api: self.public_api.unwrap_or(false),
+            jobs: self.jobs.unwrap_or(0),
+            signature_width: self.signature_width.unwrap_or(DEFAULT_SIGNATURE_WIDTH),
+            templates_dir: self.templates_dir,
+            reference_policy: self.reference_policy.unwrap_or_default(),
+            reference_syntax: self
+                .reference_syntax
+                .unwrap_or_else(default_reference_syntaxes),
+            cache_ttl: Duration::from_secs(self.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL) * 60 * 60),
+            fetch: self.fetch.unwrap_or_default(),
+            manifest_dir: self
+                .manifest_dir
+                .unwrap_or_else(|| get_cache_path().join("manifests")),
         })
     }
 
+    /// A copy without the settings that only change how the docs are built,
+    /// not what they look like
+    pub fn output_settings(&self) -> Self {
+        Self {
+            skip_write: None,
+            offline: None,
+            jobs: None,
+            cache_ttl: None,
+            fetch: None,
+            manifest_dir: None,
+            ..self.clone()
+        }
+    }
+
     pub fn to_file(&self, path: &Path) -> Result<()> {
         let serialized = toml::to_string(&self)?;
         let mut file = File::create(path)?;
@@ -267,6 +518,13 @@ pub fn merge(mut self, other: ConfigBuilder) -> Self {
             self.ssg = other.ssg;
         }
 
+        if other.render.is_some() {
+            self.render = other.render;
+        }
+        if other.fetch.is_some() {
+            self.fetch = other.fetch;
+        }
+
         if other.notebook_path.is_some() {
             self.notebook_path = other.notebook_path;
         }
@@ -279,6 +537,39 @@ pub fn merge(mut self, other: ConfigBuilder) -> Self {
         if other.offline.is_some() {
             self.offline = other.offline;
         }
+        if other.docstring_style.is_some() {
+            self.docstring_style = other.docstring_style;
+        }
+        if other.inherited_members.is_some() {
+            self.inherited_members = other.inherited_members;
+        }
+        if other.class_hierarchy.is_some() {
+            self.class_hierarchy = other.class_hierarchy;
+        }
+        if other.public_api.is_some() {
+            self.public_api = other.public_api;
+        }
+        if other.jobs.is_some() {
+            self.jobs = other.jobs;
+        }
+        if other.signature_width.is_some() {
+            self.signature_width = other.signature_width;
+        }
+        if other.templates_dir.is_some() {
+            self.templates_dir = other.templates_dir;
+        }
+        if other.reference_policy.is_some() {
+            self.reference_policy = other.reference_policy;
+        }
+        if other.reference_syntax.is_some() {
+            self.reference_syntax = other.reference_syntax;
+        }
+        if other.cache_ttl.is_some() {
+            self.cache_ttl = other.cache_ttl;
+        }
+        if other.manifest_dir.is_some() {
+            self.manifest_dir = other.manifest_dir;
+        }
 
         if let Some(v) = other.exclude {
             self.exclude_paths(v);
@@ -372,7 +663,8 @@ mod test {
 
     use crate::render::SSG;
 
-    use super::ConfigBuilder;
+    use super::{ConfigBuilder, HugoConfig, RenderConfig, ZolaConfig};
+    use crate::render::formats::hugo::HugoFrontMatterFormat;
     use assert_fs::TempDir;
     use color_eyre::Result;
 
@@ -391,7 +683,13 @@ fn test_pyproject_toml() -> Result<()> {
             .with_notebook_path(Some(PathBuf::from("examples")))
             .with_skip_private(Some(false))
             .with_exclude(Some(vec![]))
-            .with_ssg(Some(SSG::Zola));
+            .with_ssg(Some(SSG::Zola))
+            .with_render_config(Some(RenderConfig {
+                zola: Some(ZolaConfig {
+                    use_shortcodes: true,
+                }),
+                hugo: None,
+            }));
 
         assert_eq!(config_builder, expected);
 
@@ -477,6 +775,27 @@ fn config_merge_other_takes_precedent() -> Result<()> {
         Ok(())
     }
 
+    #[test]
+    fn render_config_is_merged() -> Result<()> {
+        let render_config = RenderConfig {
+            zola: None,
+            hugo: Some(HugoConfig {
+                front_matter: HugoFrontMatterFormat::Yaml,
+            }),
+        };
+        let first = ConfigBuilder::default().init_with_defaults();
+        let second = ConfigBuilder::default()
+            .with_ssg(Some(SSG::Hugo))
+            .with_render_config(Some(render_config.clone()));
+
+        let merged = first.merge(second);
+
+        assert_eq!(merged.render, Some(render_config));
+        assert_eq!(merged.ssg, Some(SSG::Hugo));
+
+        Ok(())
+    }
+
     #[test]
     fn can_deserialize_example_config() -> Result<()> {
         let example_config = ConfigBuilder::from_path(&PathBuf::from("snakedown.example.toml"))?;
@@ -504,4 +823,100 @@ fn can_deserialize_serialized_config() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn externals_can_have_a_local_inventory() -> Result<()> {
+        use super::ExternalIndex;
+
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(
+            &path,
+            r#"
+[externals]
+numpy = { url = "https://numpy.org/doc/stable/", inventory = "docs/inventories/numpy.inv" }
+scipy = { url = "https://docs.scipy.org/doc/scipy/", inventory = "file:///srv/inventories/scipy.inv" }
+pandas = { url = "https://pandas.pydata.org/docs/" }
+"#,
+        )?;
+        let externals = ConfigBuilder::from_path(&path)?
+            .externals
+            .unwrap_or_default();
+        let local_inventory = |key: &str| {
+            externals
+                .get(key)
+                .map(ExternalIndex::local_inventory)
+                .transpose()
+                .map(Option::flatten)
+        };
+
+        assert_eq!(
+            local_inventory("numpy")?,
+            Some(PathBuf::from("docs/inventories/numpy.inv"))
+        );
+        #[cfg(not(windows))]
+        assert_eq!(
+            local_inventory("scipy")?,
+            Some(PathBuf::from("/srv/inventories/scipy.inv"))
+        );
+        assert_eq!(local_inventory("pandas")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_config_fills_in_missing_values() -> Result<()> {
+        use super::FetchConfig;
+
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(
+            &path,
+            r#"
+[fetch]
+retries = 0
+proxy = "http://proxy.example.com:3128"
+"#,
+        )?;
+        let config = ConfigBuilder::from_path(&path)?.build()?;
+
+        assert_eq!(
+            config.fetch,
+            FetchConfig {
+                retries: 0,
+                proxy: Some("http://proxy.example.com:3128".to_string()),
+                ..Default::default()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn externals_include_the_domains_they_opt_in_to() -> Result<()> {
+        use crate::parsing::sphinx::types::SphinxType;
+
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.join("snakedown.toml");
+        std::fs::write(
+            &path,
+            r#"
+[externals]
+builtins = { url = "https://docs.python.org/3/", domains = ["c", "std:term"] }
+"#,
+        )?;
+        let externals = ConfigBuilder::from_path(&path)?.build()?.externals;
+        let builtins = externals
+            .get("builtins")
+            .ok_or_else(|| color_eyre::eyre::eyre!("builtins is missing"))?;
+        let includes = |sphinx_type: &str| -> Result<bool> {
+            Ok(builtins.includes(&SphinxType::try_from(sphinx_type)?))
+        };
+
+        assert_eq!(builtins.domains, vec!["c", "std:term"]);
+        assert!(includes("c:function")?);
+        assert!(includes("c:macro")?);
+        assert!(includes("std:term")?);
+        assert!(!includes("std:label")?);
+        assert!(!includes("cpp:function")?);
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 use lazy_regex::regex_replace_all;
+use rayon::prelude::*;
 use std::{
     ffi::{OsStr, OsString},
     fs::{File, create_dir_all, exists},
@@ -8,7 +9,7 @@
 
 use color_eyre::eyre::{OptionExt, Result, eyre};
 
-use crate::indexing::index::RawIndex;
+use crate::{indexing::index::RawIndex, parsing::python::jupyter::parse_notebook_file};
 
 /// determines whether given path is a Python module
 /// i.e. a file with a .py extension
@@ -217,28 +218,65 @@ pub fn crawl_package(
     skip_private: bool,
     exclude: Vec<PathBuf>,
 ) -> Result<()> {
+    // parsing is the expensive part and doesn't need the rest of the index, so
+    // we do that in parallel and add the results in order to keep the output deterministic
+    let parsed: Vec<_> = package_sources(pkg_path, skip_private, &exclude)?
+        .into_par_iter()
+        .map(|path| {
+            let parsed = index.parse_file(&path);
+            (path, parsed)
+        })
+        .collect();
+    for (path, parsed) in parsed {
+        index.insert_file(path, parsed)?;
+    }
+    Ok(())
+}
+
+pub fn crawl_notebooks(index: &mut RawIndex, path: &Path) -> Result<()> {
+    let parsed: Vec<_> = notebook_sources(path)?
+        .into_par_iter()
+        .map(|path| {
+            tracing::debug!("Indexing {}", &path.display());
+            let parsed = parse_notebook_file(&path);
+            (path, parsed)
+        })
+        .collect();
+    for (path, parsed) in parsed {
+        index.insert_notebook(&path, parsed?)?;
+    }
+    Ok(())
+}
+
+/// All the files in the package that should be indexed
+pub fn package_sources(
+    pkg_path: &Path,
+    skip_private: bool,
+    exclude: &[PathBuf],
+) -> Result<Vec<PathBuf>> {
+    let mut sources = vec![];
     for entry in WalkDir::new(pkg_path)
         .into_iter()
-        .filter_entry(|e| should_include(e.path(), skip_private, &exclude))
+        .filter_entry(|e| should_include(e.path(), skip_private, exclude))
     {
         let path = entry?.into_path();
         if path.is_file() {
-            tracing::debug!("Indexing {}", &path.display());
-            index.index_file(path)?;
+            sources.push(path);
         }
     }
-    Ok(())
+    Ok(sources)
 }
 
-pub fn crawl_notebooks(index: &mut RawIndex, path: &Path) -> Result<()> {
+/// All the notebooks under `path`
+pub fn notebook_sources(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut sources = vec![];
     for entry in WalkDir::new(path).into_iter() {
         let path = entry?.into_path();
         if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("ipynb") {
-            tracing::debug!("Indexing {}", &path.display());
-            index.index_notebook(&path)?;
+            sources.push(path);
         }
     }
-    Ok(())
+    Ok(sources)
 }
 
 /// will walk the provided path and index all the subpackages and modules
@@ -272,6 +310,21 @@ pub fn walk_package(
     })
 }
 
+/// Whether [`crawl_package`] would have indexed the file at `path`, which is
+/// the case if neither the file nor any of the packages it is in are skipped.
+pub fn should_index_path(
+    pkg_path: &Path,
+    path: &Path,
+    skip_private: bool,
+    exclude: &[PathBuf],
+) -> bool {
+    path.starts_with(pkg_path)
+        && path
+            .ancestors()
+            .take_while(|p| p.starts_with(pkg_path))
+            .all(|p| should_include(p, skip_private, exclude))
+}
+
 fn should_include(path: &Path, skip_private: bool, excluded: &[PathBuf]) -> bool {
     if !(is_python_package(path).unwrap_or(false) || is_python_module(path).unwrap_or(false)) {
         tracing::info!(
@@ -529,4 +582,44 @@ fn test_shallow_prefix() -> Result<()> {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_should_index_path() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.join("pkg");
+        create_empty_python_package_on_disk(&pkg)?;
+        create_empty_python_package_on_disk(&pkg.join("_private"))?;
+        create_empty_python_package_on_disk(&pkg.join("excluded"))?;
+        for module in ["mod.py", "_private/mod.py", "excluded/mod.py"] {
+            File::create(pkg.join(module))?;
+        }
+        let exclude = vec![PathBuf::from("pkg/excluded")];
+
+        assert!(should_index_path(&pkg, &pkg.join("mod.py"), true, &exclude));
+        assert!(!should_index_path(
+            &pkg,
+            &pkg.join("_private/mod.py"),
+            true,
+            &exclude
+        ));
+        assert!(should_index_path(
+            &pkg,
+            &pkg.join("_private/mod.py"),
+            false,
+            &exclude
+        ));
+        assert!(!should_index_path(
+            &pkg,
+            &pkg.join("excluded/mod.py"),
+            false,
+            &exclude
+        ));
+        assert!(!should_index_path(
+            &pkg,
+            &temp_dir.join("mod.py"),
+            false,
+            &exclude
+        ));
+        Ok(())
+    }
 }
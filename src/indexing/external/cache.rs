@@ -1,15 +1,33 @@
 use std::{
-    fs::{create_dir_all, exists},
-    path::PathBuf,
+    fs::{create_dir_all, exists, read_dir, read_to_string, remove_file, write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use color_eyre::Result;
+use serde::{Deserialize, Serialize};
 
-/// `$HOME/.snakedown/cache` if $HOME exists and `./.shakedown/cache` else
-// TODO: start tying different combinations of XDG_CACHE_HOME and APPDIR where appropriate
-// see also: https://github.com/savente93/snakedown/issues/56
+/// Where cached files are kept, in order of preference:
+/// `$SNAKEDOWN_CACHE_DIR`, `$XDG_CACHE_HOME/snakedown`, `$HOME/.cache/snakedown`,
+/// `%LOCALAPPDATA%\snakedown\cache` and `./.snakedown/cache` if none of those are set.
 pub fn get_cache_path() -> PathBuf {
-    PathBuf::from(".").join(".snakedown").join("cache")
+    cache_path_from_env(|var| std::env::var(var).ok())
+}
+
+fn cache_path_from_env(var: impl Fn(&str) -> Option<String>) -> PathBuf {
+    // empty variables are treated as unset, like the XDG spec asks
+    let var = |name: &str| var(name).filter(|value| !value.is_empty());
+    if let Some(dir) = var("SNAKEDOWN_CACHE_DIR") {
+        PathBuf::from(dir)
+    } else if let Some(dir) = var("XDG_CACHE_HOME") {
+        PathBuf::from(dir).join("snakedown")
+    } else if let Some(home) = var("HOME") {
+        PathBuf::from(home).join(".cache").join("snakedown")
+    } else if let Some(dir) = var("LOCALAPPDATA") {
+        PathBuf::from(dir).join("snakedown").join("cache")
+    } else {
+        PathBuf::from(".").join(".snakedown").join("cache")
+    }
 }
 
 /// checks if cache exists at the path returned by `get_cache_path` if so that is returned
@@ -28,6 +46,141 @@ pub fn init_cache(maybe_cache_path: Option<PathBuf>) -> Result<PathBuf> {
     Ok(cache_path)
 }
 
+/// Where the inventory of the external index with this key is cached
+pub fn inventory_path(cache_path: &Path, key: &str) -> PathBuf {
+    cache_path
+        .join("sphinx")
+        .join(key.to_lowercase())
+        .with_extension("inv")
+}
+
+/// What we know about where a cached inventory came from, stored next to it
+/// so we can tell when to ask the server whether it changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct CacheEntry {
+    pub url: String,
+    /// seconds since the unix epoch
+    pub fetched_at: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheEntry {
+    pub fn new(url: String, etag: Option<String>, last_modified: Option<String>) -> Self {
+        Self {
+            url,
+            fetched_at: now(),
+            etag,
+            last_modified,
+        }
+    }
+
+    fn path(cache_path: &Path, key: &str) -> PathBuf {
+        inventory_path(cache_path, key).with_extension("toml")
+    }
+
+    /// Inventories that were cached before we kept track of this are treated
+    /// as if they were fetched when the file was last modified.
+    pub fn load(cache_path: &Path, key: &str) -> Option<Self> {
+        let entry_path = Self::path(cache_path, key);
+        if let Ok(contents) = read_to_string(&entry_path) {
+            match toml::from_str(&contents) {
+                Ok(entry) => return Some(entry),
+                Err(e) => tracing::warn!("ignoring corrupt {}: {e}", entry_path.display()),
+            }
+        }
+        let modified = inventory_path(cache_path, key)
+            .metadata()
+            .ok()?
+            .modified()
+            .ok()?;
+        Some(Self {
+            fetched_at: seconds_since_epoch(modified),
+            ..Default::default()
+        })
+    }
+
+    pub fn save(&self, cache_path: &Path, key: &str) -> Result<()> {
+        write(Self::path(cache_path, key), toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        self.age() < ttl
+    }
+
+    /// Mark the entry as fetched just now, e.g. after the server told us it didn't change
+    pub fn touch(&mut self) {
+        self.fetched_at = now();
+    }
+}
+
+fn seconds_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn now() -> u64 {
+    seconds_since_epoch(SystemTime::now())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedInventory {
+    pub key: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub entry: Option<CacheEntry>,
+}
+
+/// All inventories in the cache, sorted by key
+pub fn list_cache(cache_path: &Path) -> Result<Vec<CachedInventory>> {
+    let sphinx_path = cache_path.join("sphinx");
+    if !exists(&sphinx_path)? {
+        return Ok(vec![]);
+    }
+    let mut inventories = vec![];
+    for dir_entry in read_dir(&sphinx_path)? {
+        let path = dir_entry?.path();
+        if path.extension().is_none_or(|ext| ext != "inv") {
+            continue;
+        }
+        let Some(key) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+        inventories.push(CachedInventory {
+            size: path.metadata()?.len(),
+            entry: CacheEntry::load(cache_path, &key),
+            key,
+            path,
+        });
+    }
+    inventories.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(inventories)
+}
+
+/// Removes the given inventories from the cache, or all of them if no keys
+/// are given. Returns the keys of the inventories that were removed.
+pub fn clear_cache(cache_path: &Path, keys: &[String]) -> Result<Vec<String>> {
+    let mut removed = vec![];
+    for inventory in list_cache(cache_path)? {
+        if !keys.is_empty() && !keys.iter().any(|k| k.to_lowercase() == inventory.key) {
+            continue;
+        }
+        remove_file(&inventory.path)?;
+        let entry_path = CacheEntry::path(cache_path, &inventory.key);
+        if exists(&entry_path)? {
+            remove_file(entry_path)?;
+        }
+        removed.push(inventory.key);
+    }
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod test {
     use std::{
@@ -76,4 +229,98 @@ fn init_cache_doesnt_touch_existing_dir() -> Result<()> {
         }
         Ok(())
     }
+
+    #[test]
+    fn cache_location_follows_environment() -> Result<()> {
+        use super::cache_path_from_env;
+        use std::collections::HashMap;
+        use std::path::PathBuf;
+
+        let path_with = |vars: &[(&str, &str)]| {
+            let vars: HashMap<_, _> = vars.iter().copied().collect();
+            cache_path_from_env(|name| vars.get(name).map(|v| v.to_string()))
+        };
+        let home = ("HOME", "/home/me");
+        let xdg = ("XDG_CACHE_HOME", "/tmp/xdg");
+        let explicit = ("SNAKEDOWN_CACHE_DIR", "/srv/cache");
+
+        assert_eq!(
+            path_with(&[home, xdg, explicit]),
+            PathBuf::from("/srv/cache")
+        );
+        assert_eq!(path_with(&[home, xdg]), PathBuf::from("/tmp/xdg/snakedown"));
+        assert_eq!(
+            path_with(&[home, ("XDG_CACHE_HOME", "")]),
+            PathBuf::from("/home/me/.cache/snakedown")
+        );
+        assert_eq!(
+            path_with(&[("LOCALAPPDATA", "C:/Users/me/AppData/Local")]),
+            PathBuf::from("C:/Users/me/AppData/Local/snakedown/cache")
+        );
+        assert_eq!(path_with(&[]), PathBuf::from("./.snakedown/cache"));
+        Ok(())
+    }
+
+    #[test]
+    fn cache_entries_round_trip() -> Result<()> {
+        use super::{CacheEntry, inventory_path};
+        use std::time::Duration;
+
+        let tmp_dir = TempDir::new()?;
+        let cache_path = init_cache(Some(tmp_dir.path().join("cache")))?;
+        assert_eq!(CacheEntry::load(&cache_path, "NumPy"), None);
+
+        // inventories cached before entries were kept are as old as the file
+        std::fs::write(inventory_path(&cache_path, "NumPy"), "inventory")?;
+        let legacy = CacheEntry::load(&cache_path, "NumPy");
+        assert!(legacy.is_some_and(|e| e.is_fresh(Duration::from_secs(60)) && e.url.is_empty()));
+
+        let mut entry = CacheEntry::new(
+            "https://numpy.org/doc/stable/objects.inv".to_string(),
+            Some("\"abc\"".to_string()),
+            None,
+        );
+        entry.fetched_at -= 60 * 60;
+        entry.save(&cache_path, "NumPy")?;
+        let loaded = CacheEntry::load(&cache_path, "numpy");
+        assert_eq!(loaded.as_ref(), Some(&entry));
+        assert!(loaded.is_some_and(|e| !e.is_fresh(Duration::from_secs(60))));
+        Ok(())
+    }
+
+    #[test]
+    fn list_and_clear_cache() -> Result<()> {
+        use super::{CacheEntry, clear_cache, inventory_path, list_cache};
+
+        let tmp_dir = TempDir::new()?;
+        let cache_path = init_cache(Some(tmp_dir.path().join("cache")))?;
+        for key in ["scipy", "numpy", "pandas"] {
+            std::fs::write(inventory_path(&cache_path, key), key)?;
+            CacheEntry::new(format!("https://{key}.org/objects.inv"), None, None)
+                .save(&cache_path, key)?;
+        }
+
+        let keys = |cache_path| -> Result<Vec<String>> {
+            Ok(list_cache(cache_path)?.into_iter().map(|i| i.key).collect())
+        };
+        assert_eq!(keys(&cache_path)?, vec!["numpy", "pandas", "scipy"]);
+        assert_eq!(
+            list_cache(&cache_path)?
+                .first()
+                .and_then(|i| i.entry.clone())
+                .map(|e| e.url),
+            Some("https://numpy.org/objects.inv".to_string())
+        );
+
+        assert_eq!(
+            clear_cache(&cache_path, &["NumPy".to_string()])?,
+            vec!["numpy"]
+        );
+        assert_eq!(keys(&cache_path)?, vec!["pandas", "scipy"]);
+        assert!(!exists(cache_path.join("sphinx").join("numpy.toml"))?);
+
+        assert_eq!(clear_cache(&cache_path, &[])?, vec!["pandas", "scipy"]);
+        assert!(keys(&cache_path)?.is_empty());
+        Ok(())
+    }
 }
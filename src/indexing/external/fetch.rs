@@ -1,157 +1,473 @@
 use std::{
     collections::HashMap,
-    fs::{File, remove_file},
+    fs::File,
+    future::Future,
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
-use reqwest::Response;
+use color_eyre::{Result, eyre::eyre};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use tokio::task::JoinSet;
 use url::Url;
 
-use crate::{config::ExternalIndex, indexing::external::cache::init_cache};
-use color_eyre::Result;
+use crate::{
+    config::{ExternalIndex, FetchConfig},
+    indexing::external::cache::{CacheEntry, inventory_path},
+};
+
+/// The parts of a response we need to cache an inventory
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Downloads inventories. This is a trait so the tests don't need the internet.
+pub trait HttpClient: Send + Sync + 'static {
+    /// Asks the server to only send the body if it changed since `cached` was fetched
+    fn get(
+        &self,
+        url: &Url,
+        cached: Option<&CacheEntry>,
+    ) -> impl Future<Output = Result<HttpResponse>> + Send;
+}
+
+pub struct ReqwestClient {
+    client: reqwest::Client,
+}
+
+impl ReqwestClient {
+    pub fn new(config: &FetchConfig) -> Result<Self> {
+        // reqwest uses the proxy environment variables by default
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(Self {
+            client: builder.build()?,
+        })
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    async fn get(&self, url: &Url, cached: Option<&CacheEntry>) -> Result<HttpResponse> {
+        let mut request = self.client.get(url.clone());
+        if let Some(entry) = cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = request.send().await?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Ok(HttpResponse {
+            status: response.status().as_u16(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            body: response.bytes().await?.to_vec(),
+        })
+    }
+}
 
-pub async fn cache_remote_objects_inv(
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// how long a cached inventory is used before checking whether it changed
+    pub ttl: Duration,
+    /// how long a single attempt can take
+    pub timeout: Duration,
+    pub retries: u32,
+    /// how long to wait before the first retry, doubling for every retry after that
+    pub backoff: Duration,
+    /// download the inventory even if the cached one is fresh
+    pub force: bool,
+}
+
+impl FetchOptions {
+    pub fn new(config: &FetchConfig, ttl: Duration) -> Self {
+        Self {
+            ttl,
+            timeout: Duration::from_secs(config.timeout),
+            retries: config.retries,
+            backoff: Duration::from_millis(500),
+            force: false,
+        }
+    }
+}
+
+/// Retries when the server can't be reached, takes too long or has trouble of
+/// its own. Anything else, like a 404, won't get better by asking again.
+async fn get_with_retries<C: HttpClient>(
+    client: &C,
+    url: &Url,
+    cached: Option<&CacheEntry>,
+    options: &FetchOptions,
+) -> Result<HttpResponse> {
+    let mut attempt = 0;
+    loop {
+        let error = match tokio::time::timeout(options.timeout, client.get(url, cached)).await {
+            Ok(Ok(response)) if response.status == 429 || response.status >= 500 => {
+                eyre!("{url} responded with {}", response.status)
+            }
+            Ok(Ok(response)) if response.status >= 400 => {
+                return Err(eyre!("{url} responded with {}", response.status));
+            }
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(e)) => e,
+            Err(_) => eyre!("{url} did not respond within {:?}", options.timeout),
+        };
+        if attempt >= options.retries {
+            return Err(error.wrap_err(format!("giving up after {} attempt(s)", attempt + 1)));
+        }
+        let delay = options
+            .backoff
+            .saturating_mul(2_u32.saturating_pow(attempt));
+        tracing::debug!("retrying {url} in {delay:?}: {error}");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Makes sure the `objects.inv` at `url` is in the cache. A cached inventory
+/// older than the ttl is revalidated with the server, which only sends it
+/// again if it changed.
+pub async fn cache_remote_objects_inv<C: HttpClient>(
+    client: &C,
     url: &str,
-    project_name: String,
-    maybe_cache_path: Option<PathBuf>,
-    force: bool,
+    project_name: &str,
+    cache_path: &Path,
+    options: &FetchOptions,
 ) -> Result<()> {
-    let cache_path = init_cache(maybe_cache_path)?
-        .join("sphinx")
-        .join(PathBuf::from(project_name.to_lowercase()))
-        .with_extension("inv");
-
-    if cache_path.exists() {
-        if force {
-            remove_file(&cache_path)?;
-        } else {
+    let inv_path = inventory_path(cache_path, project_name);
+
+    let mut cached_entry = None;
+    if inv_path.exists() && !options.force {
+        match CacheEntry::load(cache_path, project_name) {
             // object is already cached so we don't have to do anything else
-            return Ok(());
+            Some(entry) if entry.is_fresh(options.ttl) => return Ok(()),
+            entry => cached_entry = entry,
         }
     }
 
     let full_url = Url::parse(url)?.join("objects.inv")?;
-    let response = fetch_objects_inv_blocking(full_url)
-        .await?
-        .error_for_status()?;
+    let response = get_with_retries(client, &full_url, cached_entry.as_ref(), options).await?;
+
+    if response.status == 304 {
+        let mut entry = cached_entry
+            .ok_or_else(|| eyre!("{full_url} responded with 304 to an unconditional request"))?;
+        tracing::debug!("{project_name} has not changed since it was cached");
+        entry.touch();
+        return entry.save(cache_path, project_name);
+    }
 
-    let data = response.bytes().await?;
+    let mut file = File::create(&inv_path)?;
+    file.write_all(&response.body)?;
+    CacheEntry::new(full_url.to_string(), response.etag, response.last_modified)
+        .save(cache_path, project_name)
+}
 
-    let mut file = File::create(cache_path)?;
+/// Downloads the inventories of all externals at the same time and reports
+/// how that went for each of them, sorted by key.
+async fn fetch_all<C: HttpClient>(
+    client: Arc<C>,
+    externals: &HashMap<String, ExternalIndex>,
+    cache_path: &Path,
+    options: &FetchOptions,
+) -> Result<Vec<(String, Result<()>)>> {
+    let mut downloads = JoinSet::new();
+    for (key, external_index) in externals {
+        if external_index.inventory.is_some() {
+            tracing::debug!("not fetching {key}, its inventory is read from disk");
+            continue;
+        }
+        tracing::debug!("fetching: {}", key);
+        let client = client.clone();
+        let url = external_index.url.clone();
+        let key = key.clone();
+        let cache_path = PathBuf::from(cache_path);
+        let options = options.clone();
+        downloads.spawn(async move {
+            let result =
+                cache_remote_objects_inv(client.as_ref(), &url, &key, &cache_path, &options).await;
+            (key, result)
+        });
+    }
 
-    file.write_all(&data)?;
-    Ok(())
+    let mut results = vec![];
+    while let Some(joined) = downloads.join_next().await {
+        results.push(joined?);
+    }
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(results)
 }
 
-pub async fn fetch_objects_inv_blocking(url: Url) -> Result<Response> {
-    Ok(reqwest::get(url).await?)
+fn report_failures(failures: Vec<(String, color_eyre::Report)>) -> Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let report = failures
+        .iter()
+        .map(|(key, e)| format!("  - {key}: {e:#}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(eyre!(
+        "Failed to download {} inventories:\n{}",
+        failures.len(),
+        report
+    ))
 }
 
-pub async fn fill_cache(externals: &HashMap<String, ExternalIndex>) -> Result<()> {
-    for (key, external_index) in externals {
-        tracing::debug!("fetching: {}", key);
-        cache_remote_objects_inv(&external_index.url, key.to_string(), None, false).await?;
+/// Makes sure the inventories of all externals are in the cache. If an
+/// inventory can't be downloaded but an older one is cached, that one is used.
+pub async fn fill_cache<C: HttpClient>(
+    client: Arc<C>,
+    externals: &HashMap<String, ExternalIndex>,
+    cache_path: &Path,
+    options: &FetchOptions,
+) -> Result<()> {
+    let mut failures = vec![];
+    for (key, result) in fetch_all(client, externals, cache_path, options).await? {
+        let Err(e) = result else {
+            continue;
+        };
+        if inventory_path(cache_path, &key).exists() {
+            tracing::warn!("could not update the inventory of {key}, using the cached one: {e:#}");
+        } else {
+            failures.push((key, e));
+        }
     }
-    Ok(())
+    report_failures(failures)
+}
+
+/// Downloads the inventories of the given externals again, or of all of them
+/// if no keys are given. Returns the keys of the inventories that were refreshed.
+pub async fn refresh_cache<C: HttpClient>(
+    client: Arc<C>,
+    externals: &HashMap<String, ExternalIndex>,
+    keys: &[String],
+    cache_path: &Path,
+    options: &FetchOptions,
+) -> Result<Vec<String>> {
+    let selected = externals
+        .iter()
+        .filter(|(key, _)| keys.is_empty() || keys.contains(key))
+        .map(|(key, external_index)| (key.clone(), external_index.clone()))
+        .collect();
+    let options = FetchOptions {
+        force: true,
+        ..options.clone()
+    };
+
+    let mut refreshed = vec![];
+    let mut failures = vec![];
+    for (key, result) in fetch_all(client, &selected, cache_path, &options).await? {
+        match result {
+            Ok(()) => refreshed.push(key),
+            Err(e) => failures.push((key, e)),
+        }
+    }
+    report_failures(failures)?;
+    Ok(refreshed)
 }
 
 #[cfg(test)]
 mod test {
 
-    use std::fs::{File, create_dir_all, exists};
+    use std::collections::HashMap;
+    use std::fs::{File, exists, read};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use assert_fs::TempDir;
-    use color_eyre::Result;
-    use walkdir::WalkDir;
+    use color_eyre::{Result, eyre::eyre};
+    use pretty_assertions::assert_eq;
+    use tokio::sync::Barrier;
+    use url::Url;
+
+    use super::{
+        FetchOptions, HttpClient, HttpResponse, cache_remote_objects_inv, fill_cache, refresh_cache,
+    };
+    use crate::config::ExternalIndex;
+    use crate::indexing::external::cache::{CacheEntry, init_cache, inventory_path};
+
+    #[derive(Clone)]
+    enum Stub {
+        Respond(HttpResponse),
+        Unreachable,
+        Hang,
+    }
+
+    fn ok(body: &[u8]) -> Stub {
+        Stub::Respond(HttpResponse {
+            status: 200,
+            etag: Some("\"v1\"".to_string()),
+            body: body.to_vec(),
+            ..Default::default()
+        })
+    }
 
-    use crate::indexing::external::fetch::cache_remote_objects_inv;
+    fn status(status: u16) -> Stub {
+        Stub::Respond(HttpResponse {
+            status,
+            ..Default::default()
+        })
+    }
+
+    /// Answers with the stubs of the url in order, repeating the last one
+    #[derive(Default)]
+    struct StubClient {
+        stubs: Mutex<HashMap<String, Vec<Stub>>>,
+        /// the url and etag of every request
+        requests: Mutex<Vec<(String, Option<String>)>>,
+        /// makes every request wait for the others if set
+        barrier: Option<Barrier>,
+    }
+
+    impl StubClient {
+        fn with(stubs: &[(&str, Vec<Stub>)]) -> Self {
+            Self {
+                stubs: Mutex::new(
+                    stubs
+                        .iter()
+                        .map(|(url, s)| (url.to_string(), s.clone()))
+                        .collect(),
+                ),
+                ..Default::default()
+            }
+        }
+
+        fn requests(&self) -> Vec<(String, Option<String>)> {
+            self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+        }
+    }
+
+    impl HttpClient for StubClient {
+        async fn get(&self, url: &Url, cached: Option<&CacheEntry>) -> Result<HttpResponse> {
+            let stub = {
+                let mut stubs = self.stubs.lock().map_err(|e| eyre!("{e}"))?;
+                self.requests
+                    .lock()
+                    .map_err(|e| eyre!("{e}"))?
+                    .push((url.to_string(), cached.and_then(|c| c.etag.clone())));
+                let url_stubs = stubs
+                    .get_mut(url.as_str())
+                    .ok_or_else(|| eyre!("no stub for {url}"))?;
+                if url_stubs.len() > 1 {
+                    url_stubs.remove(0)
+                } else {
+                    url_stubs[0].clone()
+                }
+            };
+            if let Some(barrier) = &self.barrier {
+                barrier.wait().await;
+            }
+            match stub {
+                Stub::Respond(response) => Ok(response),
+                Stub::Unreachable => Err(eyre!("connection refused")),
+                Stub::Hang => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Err(eyre!("should have timed out"))
+                }
+            }
+        }
+    }
+
+    fn options() -> FetchOptions {
+        FetchOptions {
+            ttl: Duration::MAX,
+            timeout: Duration::from_millis(100),
+            retries: 3,
+            backoff: Duration::from_millis(1),
+            force: false,
+        }
+    }
 
     #[tokio::test]
     async fn fails_on_404_response() -> Result<()> {
-        let url = "https://github.com/pandas-dev/pandas/docs/stable";
+        let url = "https://github.com/pandas-dev/pandas/docs/stable/";
+        let client = StubClient::with(&[(
+            "https://github.com/pandas-dev/pandas/docs/stable/objects.inv",
+            vec![status(404)],
+        )]);
 
         let tmp_dir = TempDir::new()?;
-        let result = cache_remote_objects_inv(
-            url,
-            "asdf".to_string(),
-            Some(tmp_dir.path().to_path_buf()),
-            false,
-        )
-        .await;
+        let result =
+            cache_remote_objects_inv(&client, url, "asdf", tmp_dir.path(), &options()).await;
 
         assert!(result.is_err());
+        // asking again won't make it appear
+        assert_eq!(client.requests().len(), 1);
 
-        assert!(!exists(
-            tmp_dir
-                .path()
-                .join("sphinx")
-                .join("asdf")
-                .with_extension("inv")
-        )?);
+        assert!(!exists(inventory_path(tmp_dir.path(), "asdf"))?);
 
         Ok(())
     }
     #[tokio::test]
     async fn cache_clean_numpy_obj_inv() -> Result<()> {
-        let url = "https://numpy.org/doc/stable/objects.inv";
+        let url = "https://numpy.org/doc/stable/";
+        let inventory = read("tests/sphinx_objects/numpy.inv")?;
+        let client = StubClient::with(&[(
+            "https://numpy.org/doc/stable/objects.inv",
+            vec![ok(&inventory)],
+        )]);
 
         let tmp_dir = TempDir::new()?;
-        cache_remote_objects_inv(
-            url,
-            "numpy".to_string(),
-            Some(tmp_dir.path().to_path_buf()),
-            false,
-        )
-        .await?;
+        let cache_path = init_cache(Some(tmp_dir.path().to_path_buf()))?;
+        cache_remote_objects_inv(&client, url, "numpy", &cache_path, &options()).await?;
 
-        assert!(exists(
-            tmp_dir
-                .path()
-                .join("sphinx")
-                .join("numpy")
-                .with_extension("inv")
-        )?);
+        assert_eq!(read(inventory_path(&cache_path, "numpy"))?, inventory);
+        let entry = CacheEntry::load(&cache_path, "numpy");
+        assert_eq!(
+            entry.map(|e| (e.url, e.etag)),
+            Some((
+                "https://numpy.org/doc/stable/objects.inv".to_string(),
+                Some("\"v1\"".to_string())
+            ))
+        );
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_cache_force() -> Result<()> {
-        let url = "https://numpy.org/doc/stable/objects.inv";
+        let url = "https://numpy.org/doc/stable/";
+        let client = StubClient::with(&[(
+            "https://numpy.org/doc/stable/objects.inv",
+            vec![ok(&read("tests/sphinx_objects/numpy.inv")?)],
+        )]);
 
         let tmp_dir = TempDir::new()?;
-        let cache_path = tmp_dir.path().join("sphinx");
-        let obj_path = cache_path.join("numpy.inv");
-        create_dir_all(&cache_path)?;
+        let cache_path = init_cache(Some(tmp_dir.path().to_path_buf()))?;
+        let obj_path = inventory_path(&cache_path, "numpy");
 
         let _ = File::create(&obj_path)?;
 
-        cache_remote_objects_inv(
-            url,
-            "numpy".to_string(),
-            Some(tmp_dir.path().to_path_buf()),
-            false,
-        )
-        .await?;
+        cache_remote_objects_inv(&client, url, "numpy", &cache_path, &options()).await?;
 
         {
             let file_size = std::fs::metadata(&obj_path)?.len();
             assert!(file_size == 0);
+            assert!(client.requests().is_empty());
         }
 
-        cache_remote_objects_inv(
-            url,
-            "numpy".to_string(),
-            Some(tmp_dir.path().to_path_buf()),
-            true,
-        )
-        .await?;
+        let force = FetchOptions {
+            force: true,
+            ..options()
+        };
+        cache_remote_objects_inv(&client, url, "numpy", &cache_path, &force).await?;
 
-        for entry in WalkDir::new(tmp_dir.path()).follow_links(true) {
-            let entry = entry?;
-            println!("{}", entry.path().display());
-        }
         {
             let file_size = std::fs::metadata(&obj_path)?.len();
             assert!(file_size > 0);
@@ -159,4 +475,181 @@ async fn test_cache_force() -> Result<()> {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn retries_with_backoff() -> Result<()> {
+        let url = "https://example.com/docs/";
+        let stubs = vec![Stub::Unreachable, status(503), Stub::Hang, ok(b"inventory")];
+        let inv_url = "https://example.com/docs/objects.inv";
+
+        let tmp_dir = TempDir::new()?;
+        let cache_path = init_cache(Some(tmp_dir.path().to_path_buf()))?;
+
+        let client = StubClient::with(&[(inv_url, stubs.clone())]);
+        cache_remote_objects_inv(&client, url, "example", &cache_path, &options()).await?;
+        assert_eq!(client.requests().len(), 4);
+        assert_eq!(read(inventory_path(&cache_path, "example"))?, b"inventory");
+
+        let client = StubClient::with(&[(inv_url, stubs)]);
+        let too_few = FetchOptions {
+            retries: 2,
+            ..options()
+        };
+        let error = cache_remote_objects_inv(&client, url, "other", &cache_path, &too_few)
+            .await
+            .err()
+            .map(|e| format!("{e:#}"))
+            .unwrap_or_default();
+        assert!(error.starts_with("giving up after 3 attempt(s)"), "{error}");
+        assert!(error.contains("did not respond within"), "{error}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stale_inventories_are_revalidated() -> Result<()> {
+        let url = "https://example.com/docs/";
+        let client =
+            StubClient::with(&[("https://example.com/docs/objects.inv", vec![status(304)])]);
+
+        let tmp_dir = TempDir::new()?;
+        let cache_path = init_cache(Some(tmp_dir.path().to_path_buf()))?;
+        std::fs::write(inventory_path(&cache_path, "example"), "cached")?;
+        let mut entry = CacheEntry::new(
+            "https://example.com/docs/objects.inv".to_string(),
+            Some("\"v1\"".to_string()),
+            None,
+        );
+        entry.fetched_at -= 60 * 60;
+        entry.save(&cache_path, "example")?;
+
+        let ttl = FetchOptions {
+            ttl: Duration::from_secs(60),
+            ..options()
+        };
+        cache_remote_objects_inv(&client, url, "example", &cache_path, &ttl).await?;
+
+        assert_eq!(
+            client.requests(),
+            vec![(
+                "https://example.com/docs/objects.inv".to_string(),
+                Some("\"v1\"".to_string())
+            )]
+        );
+        assert_eq!(read(inventory_path(&cache_path, "example"))?, b"cached");
+        assert!(CacheEntry::load(&cache_path, "example").is_some_and(|e| e.is_fresh(ttl.ttl)));
+        Ok(())
+    }
+
+    fn externals(keys: &[&str]) -> HashMap<String, ExternalIndex> {
+        keys.iter()
+            .map(|key| {
+                (
+                    key.to_string(),
+                    ExternalIndex::new(None, format!("https://{key}.org/")),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn inventories_are_fetched_at_the_same_time() -> Result<()> {
+        let keys = ["numpy", "scipy", "pandas"];
+        let mut client = StubClient::with(&[
+            ("https://numpy.org/objects.inv", vec![ok(b"numpy")]),
+            ("https://scipy.org/objects.inv", vec![ok(b"scipy")]),
+            ("https://pandas.org/objects.inv", vec![ok(b"pandas")]),
+        ]);
+        // one at a time, the first request would wait for the others forever
+        client.barrier = Some(Barrier::new(keys.len()));
+
+        let tmp_dir = TempDir::new()?;
+        let cache_path = init_cache(Some(tmp_dir.path().to_path_buf()))?;
+        let no_retries = FetchOptions {
+            timeout: Duration::from_secs(5),
+            retries: 0,
+            ..options()
+        };
+        fill_cache(
+            Arc::new(client),
+            &externals(&keys),
+            &cache_path,
+            &no_retries,
+        )
+        .await?;
+        for key in keys {
+            assert_eq!(read(inventory_path(&cache_path, key))?, key.as_bytes());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failures_are_reported_per_external() -> Result<()> {
+        let client = Arc::new(StubClient::with(&[
+            ("https://numpy.org/objects.inv", vec![ok(b"numpy")]),
+            ("https://scipy.org/objects.inv", vec![status(404)]),
+            ("https://pandas.org/objects.inv", vec![status(500)]),
+            ("https://xarray.org/objects.inv", vec![status(410)]),
+        ]));
+
+        let tmp_dir = TempDir::new()?;
+        let cache_path = init_cache(Some(tmp_dir.path().to_path_buf()))?;
+        // a stale copy is better than nothing
+        std::fs::write(inventory_path(&cache_path, "xarray"), "stale")?;
+        let always_stale = FetchOptions {
+            ttl: Duration::ZERO,
+            ..options()
+        };
+
+        let error = fill_cache(
+            client.clone(),
+            &externals(&["numpy", "scipy", "pandas", "xarray"]),
+            &cache_path,
+            &always_stale,
+        )
+        .await
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_default();
+        assert!(
+            error.starts_with("Failed to download 2 inventories:\n  - pandas: "),
+            "{error}"
+        );
+        assert!(error.contains("\n  - scipy: https://scipy.org/objects.inv responded with 404"));
+        assert!(!error.contains("numpy") && !error.contains("xarray"));
+        assert_eq!(read(inventory_path(&cache_path, "numpy"))?, b"numpy");
+        assert_eq!(read(inventory_path(&cache_path, "xarray"))?, b"stale");
+
+        // refreshing names everything that didn't work
+        let error = refresh_cache(
+            client,
+            &externals(&["numpy", "xarray"]),
+            &[],
+            &cache_path,
+            &options(),
+        )
+        .await
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_default();
+        assert!(
+            error.starts_with("Failed to download 1 inventories:\n  - xarray: "),
+            "{error}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn local_inventories_are_not_fetched() -> Result<()> {
+        // the stub has no responses, so this fails if snakedown tries to download it
+        let client = Arc::new(StubClient::default());
+        let externals = HashMap::from([(
+            "vendored".to_string(),
+            ExternalIndex::new(None, "https://example.invalid/docs/".to_string())
+                .with_inventory(Some("tests/sphinx_objects/numpy.inv".to_string())),
+        )]);
+        let tmp_dir = TempDir::new()?;
+        fill_cache(client.clone(), &externals, tmp_dir.path(), &options()).await?;
+        assert!(client.requests().is_empty());
+        Ok(())
+    }
 }
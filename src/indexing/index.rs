@@ -1,21 +1,33 @@
 use crate::{
+    indexing::object_ref::{ObjectRef, ReferenceSyntax, default_reference_syntaxes},
     parsing::{
         ObjectDocumentation,
+        docstring::{DocstringStyle, parse_docstring},
         python::{
-            class::ClassDocumentation,
+            attribute::{AttributeDocumentation, is_private_attribute},
+            class::{ClassDocumentation, InheritedMembers},
             function::FunctionDocumentation,
+            import::ImportedName,
             jupyter::parse_notebook_file,
             module::{ModuleDocumentation, extract_module_documentation},
-            utils::parse_python_file,
+            object::{Member, MemberKind},
+            type_alias::{TypeAliasDocumentation, is_private_type_alias},
+            utils::parse_python_str,
+            variable::{VariableDocumentation, is_private_variable},
         },
+        sphinx::types::{ExternalSphinxRef, PyRole, SphinxPriority, SphinxType},
+    },
+    render::{
+        expr::{annotation_names, value_names},
+        formats::Renderer,
     },
-    render::formats::Renderer,
 };
 use color_eyre::{Report, Result, eyre::eyre};
 use edit_distance::edit_distance;
 use nbformat::v4::Cell;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, hash_map::Entry},
+    fs::read_to_string,
     path::{Path, PathBuf},
 };
 use tracing::warn;
@@ -30,17 +42,37 @@
 // then later we can just write everything to disk separately. That's a nice separation of concerns
 // see also: https://github.com/savente93/snakedown/issues/57
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RawIndex {
     pub pkg_name: String,
     pub internal_object_store: HashMap<String, ObjectDocumentation>,
     pub external_object_store: HashMap<String, Url>,
     pub notebook_store: HashMap<String, Vec<Cell>>,
+    /// Names a package makes available through imports in its `__init__.py`,
+    /// either listed in `__all__` or imported from inside the package.
+    pub reexports: HashMap<String, Vec<ImportedName>>,
+    /// Maps the definition path of re-exported objects to their public path
+    pub aliases: HashMap<String, String>,
+    /// Maps the public path of re-exported objects to their definition path
+    pub moved_from: HashMap<String, String>,
+    /// Modules that have a private module somewhere in their import path
+    pub private_modules: HashSet<String>,
+    /// The file every indexed module was read from
+    pub module_paths: HashMap<String, PathBuf>,
+    /// The names every indexed module binds through imports, with relative
+    /// imports already made absolute
+    pub imports: HashMap<String, Vec<ImportedName>>,
+    /// How references in docstrings can be written
+    pub reference_syntaxes: Vec<ReferenceSyntax>,
     pub skip_undoc: bool,
     pub skip_private: bool,
     pub pkg_root: PathBuf,
 }
 
+/// The key of an object, its docstring and the docstrings of its attributes
+/// (by position) with the references expanded
+type ExpandedDocstrings = (String, Option<String>, Vec<(usize, String)>);
+
 impl RawIndex {
     pub fn new(pkg_root: PathBuf, skip_undoc: bool, skip_private: bool) -> Result<Self> {
         let pkg_name = pkg_root
@@ -53,6 +85,13 @@ pub fn new(pkg_root: PathBuf, skip_undoc: bool, skip_private: bool) -> Result<Se
             internal_object_store: HashMap::new(),
             external_object_store: HashMap::new(),
             notebook_store: HashMap::new(),
+            reexports: HashMap::new(),
+            aliases: HashMap::new(),
+            moved_from: HashMap::new(),
+            private_modules: HashSet::new(),
+            module_paths: HashMap::new(),
+            imports: HashMap::new(),
+            reference_syntaxes: default_reference_syntaxes(),
             pkg_root,
             skip_undoc,
             skip_private,
@@ -60,24 +99,56 @@ pub fn new(pkg_root: PathBuf, skip_undoc: bool, skip_private: bool) -> Result<Se
     }
 
     pub fn index_file(&mut self, path: PathBuf) -> Result<()> {
+        let parsed = self.parse_file(&path);
+        self.insert_file(path, parsed)
+    }
+
+    /// Extracts the documentation of a file without adding it to the index, so
+    /// several files can be parsed at the same time. See [`RawIndex::insert_file`].
+    pub fn parse_file(&self, path: &Path) -> Result<ModuleDocumentation> {
         tracing::info!("Indexing {}", &path.display());
+        let source = read_to_string(path)?;
+        let contents = parse_python_str(&source)?;
+        let mut mod_docs =
+            extract_module_documentation(&contents, self.skip_private, self.skip_undoc);
+        mod_docs.locate(&source);
+        Ok(mod_docs)
+    }
 
-        let parsed = parse_python_file(&path);
+    /// Adds the documentation of a file parsed with [`RawIndex::parse_file`] to the index
+    pub fn insert_file(
+        &mut self,
+        path: PathBuf,
+        parsed: Result<ModuleDocumentation>,
+    ) -> Result<()> {
+        let (module_import_path, is_package) = self.module_import_path(&path)?;
 
-        let rel_module_file_path = path.clone().strip_prefix(&self.pkg_root)?.to_path_buf();
-        let module_import_path: String = {
-            let tmp_module_path =
-                get_from_import_path(self.pkg_name.clone(), &rel_module_file_path)?;
-            tmp_module_path
-                .strip_suffix(".__init__")
-                .unwrap_or(&tmp_module_path)
-                .to_string()
-        };
+        self.module_paths
+            .insert(module_import_path.clone(), path.clone());
+
+        if module_import_path
+            .split('.')
+            .any(|c| c.starts_with('_') && !c.starts_with("__"))
+        {
+            self.private_modules.insert(module_import_path.clone());
+        }
 
         match parsed {
-            Ok(contents) => {
-                let mod_docs =
-                    extract_module_documentation(&contents, self.skip_private, self.skip_undoc);
+            Ok(mod_docs) => {
+                self.imports.insert(
+                    module_import_path.clone(),
+                    mod_docs
+                        .imports
+                        .iter()
+                        .filter_map(|import| import.resolve(&module_import_path, is_package))
+                        .collect(),
+                );
+                if is_package {
+                    let reexports = self.extract_reexports(&mod_docs, &module_import_path);
+                    if !reexports.is_empty() {
+                        self.reexports.insert(module_import_path.clone(), reexports);
+                    }
+                }
                 if should_include_module(&mod_docs, self.skip_undoc) {
                     self.internal_object_store.insert(
                         module_import_path.clone(),
@@ -98,6 +169,19 @@ pub fn index_file(&mut self, path: PathBuf) -> Result<()> {
                             index_functions(self, &function_docs, module_import_path.clone())?;
                         }
                     }
+
+                    for var_docs in &mod_docs.variables {
+                        if should_include_variable(var_docs, self.skip_private, self.skip_undoc) {
+                            index_variable(self, var_docs, module_import_path.clone());
+                        }
+                    }
+
+                    for alias_docs in &mod_docs.type_aliases {
+                        if should_include_type_alias(alias_docs, self.skip_private, self.skip_undoc)
+                        {
+                            index_type_alias(self, alias_docs, module_import_path.clone());
+                        }
+                    }
                 }
 
                 Ok(())
@@ -113,14 +197,40 @@ pub fn index_file(&mut self, path: PathBuf) -> Result<()> {
         }
     }
 
+    /// The import path of the module in a file, and whether it is a package
+    fn module_import_path(&self, path: &Path) -> Result<(String, bool)> {
+        let rel_module_file_path = path.strip_prefix(&self.pkg_root)?.to_path_buf();
+        let tmp_module_path = get_from_import_path(self.pkg_name.clone(), &rel_module_file_path)?;
+        let is_package = tmp_module_path.ends_with(".__init__");
+        let module_import_path = tmp_module_path
+            .strip_suffix(".__init__")
+            .unwrap_or(&tmp_module_path)
+            .to_string();
+        Ok((module_import_path, is_package))
+    }
+
+    /// Makes a module known without parsing it, so the names used in the modules
+    /// that are parsed can be traced back to the file they are defined in.
+    /// See [`RawIndex::dependencies`].
+    pub fn register_module(&mut self, path: PathBuf) -> Result<()> {
+        let (module_import_path, _) = self.module_import_path(&path)?;
+        self.module_paths.insert(module_import_path, path);
+        Ok(())
+    }
+
     pub fn index_notebook(&mut self, path: &Path) -> Result<()> {
+        let notebook_contents = parse_notebook_file(path)?;
+        self.insert_notebook(path, notebook_contents)
+    }
+
+    /// Adds the cells of a notebook parsed with [`parse_notebook_file`] to the index
+    pub fn insert_notebook(&mut self, path: &Path, notebook_contents: Vec<Cell>) -> Result<()> {
         let notebook_name = path
             .file_stem()
             .ok_or(eyre!("Could not deternime file stem"))?
             .to_str()
             .ok_or(eyre!("Could not convert file stem to string"))?
             .to_string();
-        let notebook_contents = parse_notebook_file(path)?;
 
         if self
             .notebook_store
@@ -132,17 +242,52 @@ pub fn index_notebook(&mut self, path: &Path) -> Result<()> {
         Ok(())
     }
 
+    /// Removes everything that was indexed from a file, so it can be indexed again
+    /// after it changed. Objects of sub modules are left alone.
+    pub fn remove_file(&mut self, path: &Path) {
+        let Some(module) = self
+            .module_paths
+            .iter()
+            .find(|(_, p)| p.as_path() == path)
+            .map(|(m, _)| m.clone())
+        else {
+            return;
+        };
+        tracing::debug!("Removing {} from the index", &module);
+
+        let owned: Vec<String> = self
+            .internal_object_store
+            .keys()
+            .filter(|k| self.owning_module(k) == Some(&module))
+            .cloned()
+            .collect();
+        for key in owned {
+            self.internal_object_store.remove(&key);
+        }
+
+        self.module_paths.remove(&module);
+        self.reexports.remove(&module);
+        self.imports.remove(&module);
+        self.private_modules.remove(&module);
+    }
+
+    pub fn remove_notebook(&mut self, path: &Path) {
+        if let Some(notebook_name) = path.file_stem().and_then(|s| s.to_str()) {
+            self.notebook_store.remove(notebook_name);
+        }
+    }
+
     pub fn validate_references(&self) -> Result<(), Vec<Report>> {
         let mut errors: Vec<_> = Vec::new();
         for (key, obj) in self.internal_object_store.iter() {
-            if let Some((_, used_references)) = obj.extract_used_references() {
+            if let Some((_, used_references)) =
+                obj.extract_used_references(&self.reference_syntaxes)
+            {
                 for used_ref in used_references {
-                    if !self
-                        .internal_object_store
-                        .contains_key(&used_ref.fully_qualified_name)
-                        && !self
-                            .external_object_store
-                            .contains_key(&used_ref.fully_qualified_name)
+                    if used_ref.link
+                        && self
+                            .resolve_reference(key, &used_ref.fully_qualified_name)
+                            .is_none()
                     {
                         let suggestion =
                             self.suggest_reference(&used_ref.fully_qualified_name, 5, 5);
@@ -207,36 +352,818 @@ fn suggest_reference(
         }
     }
 
+    /// If a package defines `__all__` every name in it that is bound by an import
+    /// is re-exported, otherwise all public names imported from inside the package are.
+    fn extract_reexports(
+        &self,
+        mod_docs: &ModuleDocumentation,
+        module_import_path: &str,
+    ) -> Vec<ImportedName> {
+        mod_docs
+            .imports
+            .iter()
+            .filter(|import| match &mod_docs.exports {
+                Some(exports) => exports.contains(&import.name),
+                None => !import.name.starts_with('_'),
+            })
+            .filter_map(|import| import.resolve(module_import_path, true))
+            .filter(|import| {
+                import.target == self.pkg_name
+                    || import.target.starts_with(&format!("{}.", self.pkg_name))
+            })
+            .collect()
+    }
+
+    /// Moves objects that are re-exported by a package to their public path,
+    /// and remembers their definition path as an alias. Nested packages are
+    /// handled first, so re-exports of re-exports end up at the outermost package.
+    pub fn apply_reexports(&mut self) {
+        let mut packages: Vec<String> = self.reexports.keys().cloned().collect();
+        packages.sort_by(|a, b| {
+            b.matches('.')
+                .count()
+                .cmp(&a.matches('.').count())
+                .then(a.cmp(b))
+        });
+
+        for package in packages {
+            let reexports = self.reexports.get(&package).cloned().unwrap_or_default();
+            for import in reexports {
+                let public = format!("{}.{}", package, import.name);
+                let Some(source) = self.canonical_name(&import.target) else {
+                    tracing::debug!("{} re-exports unknown object {}", package, import.target);
+                    continue;
+                };
+                if source == public {
+                    continue;
+                }
+                if self.internal_object_store.contains_key(&public) {
+                    warn!("{public} is already defined, not re-exporting {source} there");
+                    continue;
+                }
+                self.move_object(&source, &public);
+            }
+        }
+    }
+
+    fn move_object(&mut self, source: &str, destination: &str) {
+        tracing::debug!("Moving {source} to its public path {destination}");
+        let source_prefix = format!("{source}.");
+        let moved: Vec<String> = self
+            .internal_object_store
+            .keys()
+            .filter(|k| *k == source || k.starts_with(&source_prefix))
+            .cloned()
+            .collect();
+
+        for old_key in moved {
+            let new_key = format!("{}{}", destination, &old_key[source.len()..]);
+            if let Some(object) = self.internal_object_store.remove(&old_key) {
+                self.internal_object_store.insert(new_key.clone(), object);
+            }
+            let definition = self.moved_from.remove(&old_key).unwrap_or(old_key.clone());
+            self.moved_from.insert(new_key.clone(), definition);
+            self.aliases.insert(old_key, new_key);
+        }
+
+        // objects that were re-exported before may have moved again
+        for target in self.aliases.values_mut() {
+            if target == source || target.starts_with(&source_prefix) {
+                *target = format!("{}{}", destination, &target[source.len()..]);
+            }
+        }
+    }
+
+    /// The indexed module an object was defined in
+    fn owning_module(&self, key: &str) -> Option<&String> {
+        self.module_paths
+            .keys()
+            .filter(|m| key == m.as_str() || key.starts_with(&format!("{m}.")))
+            .max_by_key(|m| m.len())
+    }
+
+    /// The file an object was read from. This also works for objects that were
+    /// moved to their public path.
+    pub fn source_path(&self, key: &str) -> Option<&Path> {
+        let definition = self.moved_from.get(key).map(String::as_str).unwrap_or(key);
+        self.owning_module(definition)
+            .and_then(|m| self.module_paths.get(m))
+            .map(|p| p.as_path())
+    }
+
+    /// The key an object is stored under, following aliases of re-exported objects
+    pub fn canonical_name(&self, name: &str) -> Option<String> {
+        if self.internal_object_store.contains_key(name) {
+            Some(name.to_string())
+        } else {
+            self.aliases.get(name).cloned()
+        }
+    }
+
+    /// Removes everything defined in private modules, except what was re-exported
+    /// to a public path. Private modules still have to be indexed so we can find
+    /// the objects that are re-exported from them.
+    pub fn remove_private_modules(&mut self) {
+        let private_modules = &self.private_modules;
+        let is_private = |key: &str| {
+            private_modules
+                .iter()
+                .any(|m| key == m || key.starts_with(&format!("{m}.")))
+        };
+        self.internal_object_store.retain(|key, _| {
+            let keep = !is_private(key);
+            if !keep {
+                tracing::debug!("skipping {key} because it is defined in a private module");
+            }
+            keep
+        });
+        // aliases of private paths are kept, so references to them still resolve
+        let store = &self.internal_object_store;
+        self.aliases.retain(|_, target| store.contains_key(target));
+    }
+
+    /// Resolves the base classes of every indexed class, first against the
+    /// package itself and then against the external indexes. Bases we can't
+    /// find are left as is and rendered without a link.
+    pub fn resolve_class_bases(&mut self) {
+        let mut resolved: Vec<(String, Vec<Option<String>>)> = vec![];
+        for (key, object) in self.internal_object_store.iter() {
+            if let ObjectDocumentation::Class(class_docs) = object {
+                let module = self.defining_module(key);
+                let targets = class_docs
+                    .bases
+                    .iter()
+                    .map(|base| {
+                        base.dotted_name()
+                            .and_then(|name| self.resolve_class_name(&module, &name))
+                    })
+                    .collect();
+                resolved.push((key.clone(), targets));
+            }
+        }
+
+        for (key, targets) in resolved {
+            if let Some(ObjectDocumentation::Class(class_docs)) =
+                self.internal_object_store.get_mut(&key)
+            {
+                for (base, target) in class_docs.bases.iter_mut().zip(targets) {
+                    if target.is_none() {
+                        tracing::debug!(
+                            "could not resolve base class {} of {}",
+                            base.dotted_name().unwrap_or_default(),
+                            key
+                        );
+                    }
+                    base.target = target;
+                }
+            }
+        }
+    }
+
+    /// Bases have to be classes, so names that resolve to anything else
+    /// we indexed are ignored
+    fn resolve_class_name(&self, module: &str, name: &str) -> Option<String> {
+        self.resolve_name(module, name).filter(|target| {
+            self.is_internal_class(target) || !self.internal_object_store.contains_key(target)
+        })
+    }
+
+    /// The module an object was defined in, which for re-exported objects is
+    /// not the module they are documented under
+    fn defining_module(&self, key: &str) -> String {
+        let definition = self.moved_from.get(key).map(String::as_str).unwrap_or(key);
+        self.owning_module(definition)
+            .cloned()
+            .or_else(|| key.rsplit_once('.').map(|(m, _)| m.to_string()))
+            .unwrap_or_default()
+    }
+
+    /// Resolves a dotted name as it is written in `module` to the fully qualified
+    /// name of an indexed object, or the url of an external one. Names bound by
+    /// the imports of the module are followed first, then names defined in the
+    /// module itself and finally the name is taken to be fully qualified already.
+    pub fn resolve_name(&self, module: &str, name: &str) -> Option<String> {
+        let (head, rest) = match name.split_once('.') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (name, None),
+        };
+        let imported = self
+            .imports
+            .get(module)
+            .and_then(|imports| imports.iter().find(|i| i.name == head));
+        if let Some(import) = imported {
+            // the import shadows anything else the name could refer to
+            return match rest {
+                Some(rest) => self.lookup_name(&format!("{}.{}", import.target, rest)),
+                None => self.lookup_name(&import.target),
+            };
+        }
+
+        self.canonical_name(&format!("{module}.{name}"))
+            .or_else(|| self.lookup_name(name))
+    }
+
+    /// Resolves a reference used in the docstring of `key` the way Python would
+    /// resolve the name in the module the object is defined in, see
+    /// [`RawIndex::resolve_name`]. References starting with a dot are relative
+    /// to that module, like relative imports.
+    pub fn resolve_reference(&self, key: &str, reference: &str) -> Option<String> {
+        // python names can't contain a colon, so this is something like
+        // `c:PyObject_GetAttr` from an external index
+        if reference.contains(':') {
+            return self.lookup_name(reference);
+        }
+        let module = self.defining_module(key);
+        if !reference.starts_with('.') {
+            return self.resolve_name(&module, reference);
+        }
+
+        self.absolute_reference(&module, reference)
+            .and_then(|absolute| self.lookup_name(&absolute))
+    }
+
+    /// Makes a reference starting with a dot absolute, like a relative import in `module`
+    fn absolute_reference(&self, module: &str, reference: &str) -> Option<String> {
+        let is_package = self
+            .module_paths
+            .get(module)
+            .is_some_and(|p| p.file_stem().is_some_and(|s| s == "__init__"));
+        let relative = ImportedName {
+            name: reference.to_string(),
+            target: reference.to_string(),
+        };
+        relative
+            .resolve(module, is_package)
+            .map(|absolute| absolute.target)
+    }
+
+    /// Looks up a fully qualified name, first in this package and then in the
+    /// external indexes
+    fn lookup_name(&self, full_name: &str) -> Option<String> {
+        self.canonical_name(full_name).or_else(|| {
+            self.external_object_store
+                .get(full_name)
+                .map(|u| u.as_str().to_string())
+        })
+    }
+
+    /// Resolves the names used in the annotations and defaults of functions and
+    /// class attributes, so they can be linked when rendered. Names we can't
+    /// find are rendered without a link.
+    pub fn resolve_annotations(&mut self) {
+        let mut resolved: Vec<(String, HashMap<String, String>)> = vec![];
+        for (key, object) in self.internal_object_store.iter() {
+            let names = match object {
+                ObjectDocumentation::Function(fn_docs) => function_annotation_names(fn_docs),
+                ObjectDocumentation::Class(class_docs) => class_annotation_names(class_docs),
+                _ => continue,
+            };
+            let module = self.defining_module(key);
+            let targets = names
+                .into_iter()
+                .filter_map(|name| {
+                    let target = self.resolve_name(&module, &name);
+                    if target.is_none() {
+                        tracing::debug!("could not resolve {name} used in {key}");
+                    }
+                    target.map(|t| (name, t))
+                })
+                .collect();
+            resolved.push((key.clone(), targets));
+        }
+
+        for (key, targets) in resolved {
+            match self.internal_object_store.get_mut(&key) {
+                Some(ObjectDocumentation::Function(fn_docs)) => {
+                    fn_docs.annotation_targets = targets;
+                }
+                Some(ObjectDocumentation::Class(class_docs)) => {
+                    class_docs.annotation_targets = targets;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn is_internal_class(&self, key: &str) -> bool {
+        matches!(
+            self.internal_object_store.get(key),
+            Some(ObjectDocumentation::Class(_))
+        )
+    }
+
+    /// The resolved bases of a class that are part of this package
+    pub fn internal_bases(&self, key: &str) -> Vec<String> {
+        match self.internal_object_store.get(key) {
+            Some(ObjectDocumentation::Class(class_docs)) => class_docs
+                .bases
+                .iter()
+                .filter_map(|b| b.target.clone())
+                .filter(|t| self.is_internal_class(t))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The C3 linearization of a class, restricted to the classes in this package.
+    /// Returns `None` if no consistent order exists, e.g. because of a cycle.
+    pub fn method_resolution_order(&self, key: &str) -> Option<Vec<String>> {
+        self.linearize(key, &mut vec![])
+    }
+
+    fn linearize(&self, key: &str, visiting: &mut Vec<String>) -> Option<Vec<String>> {
+        if visiting.iter().any(|v| v == key) {
+            return None;
+        }
+        visiting.push(key.to_string());
+
+        let bases = self.internal_bases(key);
+        let mut sequences = vec![];
+        for base in &bases {
+            sequences.push(self.linearize(base, visiting)?);
+        }
+        sequences.push(bases);
+        visiting.pop();
+
+        let mut mro = vec![key.to_string()];
+        loop {
+            sequences.retain(|s| !s.is_empty());
+            if sequences.is_empty() {
+                return Some(mro);
+            }
+            let head = sequences
+                .iter()
+                .map(|s| &s[0])
+                .find(|candidate| !sequences.iter().any(|s| s[1..].contains(candidate)))?
+                .clone();
+            for sequence in sequences.iter_mut() {
+                if sequence[0] == head {
+                    sequence.remove(0);
+                }
+            }
+            mro.push(head);
+        }
+    }
+
+    /// Records, for every class, which methods it inherits from the
+    /// classes in this package. Requires [`RawIndex::resolve_class_bases`] to have run.
+    pub fn collect_inherited_members(&mut self) {
+        let mut inherited: Vec<(String, Vec<InheritedMembers>)> = vec![];
+        for (key, object) in self.internal_object_store.iter() {
+            let ObjectDocumentation::Class(class_docs) = object else {
+                continue;
+            };
+            let Some(mro) = self.method_resolution_order(key) else {
+                warn!("could not determine the method resolution order of {key}");
+                continue;
+            };
+
+            let mut seen: Vec<String> = class_docs
+                .methods
+                .iter()
+                .map(|m| m.name.to_string())
+                .collect();
+            let mut members = vec![];
+            for base in mro.iter().skip(1) {
+                let Some(ObjectDocumentation::Class(base_docs)) =
+                    self.internal_object_store.get(base)
+                else {
+                    continue;
+                };
+                let methods: Vec<String> = base_docs
+                    .methods
+                    .iter()
+                    .filter(|m| should_include_function(m, self.skip_private, self.skip_undoc))
+                    .map(|m| m.name.to_string())
+                    .filter(|name| !seen.contains(name))
+                    .collect();
+                seen.extend(methods.iter().cloned());
+                if !methods.is_empty() {
+                    members.push(InheritedMembers {
+                        base: base.clone(),
+                        methods,
+                    });
+                }
+            }
+            inherited.push((key.clone(), members));
+        }
+
+        for (key, members) in inherited {
+            if let Some(ObjectDocumentation::Class(class_docs)) =
+                self.internal_object_store.get_mut(&key)
+            {
+                class_docs.inherited_members = members;
+            }
+        }
+    }
+
+    /// Records, for every module and class, the objects directly inside it that
+    /// have a page of their own. This should run after everything that moves or
+    /// removes objects, so the pages only list what they can link to.
+    pub fn collect_members(&mut self, docstring_style: DocstringStyle) {
+        let mut members: HashMap<String, Vec<Member>> = HashMap::new();
+        for (key, object) in &self.internal_object_store {
+            let Some((parent_key, name)) = key.rsplit_once('.') else {
+                continue;
+            };
+            let Some(parent) = self.internal_object_store.get(parent_key) else {
+                continue;
+            };
+            let kind = match (parent, object) {
+                (ObjectDocumentation::Class(_), ObjectDocumentation::Function(_)) => {
+                    MemberKind::Method
+                }
+                (_, ObjectDocumentation::Module(_)) => MemberKind::Module,
+                (_, ObjectDocumentation::Class(_)) => MemberKind::Class,
+                (_, ObjectDocumentation::Function(_)) => MemberKind::Function,
+                (_, ObjectDocumentation::Attribute(_)) => MemberKind::Attribute,
+                (_, ObjectDocumentation::Variable(var_docs)) if var_docs.is_constant() => {
+                    MemberKind::Constant
+                }
+                (_, ObjectDocumentation::Variable(_)) => MemberKind::Variable,
+                (_, ObjectDocumentation::TypeAlias(_)) => MemberKind::TypeAlias,
+            };
+            members
+                .entry(parent_key.to_string())
+                .or_default()
+                .push(Member {
+                    name: name.to_string(),
+                    fully_qualified_name: key.clone(),
+                    kind,
+                    summary: object
+                        .docstring()
+                        .and_then(|d| parse_docstring(d.trim(), docstring_style).summary),
+                });
+        }
+
+        for object in self.internal_object_store.values_mut() {
+            match object {
+                ObjectDocumentation::Module(mod_docs) => mod_docs.members.clear(),
+                ObjectDocumentation::Class(class_docs) => class_docs.members.clear(),
+                _ => {}
+            }
+        }
+        for (key, mut object_members) in members {
+            object_members.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+            match self.internal_object_store.get_mut(&key) {
+                Some(ObjectDocumentation::Module(mod_docs)) => mod_docs.members = object_members,
+                Some(ObjectDocumentation::Class(class_docs)) => {
+                    class_docs.members = object_members;
+                }
+                _ => {}
+            }
+        }
+    }
+
     //TODO: This is not an efficient way to do this, but for the test cases it works,
     //at some point we should find a more high performance solution.
     // see: https://github.com/savente93/snakedown/issues/55
     pub fn pre_process<R: Renderer>(&mut self, render: R, site_rel_api_path: &Path) -> Result<()> {
-        for (_key, object) in self.internal_object_store.iter_mut() {
-            if let Some((mut object_docstring, used_references)) = object.extract_used_references()
-            {
-                for used_ref in used_references {
-                    let display_text = used_ref
-                        .clone()
-                        .display_text
-                        .or_else(|| Some(used_ref.fully_qualified_name.clone()));
-
-                    let target = self
-                        .external_object_store
-                        .get(&used_ref.fully_qualified_name)
-                        .map(|u| u.as_str().to_string())
-                        .unwrap_or_else(|| used_ref.fully_qualified_name.clone());
-
-                    let expanded_ref =
-                        render.render_reference(display_text, site_rel_api_path, target)?;
-                    object_docstring =
-                        object_docstring.replace(&used_ref.original(), &expanded_ref);
+        let mut expanded_docstrings: Vec<ExpandedDocstrings> = vec![];
+        for (key, object) in self.internal_object_store.iter() {
+            let expanded = match object.extract_used_references(&self.reference_syntaxes) {
+                Some((object_docstring, used_references)) => Some(self.expand_references(
+                    key,
+                    &render,
+                    site_rel_api_path,
+                    object_docstring,
+                    used_references,
+                )?),
+                None => None,
+            };
+            // attributes are also listed on the page of their class,
+            // so their docstrings need to be expanded there as well
+            let mut expanded_attributes = vec![];
+            if let ObjectDocumentation::Class(class_docs) = object {
+                for (i, attr_docs) in class_docs.attributes.iter().enumerate() {
+                    if let Some((attr_docstring, used_references)) =
+                        attr_docs.extract_used_references(&self.reference_syntaxes)
+                    {
+                        expanded_attributes.push((
+                            i,
+                            self.expand_references(
+                                key,
+                                &render,
+                                site_rel_api_path,
+                                attr_docstring,
+                                used_references,
+                            )?,
+                        ));
+                    }
+                }
+            }
+            expanded_docstrings.push((key.clone(), expanded, expanded_attributes));
+        }
+
+        for (key, expanded, expanded_attributes) in expanded_docstrings {
+            let Some(object) = self.internal_object_store.get_mut(&key) else {
+                continue;
+            };
+            if let Some(expanded) = expanded {
+                object.replace_docstring(Some(expanded));
+            }
+            if let ObjectDocumentation::Class(class_docs) = object {
+                for (i, attr_docstring) in expanded_attributes {
+                    class_docs.attributes[i].docstring = Some(attr_docstring);
                 }
-                object.replace_docstring(Some(object_docstring));
             }
         }
 
         Ok(())
     }
+
+    fn expand_references<R: Renderer>(
+        &self,
+        key: &str,
+        render: &R,
+        site_rel_api_path: &Path,
+        mut docstring: String,
+        used_references: Vec<ObjectRef>,
+    ) -> Result<String> {
+        for used_ref in used_references {
+            let display_text = used_ref
+                .clone()
+                .display_text
+                .or_else(|| Some(used_ref.fully_qualified_name.clone()));
+
+            // references that could not be resolved have been reported already,
+            // so all that's left to do is to not render a broken link
+            let target = used_ref
+                .link
+                .then(|| self.resolve_reference(key, &used_ref.fully_qualified_name))
+                .flatten();
+            let expanded_ref = match target {
+                Some(target) => render.render_reference(display_text, site_rel_api_path, target)?,
+                None => display_text.unwrap_or_default(),
+            };
+            docstring = docstring.replace(&used_ref.original, &expanded_ref);
+        }
+        Ok(docstring)
+    }
+
+    /// The other modules the pages of every module can depend on: the modules it
+    /// imports from and the modules the names in its references, annotations
+    /// and base classes point into. This goes by the names as they are written,
+    /// so it has to run before anything is moved or resolved.
+    pub fn dependencies(&self) -> HashMap<String, BTreeSet<String>> {
+        let mut names: HashMap<&String, Vec<String>> = HashMap::new();
+        for (module, imports) in &self.imports {
+            names
+                .entry(module)
+                .or_default()
+                .extend(imports.iter().map(|import| import.target.clone()));
+        }
+        for (key, object) in &self.internal_object_store {
+            if let Some(module) = self.owning_module(key) {
+                let mentioned = self.mentioned_names(module, object);
+                names.entry(module).or_default().extend(mentioned);
+            }
+        }
+
+        names
+            .into_iter()
+            .map(|(module, names)| {
+                let dependencies = names
+                    .iter()
+                    .filter_map(|name| self.owning_module(name))
+                    .filter(|m| *m != module)
+                    .cloned()
+                    .collect();
+                (module.clone(), dependencies)
+            })
+            .collect()
+    }
+
+    /// The names used in the references, annotations and base classes of an object
+    fn mentioned_names(&self, module: &str, object: &ObjectDocumentation) -> Vec<String> {
+        let mut references: Vec<ObjectRef> = object
+            .extract_used_references(&self.reference_syntaxes)
+            .map(|(_, used_references)| used_references)
+            .unwrap_or_default();
+        let mut names = vec![];
+        match object {
+            ObjectDocumentation::Class(class_docs) => {
+                for attr_docs in &class_docs.attributes {
+                    references.extend(
+                        attr_docs
+                            .extract_used_references(&self.reference_syntaxes)
+                            .map(|(_, used_references)| used_references)
+                            .unwrap_or_default(),
+                    );
+                }
+                names.extend(class_docs.bases.iter().filter_map(|b| b.dotted_name()));
+                names.extend(class_annotation_names(class_docs));
+            }
+            ObjectDocumentation::Function(fn_docs) => {
+                names.extend(function_annotation_names(fn_docs));
+            }
+            _ => {}
+        }
+        for reference in references {
+            let name = reference.fully_qualified_name;
+            if name.starts_with('.') {
+                names.extend(self.absolute_reference(module, &name));
+            } else {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// What the pages of other modules can see of every module: the objects it
+    /// defines with their summaries, the bases of its classes and the names it
+    /// imports. Other modules only have to be rendered again when this changes,
+    /// see [`RawIndex::dependencies`].
+    pub fn interfaces(&self, docstring_style: DocstringStyle) -> HashMap<String, String> {
+        let mut objects: Vec<(&String, &ObjectDocumentation)> =
+            self.internal_object_store.iter().collect();
+        objects.sort_by_key(|(key, _)| *key);
+
+        let mut interfaces: HashMap<String, String> = HashMap::new();
+        for (key, object) in objects {
+            let Some(module) = self.owning_module(key) else {
+                continue;
+            };
+            let interface = interfaces.entry(module.clone()).or_default();
+            let kind = match object {
+                ObjectDocumentation::Module(_) => "module",
+                ObjectDocumentation::Class(_) => "class",
+                ObjectDocumentation::Function(_) => "function",
+                ObjectDocumentation::Attribute(_) => "attribute",
+                ObjectDocumentation::Variable(_) => "variable",
+                ObjectDocumentation::TypeAlias(_) => "type alias",
+            };
+            let summary = object
+                .docstring()
+                .and_then(|d| parse_docstring(d.trim(), docstring_style).summary);
+            interface.push_str(&format!("{key} {kind} {summary:?}\n"));
+            if let ObjectDocumentation::Class(class_docs) = object {
+                for base in &class_docs.bases {
+                    interface.push_str(&format!("  base {:?}\n", base.dotted_name()));
+                }
+            }
+        }
+        for (module, imports) in &self.imports {
+            let interface = interfaces.entry(module.clone()).or_default();
+            for import in imports {
+                interface.push_str(&format!("import {} {}\n", import.name, import.target));
+            }
+        }
+        for (module, reexports) in &self.reexports {
+            let interface = interfaces.entry(module.clone()).or_default();
+            for reexport in reexports {
+                interface.push_str(&format!("reexport {} {}\n", reexport.name, reexport.target));
+            }
+        }
+        interfaces
+    }
+
+    /// The modules directly inside a package
+    pub fn submodules<'a>(&'a self, module: &'a str) -> impl Iterator<Item = &'a String> {
+        self.module_paths.keys().filter(move |m| {
+            m.rsplit_once('.')
+                .is_some_and(|(parent, _)| parent == module)
+        })
+    }
+
+    /// Every class with its bases in this package, see [`RawIndex::internal_bases`]
+    pub fn class_bases(&self) -> BTreeMap<String, Vec<String>> {
+        self.internal_object_store
+            .iter()
+            .filter(|(_, object)| matches!(object, ObjectDocumentation::Class(_)))
+            .map(|(key, _)| (key.clone(), self.internal_bases(key)))
+            .collect()
+    }
+
+    /// Lists every documented object with the url of its page, in the form
+    /// sphinx uses for its `objects.inv`. Aliases of re-exported objects are
+    /// listed as well, pointing to the same page.
+    pub fn inventory<R: Renderer>(
+        &self,
+        render: &R,
+        site_rel_api_path: &Path,
+    ) -> Vec<ExternalSphinxRef> {
+        let mut inventory: Vec<ExternalSphinxRef> = self
+            .inventory_entries(render, site_rel_api_path)
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect();
+        inventory.sort_by(|a, b| a.name.cmp(&b.name));
+        inventory
+    }
+
+    /// The entries of [`RawIndex::inventory`] with the key of the object they point to
+    pub fn inventory_entries<R: Renderer>(
+        &self,
+        render: &R,
+        site_rel_api_path: &Path,
+    ) -> Vec<(&String, ExternalSphinxRef)> {
+        let names = self
+            .internal_object_store
+            .keys()
+            .map(|key| (key, key))
+            .chain(self.aliases.iter());
+        let mut inventory = vec![];
+        for (name, key) in names {
+            let Some(object) = self.internal_object_store.get(key) else {
+                continue;
+            };
+            let (role, priority) = match object {
+                ObjectDocumentation::Module(_) => (PyRole::Module, SphinxPriority::High),
+                ObjectDocumentation::Class(_) => (PyRole::Class, SphinxPriority::Standard),
+                ObjectDocumentation::Function(_) => {
+                    let is_method = key.rsplit_once('.').is_some_and(|(parent, _)| {
+                        matches!(
+                            self.internal_object_store.get(parent),
+                            Some(ObjectDocumentation::Class(_))
+                        )
+                    });
+                    let role = if is_method {
+                        PyRole::Method
+                    } else {
+                        PyRole::Function
+                    };
+                    (role, SphinxPriority::Standard)
+                }
+                // properties are documented as attributes, see `extract_properties`
+                ObjectDocumentation::Attribute(attr_docs) if attr_docs.property.is_some() => {
+                    (PyRole::Property, SphinxPriority::Standard)
+                }
+                ObjectDocumentation::Attribute(_) => (PyRole::Attribute, SphinxPriority::Standard),
+                ObjectDocumentation::Variable(_) | ObjectDocumentation::TypeAlias(_) => {
+                    (PyRole::Data, SphinxPriority::Standard)
+                }
+            };
+            inventory.push((
+                key,
+                ExternalSphinxRef {
+                    name: name.clone(),
+                    sphinx_type: SphinxType::Python(role),
+                    priority,
+                    location: render.page_uri(site_rel_api_path, key),
+                    dispname: name.clone(),
+                },
+            ));
+        }
+        inventory
+    }
+}
+
+fn function_annotation_names(fn_docs: &FunctionDocumentation) -> Vec<String> {
+    let args = &fn_docs.args;
+    let mut names = vec![];
+    for arg in args
+        .posonlyargs
+        .iter()
+        .chain(&args.args)
+        .chain(&args.kwonlyargs)
+    {
+        names.extend(
+            arg.def
+                .annotation
+                .as_deref()
+                .map(annotation_names)
+                .unwrap_or_default(),
+        );
+        names.extend(arg.default.as_deref().map(value_names).unwrap_or_default());
+    }
+    for arg in args.vararg.iter().chain(&args.kwarg) {
+        names.extend(
+            arg.annotation
+                .as_deref()
+                .map(annotation_names)
+                .unwrap_or_default(),
+        );
+    }
+    names.extend(
+        fn_docs
+            .return_type
+            .as_ref()
+            .map(annotation_names)
+            .unwrap_or_default(),
+    );
+    names
+}
+
+fn class_annotation_names(class_docs: &ClassDocumentation) -> Vec<String> {
+    let mut names = vec![];
+    for attr_docs in &class_docs.attributes {
+        names.extend(
+            attr_docs
+                .type_annotation
+                .as_ref()
+                .map(annotation_names)
+                .unwrap_or_default(),
+        );
+        names.extend(
+            attr_docs
+                .default
+                .as_ref()
+                .map(value_names)
+                .unwrap_or_default(),
+        );
+    }
+    names
 }
 
 pub fn should_include_class(
@@ -257,6 +1184,33 @@ pub fn should_include_function(
         && !(skip_private && func_docs.name.starts_with("_"))
 }
 
+pub fn should_include_attribute(
+    attr_docs: &AttributeDocumentation,
+    skip_private: bool,
+    skip_undoc: bool,
+) -> bool {
+    (!skip_undoc || attr_docs.docstring.is_some())
+        && !(skip_private && is_private_attribute(attr_docs))
+}
+
+pub fn should_include_variable(
+    var_docs: &VariableDocumentation,
+    skip_private: bool,
+    skip_undoc: bool,
+) -> bool {
+    (!skip_undoc || var_docs.docstring.is_some())
+        && !(skip_private && is_private_variable(var_docs))
+}
+
+pub fn should_include_type_alias(
+    alias_docs: &TypeAliasDocumentation,
+    skip_private: bool,
+    skip_undoc: bool,
+) -> bool {
+    (!skip_undoc || alias_docs.docstring.is_some())
+        && !(skip_private && is_private_type_alias(alias_docs))
+}
+
 pub fn should_include_module(mod_docs: &ModuleDocumentation, skip_undoc: bool) -> bool {
     !skip_undoc || mod_docs.docstring.is_some()
 }
@@ -282,6 +1236,34 @@ pub fn index_functions(
     }
 }
 
+pub fn index_variable(index: &mut RawIndex, var_docs: &VariableDocumentation, prefix: String) {
+    let full_prefix = format!("{}.{}", prefix, var_docs.name);
+    tracing::debug!("Indexing {}", &full_prefix);
+
+    // things like `foo = decorate(foo)` are common enough at module level
+    // that we don't want to error on them
+    match index.internal_object_store.entry(full_prefix) {
+        Entry::Occupied(e) => warn!("{} is defined more than once, skipping variable", e.key()),
+        Entry::Vacant(e) => {
+            e.insert(ObjectDocumentation::Variable(var_docs.clone()));
+        }
+    }
+}
+
+pub fn index_type_alias(index: &mut RawIndex, alias_docs: &TypeAliasDocumentation, prefix: String) {
+    let full_prefix = format!("{}.{}", prefix, alias_docs.name);
+    tracing::debug!("Indexing {}", &full_prefix);
+
+    // things like `foo = decorate(foo)` are common enough at module level
+    // that we don't want to error on them
+    match index.internal_object_store.entry(full_prefix) {
+        Entry::Occupied(e) => warn!("{} is defined more than once, skipping type alias", e.key()),
+        Entry::Vacant(e) => {
+            e.insert(ObjectDocumentation::TypeAlias(alias_docs.clone()));
+        }
+    }
+}
+
 pub fn index_class(
     index: &mut RawIndex,
     class_docs: &ClassDocumentation,
@@ -296,13 +1278,34 @@ pub fn index_class(
         for meth_doc in &class_docs.methods {
             index_functions(index, meth_doc, full_prefix.clone())?;
         }
+        let mut class_docs = class_docs.clone();
+        class_docs
+            .attributes
+            .retain(|a| should_include_attribute(a, index.skip_private, index.skip_undoc));
+        for attr_docs in &class_docs.attributes {
+            index_attribute(index, attr_docs, full_prefix.clone());
+        }
         index
             .internal_object_store
-            .insert(full_prefix, ObjectDocumentation::Class(class_docs.clone()));
+            .insert(full_prefix, ObjectDocumentation::Class(class_docs));
         Ok(())
     }
 }
 
+pub fn index_attribute(index: &mut RawIndex, attr_docs: &AttributeDocumentation, prefix: String) {
+    let full_prefix = format!("{}.{}", prefix, attr_docs.name);
+    tracing::debug!("Indexing {}", &full_prefix);
+
+    // python allows a method to shadow an attribute and vice versa,
+    // in that case we only document the one that we encountered first
+    match index.internal_object_store.entry(full_prefix) {
+        Entry::Occupied(e) => warn!("{} is defined more than once, skipping attribute", e.key()),
+        Entry::Vacant(e) => {
+            e.insert(ObjectDocumentation::Attribute(attr_docs.clone()));
+        }
+    }
+}
+
 /// from import as in `from a.b.c import d`
 ///                         -----
 pub fn get_from_import_path(pkg_name: String, relative_module_file_path: &Path) -> Result<String> {
@@ -333,7 +1336,7 @@ pub fn suggest_known_alternative(
         .filter(|(_, score)| score < &max_edit_distance)
         .collect::<Vec<(String, usize)>>();
 
-    candidates.sort_by(|a, b| a.1.cmp(&b.1));
+    candidates.sort_by_key(|a| a.1);
 
     candidates.first().cloned()
 }
@@ -341,8 +1344,115 @@ pub fn suggest_known_alternative(
 #[cfg(test)]
 mod test {
 
-    use super::suggest_known_alternative;
+    use super::{RawIndex, suggest_known_alternative};
+    use crate::parsing::{
+        ObjectDocumentation,
+        docstring::DocstringStyle,
+        python::{class::InheritedMembers, object::MemberKind},
+    };
+    use assert_fs::prelude::*;
     use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use url::Url;
+
+    fn index_diamond() -> Result<RawIndex> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("shapes");
+        pkg.child("__init__.py").touch()?;
+        let module = pkg.child("base.py");
+        module.write_str(
+            r#"
+class Shape:
+    def area(self): ...
+    def describe(self): ...
+
+class Rounded(Shape):
+    def describe(self): ...
+    def radius(self): ...
+
+class Polygon(Shape):
+    def corners(self): ...
+    def area(self): ...
+
+class Weird(Rounded, Polygon, numpy.ndarray):
+    def corners(self): ...
+"#,
+        )?;
+
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, false)?;
+        index.external_object_store.insert(
+            "numpy.ndarray".to_string(),
+            Url::parse("https://numpy.org/doc/stable/numpy.ndarray.html")?,
+        );
+        index.index_file(module.to_path_buf())?;
+        index.resolve_class_bases();
+        Ok(index)
+    }
+
+    #[test]
+    fn resolve_internal_and_external_bases() -> Result<()> {
+        let index = index_diamond()?;
+        let Some(ObjectDocumentation::Class(weird)) =
+            index.internal_object_store.get("shapes.base.Weird")
+        else {
+            panic!("Weird was not indexed")
+        };
+        let targets: Vec<Option<String>> = weird.bases.iter().map(|b| b.target.clone()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                Some("shapes.base.Rounded".to_string()),
+                Some("shapes.base.Polygon".to_string()),
+                Some("https://numpy.org/doc/stable/numpy.ndarray.html".to_string()),
+            ]
+        );
+        assert_eq!(
+            index.internal_bases("shapes.base.Weird"),
+            vec!["shapes.base.Rounded", "shapes.base.Polygon"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn method_resolution_order_diamond() -> Result<()> {
+        let index = index_diamond()?;
+        assert_eq!(
+            index.method_resolution_order("shapes.base.Weird"),
+            Some(vec![
+                "shapes.base.Weird".to_string(),
+                "shapes.base.Rounded".to_string(),
+                "shapes.base.Polygon".to_string(),
+                "shapes.base.Shape".to_string(),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn inherited_members_follow_mro() -> Result<()> {
+        let mut index = index_diamond()?;
+        index.collect_inherited_members();
+        let Some(ObjectDocumentation::Class(weird)) =
+            index.internal_object_store.get("shapes.base.Weird")
+        else {
+            panic!("Weird was not indexed")
+        };
+        assert_eq!(
+            weird.inherited_members,
+            vec![
+                InheritedMembers {
+                    base: "shapes.base.Rounded".to_string(),
+                    methods: vec!["describe".to_string(), "radius".to_string()],
+                },
+                InheritedMembers {
+                    base: "shapes.base.Polygon".to_string(),
+                    methods: vec!["area".to_string()],
+                },
+            ]
+        );
+        Ok(())
+    }
 
     #[test]
     fn suggest_alternatives_garbage() -> Result<()> {
@@ -399,4 +1509,497 @@ fn suggest_alternatives_internal() -> Result<()> {
         assert_eq!(suggested_ref, Some(("test_pkg.bar.greet".to_string(), 1)));
         Ok(())
     }
+
+    fn index_reexporting_package() -> Result<RawIndex> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("geo");
+        pkg.child("__init__.py").write_str(
+            r#"
+from .shapes import Square
+from ._impl import area, _helper
+import numpy
+"#,
+        )?;
+        pkg.child("_impl.py").write_str(
+            r#"
+def area(shape): ...
+def _helper(): ...
+def unexported(): ...
+"#,
+        )?;
+        let shapes = pkg.child("shapes");
+        shapes.child("__init__.py").write_str(
+            r#"
+from ._square import Square, Rectangle
+
+__all__ = ["Square"]
+"#,
+        )?;
+        shapes
+            .child("_square.py")
+            .write_str("class Square:\n    def side(self): ...\nclass Rectangle: ...\n")?;
+
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, true)?;
+        for file in [
+            "__init__.py",
+            "_impl.py",
+            "shapes/__init__.py",
+            "shapes/_square.py",
+        ] {
+            index.index_file(pkg.join(file))?;
+        }
+        index.apply_reexports();
+        Ok(index)
+    }
+
+    #[test]
+    fn reexports_are_moved_to_public_path() -> Result<()> {
+        let index = index_reexporting_package()?;
+        for key in ["geo.Square", "geo.Square.side", "geo.area"] {
+            assert!(
+                index.internal_object_store.contains_key(key),
+                "{key} is missing"
+            );
+        }
+        for key in [
+            "geo.shapes.Square",
+            "geo.shapes._square.Square",
+            "geo._impl.area",
+        ] {
+            assert!(
+                !index.internal_object_store.contains_key(key),
+                "{key} should have been moved"
+            );
+        }
+        // names that aren't imported or missing from `__all__` are not re-exported
+        assert!(
+            index
+                .internal_object_store
+                .contains_key("geo._impl.unexported")
+        );
+        assert!(
+            index
+                .internal_object_store
+                .contains_key("geo.shapes._square.Rectangle")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn source_path_of_reexports_is_where_they_are_defined() -> Result<()> {
+        let index = index_reexporting_package()?;
+        let source_path = |key: &str| {
+            index
+                .source_path(key)
+                .and_then(|p| p.strip_prefix(&index.pkg_root).ok())
+                .map(|p| p.to_path_buf())
+        };
+        assert_eq!(
+            source_path("geo.Square.side"),
+            Some(std::path::PathBuf::from("shapes").join("_square.py"))
+        );
+        assert_eq!(
+            source_path("geo.area"),
+            Some(std::path::PathBuf::from("_impl.py"))
+        );
+        assert_eq!(
+            source_path("geo._impl.unexported"),
+            Some(std::path::PathBuf::from("_impl.py"))
+        );
+        assert_eq!(
+            source_path("geo"),
+            Some(std::path::PathBuf::from("__init__.py"))
+        );
+        assert_eq!(source_path("elsewhere.thing"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn reexport_aliases_resolve_to_public_path() -> Result<()> {
+        let index = index_reexporting_package()?;
+        for alias in [
+            "geo.Square",
+            "geo.shapes.Square",
+            "geo.shapes._square.Square",
+        ] {
+            assert_eq!(index.canonical_name(alias), Some("geo.Square".to_string()));
+        }
+        assert_eq!(
+            index.canonical_name("geo.shapes._square.Square.side"),
+            Some("geo.Square.side".to_string())
+        );
+        assert_eq!(index.canonical_name("geo.Rectangle"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn remove_private_modules_keeps_reexports() -> Result<()> {
+        let mut index = index_reexporting_package()?;
+        index.remove_private_modules();
+
+        let mut keys: Vec<&String> = index.internal_object_store.keys().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "geo",
+                "geo.Square",
+                "geo.Square.side",
+                "geo.area",
+                "geo.shapes"
+            ]
+        );
+        assert_eq!(
+            index.canonical_name("geo._impl.area"),
+            Some("geo.area".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove_file_leaves_sub_modules_alone() -> Result<()> {
+        let mut index = index_reexporting_package()?;
+        let root = index.pkg_root.clone();
+
+        index.remove_file(&root.join("shapes/__init__.py"));
+        assert!(!index.internal_object_store.contains_key("geo.shapes"));
+        assert!(!index.reexports.contains_key("geo.shapes"));
+        assert!(
+            index
+                .internal_object_store
+                .contains_key("geo.shapes._square.Rectangle")
+        );
+
+        index.remove_file(&root.join("shapes/_square.py"));
+        assert!(
+            !index
+                .internal_object_store
+                .contains_key("geo.shapes._square.Rectangle")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn members_are_collected_after_reexports() -> Result<()> {
+        let mut index = index_reexporting_package()?;
+        index.remove_private_modules();
+        index.collect_members(DocstringStyle::Auto);
+
+        let members = |key: &str| -> Vec<(String, MemberKind)> {
+            match index.internal_object_store.get(key) {
+                Some(ObjectDocumentation::Module(mod_docs)) => &mod_docs.members,
+                Some(ObjectDocumentation::Class(class_docs)) => &class_docs.members,
+                _ => return vec![],
+            }
+            .iter()
+            .map(|m| (m.fully_qualified_name.clone(), m.kind))
+            .collect()
+        };
+
+        assert_eq!(
+            members("geo"),
+            vec![
+                ("geo.shapes".to_string(), MemberKind::Module),
+                ("geo.Square".to_string(), MemberKind::Class),
+                ("geo.area".to_string(), MemberKind::Function),
+            ]
+        );
+        assert_eq!(
+            members("geo.Square"),
+            vec![("geo.Square.side".to_string(), MemberKind::Method)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn annotations_resolve_through_imports() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("shop");
+        pkg.child("__init__.py").touch()?;
+        pkg.child("items.py").write_str(
+            r#"
+class Item: ...
+class Basket(list): ...
+"#,
+        )?;
+        let module = pkg.child("checkout.py");
+        module.write_str(
+            r#"
+import numpy as np
+from .items import Item as Product, Basket
+
+class Receipt(Basket):
+    total: np.ndarray = np.zeros(3)
+
+def pay(item: Product, amount: "np.ndarray", note: str = "Item") -> dict[str, Receipt]: ...
+"#,
+        )?;
+
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, false)?;
+        index.external_object_store.insert(
+            "numpy.ndarray".to_string(),
+            Url::parse("https://numpy.org/doc/stable/numpy.ndarray.html")?,
+        );
+        index.external_object_store.insert(
+            "numpy.zeros".to_string(),
+            Url::parse("https://numpy.org/doc/stable/numpy.zeros.html")?,
+        );
+        for file in ["items.py", "checkout.py"] {
+            index.index_file(pkg.child(file).to_path_buf())?;
+        }
+        index.resolve_class_bases();
+        index.resolve_annotations();
+
+        let Some(ObjectDocumentation::Function(pay)) =
+            index.internal_object_store.get("shop.checkout.pay")
+        else {
+            panic!("pay was not indexed")
+        };
+        let mut targets: Vec<(&str, &str)> = pay
+            .annotation_targets
+            .iter()
+            .map(|(name, target)| (name.as_str(), target.as_str()))
+            .collect();
+        targets.sort();
+        // strings in defaults are not names, and `str`, `dict` aren't indexed
+        assert_eq!(
+            targets,
+            vec![
+                ("Product", "shop.items.Item"),
+                ("Receipt", "shop.checkout.Receipt"),
+                (
+                    "np.ndarray",
+                    "https://numpy.org/doc/stable/numpy.ndarray.html"
+                ),
+            ]
+        );
+
+        let Some(ObjectDocumentation::Class(receipt)) =
+            index.internal_object_store.get("shop.checkout.Receipt")
+        else {
+            panic!("Receipt was not indexed")
+        };
+        assert_eq!(
+            receipt.bases[0].target,
+            Some("shop.items.Basket".to_string())
+        );
+        assert_eq!(
+            receipt
+                .annotation_targets
+                .get("np.zeros")
+                .map(String::as_str),
+            Some("https://numpy.org/doc/stable/numpy.zeros.html")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn references_resolve_like_python_names() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("zoo");
+        pkg.child("__init__.py")
+            .write_str("from .animals import Cat")?;
+        pkg.child("animals.py").write_str(
+            r#"
+import numpy as np
+from .food import Fish as Snack
+
+class Cat:
+    def eat(self): ...
+
+def feed(): ...
+"#,
+        )?;
+        pkg.child("food.py").write_str("class Fish: ...")?;
+
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, false)?;
+        index.external_object_store.insert(
+            "numpy.ndarray".to_string(),
+            Url::parse("https://numpy.org/doc/stable/numpy.ndarray.html")?,
+        );
+        for file in ["__init__.py", "animals.py", "food.py"] {
+            index.index_file(pkg.child(file).to_path_buf())?;
+        }
+
+        let resolve = |key: &str, reference: &str| index.resolve_reference(key, reference);
+        // local names, also from the docstring of a method
+        assert_eq!(
+            resolve("zoo.animals.Cat.eat", "feed"),
+            Some("zoo.animals.feed".to_string())
+        );
+        assert_eq!(
+            resolve("zoo.animals", "Cat.eat"),
+            Some("zoo.animals.Cat.eat".to_string())
+        );
+        // imported aliases
+        assert_eq!(
+            resolve("zoo.animals", "Snack"),
+            Some("zoo.food.Fish".to_string())
+        );
+        assert_eq!(
+            resolve("zoo.animals", "np.ndarray"),
+            Some("https://numpy.org/doc/stable/numpy.ndarray.html".to_string())
+        );
+        // relative references, from a module and from a package
+        assert_eq!(
+            resolve("zoo.animals", ".food.Fish"),
+            Some("zoo.food.Fish".to_string())
+        );
+        assert_eq!(
+            resolve("zoo", ".animals.feed"),
+            Some("zoo.animals.feed".to_string())
+        );
+        assert_eq!(resolve("zoo", "..animals"), None);
+        // fully qualified names still work
+        assert_eq!(
+            resolve("zoo.food", "zoo.animals.Cat"),
+            Some("zoo.animals.Cat".to_string())
+        );
+        assert_eq!(resolve("zoo.food", "Cat"), None);
+        assert!(index.validate_references().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn inventory_lists_objects_and_aliases_with_their_page() -> Result<()> {
+        use crate::render::formats::md::MdRenderer;
+
+        let index = index_reexporting_package()?;
+        let inventory = index
+            .inventory(&MdRenderer::new(), &PathBuf::from("api"))
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>();
+        for line in [
+            "geo py:module 0 api/geo.md -",
+            "geo.Square py:class 1 api/geo.Square.md -",
+            "geo.Square.side py:method 1 api/geo.Square.side.md -",
+            "geo.area py:function 1 api/geo.area.md -",
+            "geo.shapes._square.Square py:class 1 api/geo.Square.md -",
+        ] {
+            assert!(inventory.contains(&line.to_string()), "{line} is missing");
+        }
+        let mut sorted = inventory.clone();
+        sorted.sort();
+        assert_eq!(inventory, sorted);
+        Ok(())
+    }
+
+    #[test]
+    fn properties_round_trip_through_the_inventory() -> Result<()> {
+        use crate::parsing::sphinx::inv_file::{parse_objects_inv, write_objects_inv};
+        use crate::render::formats::md::MdRenderer;
+        use std::io::BufReader;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("geo");
+        pkg.child("__init__.py").write_str(
+            r#"
+from functools import cached_property
+
+class Square:
+    side: int = 1
+
+    @property
+    def area(self) -> int: ...
+
+    @cached_property
+    def diagonal(self) -> float: ...
+
+    def scale(self, factor): ...
+"#,
+        )?;
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, false)?;
+        index.index_file(pkg.join("__init__.py"))?;
+
+        let inventory = index.inventory(&MdRenderer::new(), &PathBuf::from("api"));
+        let mut written = vec![];
+        write_objects_inv(&mut written, "geo", "", &inventory)?;
+        let read = parse_objects_inv(BufReader::new(written.as_slice()))?
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>();
+
+        for line in [
+            "geo.Square.area py:property 1 api/geo.Square.area.md -",
+            "geo.Square.diagonal py:property 1 api/geo.Square.diagonal.md -",
+            "geo.Square.side py:attribute 1 api/geo.Square.side.md -",
+            "geo.Square.scale py:method 1 api/geo.Square.scale.md -",
+        ] {
+            assert!(
+                read.contains(&line.to_string()),
+                "{line} is missing: {read:?}"
+            );
+        }
+        Ok(())
+    }
+
+    fn index_shapes(square_body: &str) -> Result<RawIndex> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("geo");
+        pkg.child("__init__.py").write_str("")?;
+        pkg.child("square.py").write_str(square_body)?;
+        pkg.child("canvas.py").write_str(
+            r#"
+from .square import Square
+
+def draw(shape: "Square"):
+    """Draw a shape, see [[geo.util.clip]]"""
+"#,
+        )?;
+        pkg.child("util.py").write_str("def clip(): ...\n")?;
+
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, false)?;
+        for file in ["__init__.py", "square.py", "canvas.py", "util.py"] {
+            index.index_file(pkg.join(file))?;
+        }
+        Ok(index)
+    }
+
+    #[test]
+    fn dependencies_follow_imports_and_references() -> Result<()> {
+        let index = index_shapes("class Square:\n    \"\"\"A square\"\"\"\n")?;
+        let dependencies = index.dependencies();
+
+        assert_eq!(
+            dependencies
+                .get("geo.canvas")
+                .map(|d| d.iter().map(String::as_str).collect::<Vec<_>>()),
+            Some(vec!["geo.square", "geo.util"])
+        );
+        assert!(dependencies.get("geo.square").is_none_or(|d| d.is_empty()));
+        Ok(())
+    }
+
+    #[test]
+    fn interfaces_only_change_with_what_other_modules_see() -> Result<()> {
+        let interfaces = |body: &str| -> Result<Option<String>> {
+            Ok(index_shapes(body)?
+                .interfaces(DocstringStyle::Google)
+                .remove("geo.square"))
+        };
+        let original = interfaces(
+            "class Square:\n    \"\"\"A square\n\n    More.\"\"\"\n    def area(self):\n        return 1\n",
+        )?;
+
+        assert_eq!(
+            original,
+            interfaces(
+                "class Square:\n    \"\"\"A square\n\n    Other details.\"\"\"\n    def area(self):\n        return 2\n"
+            )?
+        );
+        assert_ne!(
+            original,
+            interfaces(
+                "class Square:\n    \"\"\"A shape\n\n    More.\"\"\"\n    def area(self):\n        return 1\n"
+            )?
+        );
+        assert_ne!(
+            original,
+            interfaces(
+                "class Square:\n    \"\"\"A square\n\n    More.\"\"\"\n    def area(self):\n        return 1\nclass Circle: ...\n"
+            )?
+        );
+        Ok(())
+    }
 }
@@ -1,9 +1,69 @@
-use lazy_regex::regex_captures_iter;
+use clap::ValueEnum;
+use lazy_regex::{regex_captures, regex_captures_iter, regex_is_match};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use strum::Display;
+
+use crate::parsing::docstring::indent_of;
+
+/// What to do with references that can't be resolved
+#[derive(
+    Clone, Copy, Debug, Default, Display, ValueEnum, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ReferencePolicy {
+    /// Report all unresolved references and stop without writing anything
+    #[default]
+    Error,
+    /// Report all unresolved references, but render them as plain text and carry on
+    Warn,
+    /// Render unresolved references as plain text without reporting them
+    Ignore,
+}
+
+/// The ways references can be written in docstrings
+#[derive(
+    Clone, Copy, Debug, Default, Display, ValueEnum, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ReferenceSyntax {
+    /// `[[pkg.mod.obj]]` and `[[pkg.mod.obj|display text]]`
+    #[default]
+    Wiki,
+    /// Sphinx roles such as ``:func:`pkg.mod.obj` ``
+    Sphinx,
+    /// mkdocstrings autorefs such as `[display text][pkg.mod.obj]` and `` [`pkg.mod.obj`][] ``
+    Autorefs,
+}
+
+impl ReferenceSyntax {
+    pub fn extract(&self, text: &str) -> Vec<ObjectRef> {
+        match self {
+            ReferenceSyntax::Wiki => extract_wiki_refs(text),
+            ReferenceSyntax::Sphinx => extract_sphinx_roles(text),
+            ReferenceSyntax::Autorefs => extract_autorefs(text),
+        }
+    }
+}
+
+/// The syntaxes that are recognised unless configured otherwise. Sphinx roles
+/// and autorefs have to be turned on explicitly, as docstrings that were never
+/// meant to be linked may contain them, and unresolved references fail the build.
+pub fn default_reference_syntaxes() -> Vec<ReferenceSyntax> {
+    vec![ReferenceSyntax::Wiki]
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ObjectRef {
     pub fully_qualified_name: String,
     pub display_text: Option<String>,
+    /// Sphinx roles starting with a `!` are shown without a link,
+    /// and aren't checked either
+    pub link: bool,
+    /// The reference exactly as it was written, so it can be replaced
+    pub original: String,
 }
 
 // NOTE: only the index can render these things because
@@ -11,48 +71,261 @@ pub struct ObjectRef {
 // fn on this impl
 impl ObjectRef {
     pub fn new(name: String, display: Option<String>) -> Self {
+        let original = match &display {
+            None => format!("[[{name}]]"),
+            Some(d) => format!("[[{name}|{d}]]"),
+        };
         Self {
             fully_qualified_name: name,
             display_text: display,
+            link: true,
+            original,
         }
     }
+}
 
-    pub fn original(&self) -> String {
-        match &self.display_text {
-            None => {
-                format!("[[{}]]", self.fully_qualified_name)
-            }
-            Some(d) => format!("[[{}|{}]]", self.fully_qualified_name, d),
-        }
-    }
+/// Finds the references written in any of the given syntaxes
+pub fn extract_object_refs(text: &str, syntaxes: &[ReferenceSyntax]) -> Vec<ObjectRef> {
+    syntaxes.iter().flat_map(|s| s.extract(text)).collect()
 }
 
-pub fn extract_object_refs(text: &str) -> Vec<ObjectRef> {
-    regex_captures_iter!(r"\[\[(.*)\]\]", text)
+fn extract_wiki_refs(text: &str) -> Vec<ObjectRef> {
+    regex_captures_iter!(r"\[\[(.*?)\]\]", text)
         .filter_map(|captures| {
-            let (_, [ref_text]) = captures.extract();
+            let (original, [ref_text]) = captures.extract();
 
             let (name, display) = match ref_text.split_once("|") {
                 Some((name, display)) => (name.trim(), Some(display.trim().to_string())),
-                None => (ref_text.trim(), None),
+                // `[[term:iterable]]` is shown as `iterable`
+                None => (
+                    ref_text.trim(),
+                    ref_text
+                        .split_once(':')
+                        .map(|(_, name)| name.trim().to_string()),
+                ),
             };
 
             if name.is_empty() {
                 None
             } else {
-                Some(ObjectRef::new(name.to_owned(), display))
+                Some(ObjectRef {
+                    original: original.to_string(),
+                    ..ObjectRef::new(name.to_owned(), display)
+                })
+            }
+        })
+        .collect()
+}
+
+fn extract_sphinx_roles(text: &str) -> Vec<ObjectRef> {
+    regex_captures_iter!(r":(?:(py|std|c|cpp|js|rst):)?(\w+):`([^`]+)`", text)
+        .filter_map(|captures| {
+            let original = captures.get(0)?.as_str();
+            let role = captures.get(2)?.as_str();
+            let namespace = role_namespace(captures.get(1).map(|d| d.as_str()), role)?;
+            let content = captures.get(3)?.as_str().trim();
+            // terms and labels are prose, everything else is code
+            let code = namespace != "term" && namespace != "label";
+            let object_ref = parse_role(role, content, original, code)?;
+            if namespace.is_empty() {
+                return Some(object_ref);
             }
+            Some(ObjectRef {
+                fully_qualified_name: format!("{namespace}:{}", object_ref.fully_qualified_name),
+                ..object_ref
+            })
         })
         .collect()
 }
 
+/// The prefix of the names a role refers to, see
+/// [`crate::parsing::sphinx::types::SphinxType::namespace`]. Python objects
+/// don't have one, and roles we don't know about are left alone.
+fn role_namespace<'a>(domain: Option<&'a str>, role: &'a str) -> Option<&'a str> {
+    match (domain, role) {
+        (
+            None | Some("py"),
+            "func" | "class" | "meth" | "mod" | "attr" | "exc" | "obj" | "data" | "const" | "any",
+        ) => Some(""),
+        (None | Some("std"), "term" | "envvar" | "token" | "keyword") => Some(role),
+        (None | Some("std"), "ref") => Some("label"),
+        (None | Some("std"), "option") => Some("cmdoption"),
+        (Some(domain @ ("c" | "cpp" | "js" | "rst")), _) => Some(domain),
+        _ => None,
+    }
+}
+
+/// `[display text][identifier]` or `[identifier][]`, where the identifier may
+/// be wrapped in backticks. Anything that doesn't look like a dotted name is
+/// left alone, as it is probably a regular markdown reference link, and so is
+/// anything in code, where e.g. `matrix[i][j]` is a subscript.
+fn extract_autorefs(text: &str) -> Vec<ObjectRef> {
+    let code = code_ranges(text);
+    regex_captures_iter!(r"\[([^\[\]]+)\]\[([^\[\]]*)\]", text)
+        .filter_map(|captures| {
+            let start = captures.get(0)?.start();
+            if code.iter().any(|range| range.contains(&start)) {
+                return None;
+            }
+            let (original, [display, identifier]) = captures.extract();
+            let identifier = if identifier.trim().is_empty() {
+                display
+            } else {
+                identifier
+            };
+            let identifier = identifier.trim().trim_matches('`');
+            if !regex_is_match!(r"^[A-Za-z_][\w.]*$", identifier) {
+                return None;
+            }
+            Some(ObjectRef {
+                fully_qualified_name: identifier.to_string(),
+                display_text: Some(display.trim().to_string()),
+                link: true,
+                original: original.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Where the code in markdown text is: code spans, fenced code blocks,
+/// doctests and blocks indented further than the paragraph before them
+fn code_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    let mut in_doctest = false;
+    let mut code_indent: Option<usize> = None;
+    let mut prose_indent = 0;
+    let mut after_blank = true;
+    for line in text.split_inclusive('\n') {
+        let range = offset..offset + line.len();
+        offset += line.len();
+        let trimmed = line.trim();
+        let indent = indent_of(line);
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            ranges.push(range);
+            continue;
+        }
+        if trimmed.is_empty() {
+            in_doctest = false;
+            after_blank = true;
+            continue;
+        }
+        if let Some(block_indent) = code_indent {
+            if indent >= block_indent {
+                ranges.push(range);
+                continue;
+            }
+            code_indent = None;
+        }
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        } else if trimmed.starts_with(">>>") {
+            in_doctest = true;
+        } else if after_blank && indent >= prose_indent + 4 {
+            code_indent = Some(indent);
+        } else if !in_doctest {
+            after_blank = false;
+            prose_indent = indent;
+            ranges.extend(code_spans(line, range.start));
+            continue;
+        }
+        ranges.push(range);
+    }
+    ranges
+}
+
+/// The code spans in a line, delimited by runs of backticks of the same length
+fn code_spans(line: &str, offset: usize) -> Vec<Range<usize>> {
+    let mut spans = vec![];
+    let mut opening: Option<(usize, usize)> = None;
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let run_start = i;
+        while i < bytes.len() && bytes[i] == b'`' {
+            i += 1;
+        }
+        match opening {
+            Some((start, length)) if length == i - run_start => {
+                spans.push(offset + start..offset + i);
+                opening = None;
+            }
+            None => opening = Some((run_start, i - run_start)),
+            Some(_) => {}
+        }
+    }
+    spans
+}
+
+/// Interprets the content of a Sphinx role the way Sphinx does: `title <target>`
+/// sets the display text, `~target` only shows the last component of the target
+/// and `!target` doesn't link at all. Functions and methods get parentheses,
+/// and unless it's prose all of it is shown as code.
+fn parse_role(role: &str, content: &str, original: &str, code: bool) -> Option<ObjectRef> {
+    let (content, link) = match content.strip_prefix('!') {
+        Some(rest) => (rest, false),
+        None => (content, true),
+    };
+    let is_callable = role == "func" || role == "meth";
+
+    let (target, display) =
+        if let Some((_, title, target)) = regex_captures!(r"^(.*?)\s*<([^<>]+)>$", content) {
+            (target.trim(), title.to_string())
+        } else {
+            let (target, short) = match content.strip_prefix('~') {
+                Some(rest) => (rest, true),
+                None => (content, false),
+            };
+            let mut display = if short {
+                target.rsplit('.').next().unwrap_or(target).to_string()
+            } else {
+                target.to_string()
+            };
+            if is_callable && !display.ends_with(')') {
+                display.push_str("()");
+            }
+            (target, display)
+        };
+
+    let target = if is_callable {
+        target.trim_end_matches("()")
+    } else {
+        target
+    };
+    if target.is_empty() {
+        return None;
+    }
+    Some(ObjectRef {
+        fully_qualified_name: target.to_string(),
+        display_text: Some(if code {
+            format!("`{display}`")
+        } else {
+            display
+        }),
+        link,
+        original: original.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod test {
 
     use color_eyre::Result;
     use pretty_assertions::assert_eq;
 
-    use crate::indexing::object_ref::{ObjectRef, extract_object_refs};
+    use crate::indexing::object_ref::{
+        ObjectRef, ReferenceSyntax, default_reference_syntaxes, extract_object_refs,
+    };
     #[test]
     fn regex_test_captures() -> Result<()> {
         let test_text = r#"
@@ -67,6 +340,7 @@ mod {test}
 [[       foo.bar     ]]
 [[      asdf.asdf.asdf       |     |||display text|||      ]]
 idx[[foo == bar] = baz]
+see [[np.ndarray]] and [[.sibling|the sibling]]
 asdlkfj;alskdj;alsdkj
 askdfjoiw3fmxj,cavuiw43i
         "#;
@@ -80,16 +354,188 @@ mod {test}
             ("asdf.asdf.asdf", Some("display text".to_string())),
             ("foo.bar", None),
             ("asdf.asdf.asdf", Some("|||display text|||".to_string())),
+            ("np.ndarray", None),
+            (".sibling", Some("the sibling".to_string())),
         ]
-        // just so I don't have to type out ObjectRef::new every time
         .into_iter()
-        .map(|tup| ObjectRef::new(tup.0.to_owned(), tup.1))
-        .collect::<Vec<ObjectRef>>();
+        .map(|(name, display)| (name.to_string(), display))
+        .collect::<Vec<_>>();
 
-        let found_refs = extract_object_refs(test_text);
+        let found_refs: Vec<(String, Option<String>)> =
+            extract_object_refs(test_text, &[ReferenceSyntax::Wiki])
+                .into_iter()
+                .map(|r| (r.fully_qualified_name, r.display_text))
+                .collect();
 
         assert_eq!(expected_refs, found_refs);
 
         Ok(())
     }
+
+    #[test]
+    fn extract_sphinx_roles() -> Result<()> {
+        let test_text = r#"
+Use :func:`pkg.mod.helper` or :py:meth:`~pkg.Cls.run()` instead of
+:class:`the class <pkg.Cls>`, and see :mod:`!pkg.internal`, :attr:`pkg.Cls.x`
+and :exc:`ValueError`. :unknown:`pkg.thing` and [[pkg.other]] too.
+"#;
+
+        let found_refs =
+            extract_object_refs(test_text, &[ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]);
+        let expected_refs = vec![
+            ObjectRef::new("pkg.other".to_string(), None),
+            ObjectRef {
+                fully_qualified_name: "pkg.mod.helper".to_string(),
+                display_text: Some("`pkg.mod.helper()`".to_string()),
+                link: true,
+                original: ":func:`pkg.mod.helper`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "pkg.Cls.run".to_string(),
+                display_text: Some("`run()`".to_string()),
+                link: true,
+                original: ":py:meth:`~pkg.Cls.run()`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "pkg.Cls".to_string(),
+                display_text: Some("`the class`".to_string()),
+                link: true,
+                original: ":class:`the class <pkg.Cls>`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "pkg.internal".to_string(),
+                display_text: Some("`pkg.internal`".to_string()),
+                link: false,
+                original: ":mod:`!pkg.internal`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "pkg.Cls.x".to_string(),
+                display_text: Some("`pkg.Cls.x`".to_string()),
+                link: true,
+                original: ":attr:`pkg.Cls.x`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "ValueError".to_string(),
+                display_text: Some("`ValueError`".to_string()),
+                link: true,
+                original: ":exc:`ValueError`".to_string(),
+            },
+        ];
+
+        assert_eq!(found_refs, expected_refs);
+
+        // roles are only recognised when asked for
+        assert_eq!(
+            extract_object_refs(test_text, &default_reference_syntaxes()),
+            vec![ObjectRef::new("pkg.other".to_string(), None)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn extract_autorefs() -> Result<()> {
+        let test_text = r#"
+See [the helper][pkg.mod.helper], [`pkg.Cls`][] and [pkg.Cls.run][].
+Regular [markdown][1] links are left alone, and so is [[pkg.other]].
+"#;
+
+        let found_refs = extract_object_refs(test_text, &[ReferenceSyntax::Autorefs]);
+        let expected_refs = vec![
+            ObjectRef {
+                fully_qualified_name: "pkg.mod.helper".to_string(),
+                display_text: Some("the helper".to_string()),
+                link: true,
+                original: "[the helper][pkg.mod.helper]".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "pkg.Cls".to_string(),
+                display_text: Some("`pkg.Cls`".to_string()),
+                link: true,
+                original: "[`pkg.Cls`][]".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "pkg.Cls.run".to_string(),
+                display_text: Some("pkg.Cls.run".to_string()),
+                link: true,
+                original: "[pkg.Cls.run][]".to_string(),
+            },
+        ];
+        assert_eq!(found_refs, expected_refs);
+
+        // only the configured syntaxes are recognised
+        assert!(extract_object_refs(test_text, &[ReferenceSyntax::Sphinx]).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn autorefs_are_not_found_in_code() {
+        let test_text = r#"Index it like `matrix[i][j]` or ``grid[row][col]``, see [pkg.Grid][].
+
+```python
+grid[row][col] = 1
+```
+
+    cells[x][y]
+
+Examples:
+    >>> grid[row][col]
+    0
+    >>> rows[0][1]
+
+And back to [`pkg.Cell`][]."#;
+
+        let found: Vec<String> = extract_object_refs(test_text, &[ReferenceSyntax::Autorefs])
+            .into_iter()
+            .map(|r| r.fully_qualified_name)
+            .collect();
+        assert_eq!(found, vec!["pkg.Grid", "pkg.Cell"]);
+    }
+
+    #[test]
+    fn extract_namespaced_references() -> Result<()> {
+        let test_text = r#"
+Takes an :term:`iterable`, see :ref:`the tutorial <tut-loops>`,
+:c:func:`PyObject_GetAttr` and :envvar:`PYTHONPATH`. [[c:Py_ssize_t]] and
+[[term:iterable|iterables]] work too, but :math:`x^2` and :foo:bar:`baz` don't.
+"#;
+
+        let found_refs =
+            extract_object_refs(test_text, &[ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]);
+        let expected_refs = vec![
+            ObjectRef {
+                fully_qualified_name: "c:Py_ssize_t".to_string(),
+                display_text: Some("Py_ssize_t".to_string()),
+                link: true,
+                original: "[[c:Py_ssize_t]]".to_string(),
+            },
+            ObjectRef::new("term:iterable".to_string(), Some("iterables".to_string())),
+            ObjectRef {
+                fully_qualified_name: "term:iterable".to_string(),
+                display_text: Some("iterable".to_string()),
+                link: true,
+                original: ":term:`iterable`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "label:tut-loops".to_string(),
+                display_text: Some("the tutorial".to_string()),
+                link: true,
+                original: ":ref:`the tutorial <tut-loops>`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "c:PyObject_GetAttr".to_string(),
+                display_text: Some("`PyObject_GetAttr()`".to_string()),
+                link: true,
+                original: ":c:func:`PyObject_GetAttr`".to_string(),
+            },
+            ObjectRef {
+                fully_qualified_name: "envvar:PYTHONPATH".to_string(),
+                display_text: Some("`PYTHONPATH`".to_string()),
+                link: true,
+                original: ":envvar:`PYTHONPATH`".to_string(),
+            },
+        ];
+
+        assert_eq!(found_refs, expected_refs);
+        Ok(())
+    }
 }
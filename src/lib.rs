@@ -1,48 +1,490 @@
 pub mod config;
 pub mod fs;
 pub mod indexing;
+pub mod manifest;
 pub mod parsing;
 pub mod render;
+pub mod watch;
 
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{File, create_dir_all};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::config::ConfigBuilder;
+use crate::config::{Config, ConfigBuilder, ExternalIndex};
 use crate::fs::{crawl_notebooks, crawl_package};
 pub use crate::fs::{get_module_name, get_package_modules, walk_package};
-use crate::indexing::external::cache::init_cache;
-use crate::indexing::external::fetch::fill_cache;
+use crate::indexing::external::cache::{init_cache, inventory_path};
+use crate::indexing::external::fetch::{FetchOptions, ReqwestClient, fill_cache};
 use crate::indexing::index::RawIndex;
-use crate::parsing::sphinx::inv_file::parse_objects_inv_file;
-use crate::parsing::sphinx::types::{ExternalSphinxRef, StdRole};
-use crate::render::formats::Renderer;
+use crate::indexing::object_ref::ReferencePolicy;
+use crate::manifest::{
+    BuildManifest, BuildPlan, BuildReport, SourceEntry, hash_bytes, hash_config, hash_shared,
+    hash_sources, is_notebook, manifest_path,
+};
+use crate::parsing::sphinx::inv_file::{parse_objects_inv_file, write_objects_inv};
+use crate::parsing::sphinx::types::ExternalSphinxRef;
+use crate::render::hierarchy::{CLASS_HIERARCHY_PAGE, render_class_hierarchy};
 pub use crate::render::render_module;
-use crate::render::{jupyter::render_notebook, render_object};
-use parsing::sphinx::types::SphinxType;
+use crate::render::{jupyter::render_notebook, render_object, templates::Templates};
 
 use color_eyre::Result;
-use color_eyre::eyre::eyre;
+use color_eyre::eyre::{WrapErr, eyre};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use tera::Context;
 use url::Url;
 
-pub async fn render_docs(config_builder: ConfigBuilder) -> Result<Vec<PathBuf>> {
+pub async fn render_docs(config_builder: ConfigBuilder) -> Result<BuildReport> {
+    let config_hash = hash_config(&config_builder)?;
     let config = config_builder.build()?;
-    let absolute_pkg_path = config.pkg_path.canonicalize()?;
-    let out_api_path = if let Some(content_path) = config.renderer.content_path() {
+    fetch_externals(&config).await?;
+
+    let pool = thread_pool(&config)?;
+
+    if config.skip_write {
+        pool.install(|| render_pages(&config, load_index(&config)?))?;
+        return Ok(BuildReport::default());
+    }
+
+    let manifest_path = manifest_path(&config)?;
+    let previous = BuildManifest::load(&manifest_path).unwrap_or_default();
+    let cache_path = init_cache(None)?;
+    let shared = pool.install(|| hash_shared(&config, &cache_path))?;
+    let sources = pool.install(|| hash_sources(&config))?;
+
+    let build = match previous.plan(&config_hash, &shared, &sources) {
+        BuildPlan::UpToDate => {
+            tracing::info!("nothing changed since the last build");
+            return Ok(BuildReport {
+                unchanged: previous.outputs.len(),
+                ..Default::default()
+            });
+        }
+        BuildPlan::Full => pool.install(|| build_all(&config, &sources))?,
+        BuildPlan::Partial(changed) => {
+            pool.install(|| build_changed(&config, &previous, &sources, &changed))?
+        }
+    };
+    let report =
+        pool.install(|| record_build(&manifest_path, &previous, config_hash, shared, build))?;
+
+    for path in &report.parsed {
+        tracing::debug!("parsed {}", path.display());
+    }
+    for path in &report.written {
+        tracing::debug!("wrote {}", path.display());
+    }
+    for path in &report.removed {
+        tracing::debug!("removed {}", path.display());
+    }
+    tracing::info!(
+        "parsed {} of {} source file(s), wrote {} file(s), removed {} stale file(s), {} file(s) unchanged",
+        report.parsed.len(),
+        sources.len(),
+        report.written.len(),
+        report.removed.len(),
+        report.unchanged
+    );
+
+    Ok(report)
+}
+
+/// Writes the pages of `build` that differ from what is on disk, removes the
+/// outputs of the `previous` build that are gone and saves the new manifest
+fn record_build(
+    manifest_path: &Path,
+    previous: &BuildManifest,
+    config_hash: String,
+    shared: BTreeMap<PathBuf, String>,
+    build: Build,
+) -> Result<BuildReport> {
+    let (outputs, mut report) = previous.apply(&build.pages, &build.kept)?;
+    report.parsed = build.parsed;
+
+    BuildManifest {
+        config_hash,
+        shared,
+        sources: build.sources,
+        outputs,
+    }
+    .save(manifest_path)?;
+    Ok(report)
+}
+
+/// Renders everything in `index`, which has to be as it was crawled, and
+/// writes it like [`render_docs`] would, so the manifest stays in line with
+/// what is on disk. This is how `snakedown watch` re-renders after a change.
+pub fn rebuild_index(config: &Config, config_hash: String, index: RawIndex) -> Result<BuildReport> {
+    let manifest_path = manifest_path(config)?;
+    let previous = BuildManifest::load(&manifest_path).unwrap_or_default();
+    let shared = hash_shared(config, &init_cache(None)?)?;
+    let sources = hash_sources(config)?;
+    let all: BTreeSet<PathBuf> = sources.keys().cloned().collect();
+
+    let build = build_sources(config, index, &sources, &all, &BuildManifest::default())?;
+    record_build(&manifest_path, &previous, config_hash, shared, build)
+}
+
+/// What a build rendered and what it knows about the sources it was made from
+struct Build {
+    pages: RenderedPages,
+    sources: BTreeMap<PathBuf, SourceEntry>,
+    /// The outputs of the sources that didn't have to be rendered again
+    kept: BTreeMap<PathBuf, String>,
+    parsed: Vec<PathBuf>,
+}
+
+fn build_all(config: &Config, hashes: &BTreeMap<PathBuf, String>) -> Result<Build> {
+    let index = load_index(config)?;
+    let sources: BTreeSet<PathBuf> = hashes.keys().cloned().collect();
+    build_sources(config, index, hashes, &sources, &BuildManifest::default())
+}
+
+/// Parses the sources that changed, works out which other sources are affected
+/// by that and parses those together with everything they depend on.
+/// Only the pages of the affected sources are rendered again.
+fn build_changed(
+    config: &Config,
+    previous: &BuildManifest,
+    hashes: &BTreeMap<PathBuf, String>,
+    changed: &BTreeSet<PathBuf>,
+) -> Result<Build> {
+    let mut index = new_index(config)?;
+    for path in hashes.keys().filter(|p| !is_notebook(p)) {
+        index.register_module(path.clone())?;
+    }
+    let mut parsed = BTreeSet::new();
+    parse_modules(&mut index, changed, &mut parsed)?;
+    for path in changed.iter().filter(|p| is_notebook(p)) {
+        index.index_notebook(path)?;
+        parsed.insert(path.clone());
+    }
+
+    // other modules only see the objects, summaries and imports of a module
+    let interfaces = index.interfaces(config.docstring_style);
+    let visible_changes: BTreeSet<PathBuf> = changed
+        .iter()
+        .filter(|path| {
+            let interface = module_name(&index, path)
+                .and_then(|module| interfaces.get(module))
+                .map(|interface| hash_bytes(interface.as_bytes()))
+                .unwrap_or_default();
+            previous
+                .sources
+                .get(*path)
+                .is_none_or(|entry| entry.interface != interface)
+        })
+        .cloned()
+        .collect();
+    let mut affected = previous.dependents(&visible_changes);
+    affected.extend(changed.iter().cloned());
+
+    loop {
+        let dependencies = module_dependencies(config, &index);
+        let mut needed: BTreeSet<PathBuf> = affected
+            .iter()
+            .filter(|path| !is_notebook(path))
+            .cloned()
+            .collect();
+        for path in &parsed {
+            let Some(module) = module_name(&index, path) else {
+                continue;
+            };
+            needed.extend(dependencies.get(module).into_iter().flatten().cloned());
+            if affected.contains(path) {
+                needed.extend(
+                    index
+                        .submodules(module)
+                        .filter_map(|m| index.module_paths.get(m))
+                        .cloned(),
+                );
+            }
+        }
+        let missing: BTreeSet<PathBuf> = needed.difference(&parsed).cloned().collect();
+        if missing.is_empty() {
+            break;
+        }
+        parse_modules(&mut index, &missing, &mut parsed)?;
+    }
+
+    let mut build = build_sources(config, index, hashes, &affected, previous)?;
+    build.parsed = parsed.into_iter().collect();
+    Ok(build)
+}
+
+/// Adds the modules to the index, parsing them in parallel
+fn parse_modules(
+    index: &mut RawIndex,
+    paths: &BTreeSet<PathBuf>,
+    parsed: &mut BTreeSet<PathBuf>,
+) -> Result<()> {
+    let modules: Vec<_> = paths
+        .par_iter()
+        .filter(|path| !is_notebook(path))
+        .map(|path| (path.clone(), index.parse_file(path)))
+        .collect();
+    for (path, mod_docs) in modules {
+        index.insert_file(path.clone(), mod_docs)?;
+        parsed.insert(path);
+    }
+    Ok(())
+}
+
+fn module_name<'a>(index: &'a RawIndex, path: &Path) -> Option<&'a String> {
+    index
+        .module_paths
+        .iter()
+        .find(|(_, p)| p.as_path() == path)
+        .map(|(module, _)| module)
+}
+
+/// The files every module depends on, see [`RawIndex::dependencies`]. With
+/// `public_api` that includes the packages above it, which decide where its
+/// objects are documented.
+fn module_dependencies(config: &Config, index: &RawIndex) -> HashMap<String, BTreeSet<PathBuf>> {
+    let dependencies = index.dependencies();
+    index
+        .module_paths
+        .keys()
+        .map(|module| {
+            let mut names: BTreeSet<&String> =
+                dependencies.get(module).into_iter().flatten().collect();
+            if config.public_api {
+                names.extend(
+                    index
+                        .module_paths
+                        .keys()
+                        .filter(|package| module.starts_with(&format!("{package}."))),
+                );
+            }
+            let paths = names
+                .into_iter()
+                .filter_map(|m| index.module_paths.get(m))
+                .cloned()
+                .collect();
+            (module.clone(), paths)
+        })
+        .collect()
+}
+
+/// Renders the pages of the `affected` sources from `index`, which has to contain
+/// them and everything they depend on. What the previous build knew about
+/// the other sources is kept, and used for the pages about the whole package.
+fn build_sources(
+    config: &Config,
+    mut index: RawIndex,
+    hashes: &BTreeMap<PathBuf, String>,
+    affected: &BTreeSet<PathBuf>,
+    previous: &BuildManifest,
+) -> Result<Build> {
+    let mut sources: BTreeMap<PathBuf, SourceEntry> = BTreeMap::new();
+    let mut kept = BTreeMap::new();
+    for (path, hash) in hashes {
+        if affected.contains(path) {
+            sources.insert(
+                path.clone(),
+                SourceEntry {
+                    hash: hash.clone(),
+                    ..Default::default()
+                },
+            );
+        } else if let Some(entry) = previous.sources.get(path) {
+            for page in &entry.pages {
+                if let Some(hash) = previous.outputs.get(page) {
+                    kept.insert(page.clone(), hash.clone());
+                }
+            }
+            sources.insert(path.clone(), entry.clone());
+        }
+    }
+
+    // this has to happen before objects are moved to their public path
+    let interfaces = index.interfaces(config.docstring_style);
+    let dependencies = module_dependencies(config, &index);
+    for (module, path) in &index.module_paths {
+        let Some(entry) = sources.get_mut(path).filter(|_| affected.contains(path)) else {
+            continue;
+        };
+        entry.interface = interfaces
+            .get(module)
+            .map(|interface| hash_bytes(interface.as_bytes()))
+            .unwrap_or_default();
+        entry.depends_on = dependencies.get(module).cloned().unwrap_or_default();
+        entry.submodules = index
+            .submodules(module)
+            .filter_map(|m| index.module_paths.get(m))
+            .cloned()
+            .collect();
+    }
+
+    prepare_index(config, &mut index)?;
+    let (templates, ctx) = page_context(config)?;
+    let is_affected = |key: &str| {
+        index
+            .source_path(key)
+            .is_some_and(|path| affected.contains(path))
+    };
+
+    let mut pages = render_objects(config, &index, &templates, &ctx, is_affected)?;
+    let out_api_path = out_api_path(config);
+    for key in index.internal_object_store.keys() {
+        if let Some(source) = index.source_path(key).filter(|p| affected.contains(*p))
+            && let Some(entry) = sources.get_mut(source)
+        {
+            entry.pages.insert(object_page(&out_api_path, key));
+        }
+    }
+    for (key, entry) in index.inventory_entries(&config.renderer, &config.api_content_path) {
+        if let Some(source) = index.source_path(key).filter(|p| affected.contains(*p))
+            && let Some(source_entry) = sources.get_mut(source)
+        {
+            source_entry.inventory.push(entry.to_string());
+        }
+    }
+    for (class, bases) in index.class_bases() {
+        if let Some(source) = index.source_path(&class).filter(|p| affected.contains(*p))
+            && let Some(entry) = sources.get_mut(source)
+        {
+            entry.classes.insert(class, bases);
+        }
+    }
+
+    let notebook_paths: HashMap<&str, &PathBuf> = affected
+        .iter()
+        .filter(|path| is_notebook(path))
+        .filter_map(|path| Some((path.file_stem()?.to_str()?, path)))
+        .collect();
+    let notebooks = render_notebooks(config, &index, &templates, &ctx, |key| {
+        notebook_paths.contains_key(key)
+    })?;
+    for (key, notebook_pages) in notebooks {
+        if let Some(entry) = notebook_paths
+            .get(key.as_str())
+            .and_then(|p| sources.get_mut(*p))
+        {
+            entry.pages.extend(notebook_pages.keys().cloned());
+        }
+        pages.extend(notebook_pages);
+    }
+
+    let mut inventory = vec![];
+    let mut classes = BTreeMap::new();
+    for entry in sources.values_mut() {
+        entry.inventory.sort();
+        for line in &entry.inventory {
+            inventory.push(ExternalSphinxRef::try_from(line.as_str())?);
+        }
+        classes.extend(entry.classes.clone());
+    }
+    inventory.sort_by(|a, b| a.name.cmp(&b.name));
+    pages.extend(render_package_pages(
+        config,
+        &index.pkg_name,
+        &inventory,
+        &classes,
+        &templates,
+        &ctx,
+    )?);
+
+    Ok(Build {
+        pages,
+        sources,
+        kept,
+        parsed: hashes.keys().cloned().collect(),
+    })
+}
+
+/// The contents of every file we write, keyed by the path it is written to
+pub type RenderedPages = BTreeMap<PathBuf, Vec<u8>>;
+
+pub fn out_api_path(config: &Config) -> PathBuf {
+    if let Some(content_path) = config.renderer.content_path() {
         config
             .site_root
             .join(content_path)
             .join(&config.api_content_path)
     } else {
         config.site_root.join(&config.api_content_path)
-    };
+    }
+}
 
-    let errored = vec![];
+pub fn out_notebook_path(config: &Config) -> Option<PathBuf> {
+    let notebook_path = config.notebook_path.as_ref()?;
+    let notebook_content_path = config
+        .notebook_content_path
+        .clone()
+        .unwrap_or(notebook_path.clone());
+    if let Some(content_path) = config.renderer.content_path() {
+        Some(
+            config
+                .site_root
+                .join(content_path)
+                .join(notebook_content_path),
+        )
+    } else {
+        Some(config.site_root.join(notebook_content_path))
+    }
+}
 
-    let mut ctx = Context::new();
-    let sd_version = env!("CARGO_PKG_VERSION_MAJOR");
-    ctx.insert("SNAKEDOWN_VERSION", &sd_version);
+pub fn out_static_path(config: &Config) -> PathBuf {
+    if let Some(static_path) = config.renderer.static_path() {
+        config.site_root.join(static_path)
+    } else {
+        config.site_root.clone()
+    }
+}
+
+/// Makes sure the inventories of the external indexes are in the cache
+pub async fn fetch_externals(config: &Config) -> Result<()> {
+    let cache_path = init_cache(None)?;
+
+    if config.offline {
+        tracing::info!("Skipping fetching external indexes because running in offline mode.")
+    } else {
+        let client = Arc::new(ReqwestClient::new(&config.fetch)?);
+        let options = FetchOptions::new(&config.fetch, config.cache_ttl);
+        let result = fill_cache(client, &config.externals, &cache_path, &options).await;
+        // missing inventories are only a problem if broken references are errors,
+        // see `load_index`
+        match result {
+            Err(e) if config.reference_policy != ReferencePolicy::Error => {
+                tracing::warn!("{e}")
+            }
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
+/// Loads the external indexes from the cache and parses the package and notebooks.
+/// See [`fetch_externals`] to fill the cache.
+pub fn load_index(config: &Config) -> Result<RawIndex> {
+    let mut index = new_index(config)?;
+    let pkg_root = index.pkg_root.clone();
+
+    // objects defined in private modules can still be part of the public api
+    // so in that case we have to look at them before we know what to skip
+    crawl_package(
+        &mut index,
+        &pkg_root,
+        config.skip_private && !config.public_api,
+        config.exclude.clone(),
+    )?;
+
+    if let Some(nb_path) = &config.notebook_path {
+        tracing::debug!("crawling notebooks");
+        crawl_notebooks(&mut index, nb_path)?;
+    }
+
+    Ok(index)
+}
+
+/// An index of the external indexes in the cache, without anything of the
+/// package or notebooks yet
+fn new_index(config: &Config) -> Result<RawIndex> {
+    let absolute_pkg_path = config.pkg_path.canonicalize()?;
 
     tracing::info!("indexing package at {}", &absolute_pkg_path.display());
     let mut index = RawIndex::new(
@@ -50,93 +492,201 @@ pub async fn render_docs(config_builder: ConfigBuilder) -> Result<Vec<PathBuf>>
         config.skip_undoc,
         config.skip_private,
     )?;
+    index.reference_syntaxes = config.reference_syntax.clone();
 
     let cache_path = init_cache(None)?;
 
-    if config.offline {
-        tracing::info!("Skipping fetching external indexes because running in offline mode.")
-    } else {
-        fill_cache(&config.externals).await?;
-    }
-
-    for (key, ext_index) in config.externals {
-        let inv_path = cache_path.join("sphinx").join(key).with_extension("inv");
+    for (key, ext_index) in &config.externals {
+        let local_inventory = ext_index.local_inventory()?;
+        let is_local = local_inventory.is_some();
+        let inv_path = local_inventory.unwrap_or_else(|| inventory_path(&cache_path, key));
 
-        // TODO: This will be made more flexible once we add a permissive mode
-        // see https://github.com/savente93/snakedown/issues/38
-        if !inv_path.exists() && config.offline {
+        // without the inventory references to this index can't be resolved,
+        // which is only worth stopping for if broken references are errors
+        if !inv_path.exists()
+            && ((config.offline && !is_local) || config.reference_policy != ReferencePolicy::Error)
+        {
+            if is_local {
+                tracing::warn!(
+                    "inventory of {key} does not exist at {}, skipping it",
+                    inv_path.display()
+                );
+            } else if !config.offline {
+                tracing::warn!("inventory of {key} is not in the cache, skipping it");
+            }
             continue;
         }
         let external_base_url = Url::parse(&ext_index.url)?;
 
-        let inv_references = parse_objects_inv_file(&inv_path)?;
+        let inv_references = parse_objects_inv_file(&inv_path).wrap_err_with(|| {
+            format!(
+                "could not read the inventory of {key} at {}",
+                inv_path.display()
+            )
+        })?;
         for r in inv_references {
-            if !should_include_reference(&r) {
+            let Some(name) = reference_key(&r, ext_index) else {
                 continue;
-            }
+            };
             index
                 .external_object_store
-                .insert(r.name, external_base_url.clone().join(&r.location)?);
+                .insert(name, external_base_url.clone().join(&r.location)?);
         }
     }
 
-    crawl_package(
-        &mut index,
-        &absolute_pkg_path,
-        config.skip_private,
-        config.exclude.clone(),
-    )?;
-
-    if let Some(nb_path) = &config.notebook_path {
-        tracing::debug!("crawling notebooks");
-        crawl_notebooks(&mut index, nb_path)?;
-    }
+    Ok(index)
+}
 
-    match index.validate_references() {
-        Ok(_) => Ok(()),
-        Err(errors) => Err(eyre!(
-            "Found {} invalid references(s):\n{:?}",
+/// Reports the references that can't be resolved according to the policy.
+/// Only [`ReferencePolicy::Error`] stops the build, with the other policies
+/// the references are rendered as plain text.
+fn check_references(index: &RawIndex, policy: ReferencePolicy) -> Result<()> {
+    let Err(errors) = index.validate_references() else {
+        return Ok(());
+    };
+    let report = errors
+        .iter()
+        .map(|e| format!("  - {e}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    match policy {
+        ReferencePolicy::Error => Err(eyre!(
+            "Found {} invalid references(s):\n{}",
             errors.len(),
-            errors
+            report
         )),
-    }?;
+        ReferencePolicy::Warn => {
+            tracing::warn!(
+                "Found {} invalid references(s), they are rendered as plain text:\n{}",
+                errors.len(),
+                report
+            );
+            Ok(())
+        }
+        ReferencePolicy::Ignore => {
+            tracing::debug!(
+                "ignoring {} invalid references(s):\n{}",
+                errors.len(),
+                report
+            );
+            Ok(())
+        }
+    }
+}
 
-    index.pre_process(&config.renderer, &config.api_content_path)?;
+/// Resolves and validates all references in the index and renders every page.
+/// Nothing is written to disk yet, see [`write_pages`].
+pub fn render_pages(config: &Config, mut index: RawIndex) -> Result<RenderedPages> {
+    prepare_index(config, &mut index)?;
+    let (templates, ctx) = page_context(config)?;
 
-    if !config.skip_write {
-        create_dir_all(&out_api_path)?;
+    let mut pages = render_objects(config, &index, &templates, &ctx, |_| true)?;
+    for (_, notebook_pages) in render_notebooks(config, &index, &templates, &ctx, |_| true)? {
+        pages.extend(notebook_pages);
     }
+    let inventory = index.inventory(&config.renderer, &config.api_content_path);
+    pages.extend(render_package_pages(
+        config,
+        &index.pkg_name,
+        &inventory,
+        &index.class_bases(),
+        &templates,
+        &ctx,
+    )?);
+
+    Ok(pages)
+}
 
-    for (key, object) in index.internal_object_store.iter() {
-        let file_path = out_api_path.join(key).with_added_extension("md");
-        let rendered = render_object(object, key.clone(), &config.renderer, &ctx)?;
-        let rendered_trimmed = rendered.trim_start();
-        if !config.skip_write {
-            let mut file = File::create(file_path)?;
-            file.write_all(rendered_trimmed.as_bytes())?;
+/// Moves, resolves and validates everything in the index the way the config asks
+/// for, after which the pages can be rendered from it.
+fn prepare_index(config: &Config, index: &mut RawIndex) -> Result<()> {
+    if config.public_api {
+        index.apply_reexports();
+        if config.skip_private {
+            index.remove_private_modules();
         }
     }
 
-    if let Some(notebook_path) = &config.notebook_path {
-        let out_nb_path = if let Some(content_path) = config.renderer.content_path() {
-            config.site_root.clone().join(content_path).join(
-                config
-                    .notebook_content_path
-                    .clone()
-                    .unwrap_or(notebook_path.clone()),
-            )
-        } else {
-            config.site_root.clone().join(
-                config
-                    .notebook_content_path
-                    .clone()
-                    .unwrap_or(notebook_path.clone()),
-            )
-        };
-        if !config.skip_write {
-            create_dir_all(&out_nb_path)?;
-        }
-        for (key, cells) in index.notebook_store.iter() {
+    index.resolve_class_bases();
+    index.resolve_annotations();
+    if config.inherited_members {
+        index.collect_inherited_members();
+    }
+
+    check_references(index, config.reference_policy)?;
+
+    index.pre_process(&config.renderer, &config.api_content_path)?;
+    index.collect_members(config.docstring_style);
+    Ok(())
+}
+
+fn page_context(config: &Config) -> Result<(Templates, Context)> {
+    let templates = Templates::new(config.templates_dir.as_deref())?;
+    let mut ctx = Context::new();
+    let sd_version = env!("CARGO_PKG_VERSION_MAJOR");
+    ctx.insert("SNAKEDOWN_VERSION", &sd_version);
+    Ok((templates, ctx))
+}
+
+fn object_page(out_api_path: &Path, key: &str) -> PathBuf {
+    out_api_path.join(key).with_added_extension("md")
+}
+
+/// Renders the pages of the objects whose key is `selected`
+fn render_objects(
+    config: &Config,
+    index: &RawIndex,
+    templates: &Templates,
+    ctx: &Context,
+    selected: impl Fn(&str) -> bool + Sync,
+) -> Result<RenderedPages> {
+    let out_api_path = out_api_path(config);
+    index
+        .internal_object_store
+        .par_iter()
+        .filter(|(key, _)| selected(key))
+        .map(|(key, object)| {
+            let file_path = object_page(&out_api_path, key);
+            let mut object_ctx = ctx.clone();
+            if let Some(source_path) = index.source_path(key) {
+                object_ctx.insert(
+                    "SNAKEDOWN_SOURCE_PATH",
+                    &display_source_path(index, source_path),
+                );
+            }
+            let rendered = render_object(
+                object,
+                key.clone(),
+                &config.renderer,
+                templates,
+                &config.api_content_path,
+                config.docstring_style,
+                config.signature_width,
+                &object_ctx,
+            )?;
+            let rendered_trimmed = rendered.trim_start();
+            Ok((file_path, rendered_trimmed.as_bytes().to_vec()))
+        })
+        .collect()
+}
+
+/// Renders the notebooks whose key is `selected`, returning the pages
+/// of every notebook by its key
+fn render_notebooks(
+    config: &Config,
+    index: &RawIndex,
+    templates: &Templates,
+    ctx: &Context,
+    selected: impl Fn(&str) -> bool + Sync,
+) -> Result<Vec<(String, RenderedPages)>> {
+    let Some(out_nb_path) = out_notebook_path(config) else {
+        return Ok(vec![]);
+    };
+    index
+        .notebook_store
+        .par_iter()
+        .filter(|(key, _)| selected(key))
+        .map(|(key, cells)| {
             let dir_path = out_nb_path.join(key);
             let file_path = dir_path.clone().join("index").with_added_extension("md");
             let mut rendered = render_notebook(
@@ -146,46 +696,110 @@ pub async fn render_docs(config_builder: ConfigBuilder) -> Result<Vec<PathBuf>>
                     .as_deref(),
                 cells,
                 &config.renderer,
+                templates,
+                ctx,
             )?;
             // some tools insert an extra EOL at the end of the file
             if !rendered.text.ends_with("\n") {
                 rendered.text.push('\n');
             }
 
-            if !config.skip_write {
-                create_dir_all(dir_path.clone())?;
-                let mut file = File::create(file_path)?;
-                file.write_all(rendered.text.as_bytes())?;
-                for img in rendered.images {
-                    let mut img_file = File::create(dir_path.join(img.name))?;
-                    img_file.write_all(&img.data)?;
-                }
+            let mut notebook_pages = RenderedPages::from([(file_path, rendered.text.into_bytes())]);
+            for img in rendered.images {
+                notebook_pages.insert(dir_path.join(img.name), img.data);
             }
-        }
+            Ok((key.clone(), notebook_pages))
+        })
+        .collect()
+}
+
+/// Renders the pages about the package as a whole: the class hierarchy,
+/// the `objects.inv` and the index of the api section
+fn render_package_pages(
+    config: &Config,
+    pkg_name: &str,
+    inventory: &[ExternalSphinxRef],
+    classes: &BTreeMap<String, Vec<String>>,
+    templates: &Templates,
+    ctx: &Context,
+) -> Result<RenderedPages> {
+    let out_api_path = out_api_path(config);
+    let mut pages = RenderedPages::new();
+
+    if config.class_hierarchy
+        && let Some(rendered) = render_class_hierarchy(
+            classes,
+            &config.renderer,
+            templates,
+            &config.api_content_path,
+            ctx,
+        )?
+    {
+        let file_path = out_api_path
+            .join(CLASS_HIERARCHY_PAGE)
+            .with_added_extension("md");
+        pages.insert(file_path, rendered.into_bytes());
     }
 
+    // written where it is served from the root of the site, which is where
+    // intersphinx looks for it
+    let mut objects_inv = vec![];
+    write_objects_inv(&mut objects_inv, pkg_name, "", inventory)?;
+    pages.insert(out_static_path(config).join("objects.inv"), objects_inv);
+
     if let Some((index_file_path, index_file_content)) =
         &config.renderer.index_file(Some("API".to_string()))
-        && !config.skip_write
     {
-        let mut file = File::create(out_api_path.join(index_file_path))?;
-        file.write_all(index_file_content.as_bytes())?;
+        pages.insert(
+            out_api_path.join(index_file_path),
+            index_file_content.as_bytes().to_vec(),
+        );
     }
 
-    Ok(errored)
+    Ok(pages)
+}
+
+/// Source paths are shown relative to the directory the package is in,
+/// so they start with the package name and look the same on every platform
+fn display_source_path(index: &RawIndex, source_path: &Path) -> String {
+    let base = index.pkg_root.parent().unwrap_or(&index.pkg_root);
+    source_path
+        .strip_prefix(base)
+        .unwrap_or(source_path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn write_pages(pages: &RenderedPages) -> Result<()> {
+    pages.par_iter().try_for_each(|(path, contents)| {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let mut file = File::create(path)?;
+        file.write_all(contents)?;
+        Ok(())
+    })
+}
+
+/// The pool parsing, rendering and writing happens on, with `config.jobs` threads
+pub fn thread_pool(config: &Config) -> Result<ThreadPool> {
+    // rayon picks the number of threads itself if it is 0
+    Ok(ThreadPoolBuilder::new().num_threads(config.jobs).build()?)
 }
 
-fn should_include_reference(r: &ExternalSphinxRef) -> bool {
-    // just include python refs and std doc refs, we'll see if we actually
-    // need/want the rest
-    match r.sphinx_type {
-        SphinxType::Std(StdRole::Doc) | SphinxType::Python(_) => true,
-        SphinxType::C(_)
-        | SphinxType::Std(_)
-        | SphinxType::Mathematics(_)
-        | SphinxType::Cpp(_)
-        | SphinxType::JavaScript(_)
-        | SphinxType::ReStructuredText(_) => false,
+/// The name an external reference can be referred to by, if it should be
+/// included at all. Python objects and documents always are and go by their
+/// own name, anything else only if the external opted in to it and with a
+/// prefix, see [`parsing::sphinx::types::SphinxType::namespace`].
+fn reference_key(r: &ExternalSphinxRef, external: &ExternalIndex) -> Option<String> {
+    match r.sphinx_type.namespace() {
+        None => Some(r.name.clone()),
+        Some(namespace) if external.includes(&r.sphinx_type) => {
+            Some(format!("{namespace}:{}", r.name))
+        }
+        Some(_) => None,
     }
 }
 
@@ -196,6 +810,7 @@ mod test {
     use std::path::{Path, PathBuf};
 
     use crate::config::ConfigBuilder;
+    use crate::indexing::object_ref::ReferenceSyntax;
     use crate::render::SSG;
     use crate::render_docs;
 
@@ -333,8 +948,12 @@ async fn render_test_pkg_docs_full() -> Result<()> {
             .with_api_content_path(Some(api_content_path.clone()))
             .with_notebook_content_path(Some(notebook_content_path.clone()))
             .with_site_root(Some(temp_dir.to_path_buf()))
+            .with_manifest_dir(Some(temp_dir.join("manifests")))
             .with_skip_undoc(Some(false))
             .with_skip_private(Some(false))
+            .with_reference_syntax(Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]))
+            .with_inherited_members(Some(true))
+            .with_class_hierarchy(Some(true))
             .with_notebook_path(Some(PathBuf::from("tests/test_notebooks")))
             .with_ssg(Some(crate::render::SSG::Markdown));
         config_builder.exclude_paths(vec![
@@ -377,9 +996,12 @@ async fn render_test_pkg_docs_no_private_no_undoc() -> Result<()> {
             .with_api_content_path(Some(api_content_path.clone()))
             .with_notebook_content_path(Some(notebook_content_path.clone()))
             .with_site_root(Some(temp_dir.to_path_buf()))
+            .with_manifest_dir(Some(temp_dir.join("manifests")))
             .with_skip_undoc(Some(true))
             .with_notebook_path(Some(notebook_path))
             .with_ssg(Some(SSG::Markdown))
+            .with_reference_syntax(Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]))
+            .with_public_api(Some(true))
             .with_skip_private(Some(true));
         config_builder.exclude_paths(vec![
             PathBuf::from("test_pkg/excluded_file.py"),
@@ -437,6 +1059,7 @@ async fn render_test_pkg_docs_exit_on_err() -> Result<()> {
             .with_pkg_path(Some(test_pkg_dir))
             .with_api_content_path(Some(api_content_path))
             .with_site_root(Some(temp_dir.to_path_buf()))
+            .with_manifest_dir(Some(temp_dir.join("manifests")))
             .with_skip_undoc(Some(true))
             .with_notebook_content_path(None)
             .with_notebook_path(None)
@@ -520,4 +1143,516 @@ async fn render_test_pkg_suggests_correct_unknown_refs() -> Result<()> {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn render_docs_only_writes_changes() -> Result<()> {
+        use assert_fs::prelude::*;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py").write_str("'''The package'''")?;
+        pkg.child("a.py").write_str("def f():\n    '''f'''\n")?;
+        pkg.child("b.py").write_str("def g():\n    '''g'''\n")?;
+        let site = temp_dir.child("site");
+        let api = site.child("api");
+
+        let config_builder = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_site_root(Some(site.to_path_buf()))
+            .with_manifest_dir(Some(temp_dir.join("manifests")))
+            .with_api_content_path(Some(PathBuf::from("api")))
+            .with_notebook_path(None)
+            .with_notebook_content_path(None)
+            .with_externals(Some(Default::default()))
+            .with_offline(Some(true))
+            .with_ssg(Some(SSG::Markdown));
+
+        let report = render_docs(config_builder.clone()).await?;
+        assert!(report.written.contains(&api.join("pkg.a.f.md")));
+        assert!(report.removed.is_empty());
+
+        let report = render_docs(config_builder.clone()).await?;
+        assert!(report.written.is_empty());
+        assert!(report.removed.is_empty());
+        assert!(report.unchanged > 0);
+
+        pkg.child("a.py")
+            .write_str("def f():\n    '''f has changed'''\n")?;
+        std::fs::remove_file(pkg.child("b.py").path())?;
+        let report = render_docs(config_builder.clone()).await?;
+        // the module pages list the summaries of their members
+        // and the inventory lists every object
+        assert_eq!(
+            report.written,
+            vec![
+                api.join("pkg.a.f.md"),
+                api.join("pkg.a.md"),
+                api.join("pkg.md"),
+                site.join("objects.inv")
+            ]
+        );
+        assert_eq!(
+            report.removed,
+            vec![api.join("pkg.b.g.md"), api.join("pkg.b.md")]
+        );
+        assert!(!api.child("pkg.b.g.md").exists());
+
+        // outputs that were removed by hand are written again
+        std::fs::remove_file(api.child("pkg.md").path())?;
+        let report = render_docs(config_builder).await?;
+        assert_eq!(report.written, vec![api.join("pkg.md")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rendering_in_parallel_is_deterministic() -> Result<()> {
+        use crate::{load_index, render_pages, thread_pool};
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let render_with_jobs = |jobs| -> Result<crate::RenderedPages> {
+            let mut config_builder = ConfigBuilder::default()
+                .init_with_defaults()
+                .with_pkg_path(Some(PathBuf::from("tests/test_pkg")))
+                .with_site_root(Some(temp_dir.to_path_buf()))
+                .with_notebook_path(Some(PathBuf::from("tests/test_notebooks")))
+                .with_notebook_content_path(Some(PathBuf::from("user-guide")))
+                .with_externals(Some(Default::default()))
+                .with_offline(Some(true))
+                .with_jobs(Some(jobs));
+            config_builder.exclude_paths(vec![
+                PathBuf::from("test_pkg/excluded_file.py"),
+                PathBuf::from("test_pkg/excluded_module"),
+                PathBuf::from("test_pkg/miss_spelled_ref.py"),
+            ]);
+            config_builder.add_external(
+                "numpy".to_string(),
+                Some("numpy".to_string()),
+                "https://numpy.org/doc/stable".to_string(),
+            )?;
+            let config = config_builder.build()?;
+            thread_pool(&config)?.install(|| render_pages(&config, load_index(&config)?))
+        };
+
+        let sequential = render_with_jobs(1)?;
+        assert!(!sequential.is_empty());
+        assert_eq!(render_with_jobs(8)?, sequential);
+
+        Ok(())
+    }
+
+    #[test]
+    fn user_templates_get_the_documented_context() -> Result<()> {
+        use crate::{load_index, render_pages};
+        use assert_fs::prelude::*;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py").write_str("'''The package'''")?;
+        pkg.child("a.py").write_str(
+            "'''Module a'''\n\n\nclass C:\n    '''A class.'''\n\n    def m(self):\n        '''A method.'''\n\n\ndef f(a, /, b: int = 1, *args, c, **kwargs):\n    '''Summary of f.\n\n    More about f.\n    '''\n",
+        )?;
+        let templates = temp_dir.child("templates");
+        templates.child("module.md").write_str(
+            "{{ SNAKEDOWN_NAME }}{% for m in SNAKEDOWN_MEMBERS %} {{ m.kind }}:{{ m.fully_qualified_name }}{% endfor %}\n",
+        )?;
+        templates.child("function.md").write_str(
+            "{{ SNAKEDOWN_SOURCE_PATH }}:{{ SNAKEDOWN_SOURCE_LINE }} {{ SNAKEDOWN_DOCSTRING.summary }}{% for p in SNAKEDOWN_FUNCTION_PARAMETERS %} {{ p.kind }}:{{ p.name }}{% if p.default %}={{ p.default }}{% endif %}{% endfor %}\n",
+        )?;
+        let site = temp_dir.child("site");
+
+        let config = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_site_root(Some(site.to_path_buf()))
+            .with_externals(Some(Default::default()))
+            .with_offline(Some(true))
+            .with_templates_dir(Some(templates.to_path_buf()))
+            .build()?;
+        let pages = render_pages(&config, load_index(&config)?)?;
+        let page = |name: &str| -> String {
+            pages
+                .get(&site.join("api").join(name))
+                .map(|p| String::from_utf8_lossy(p).to_string())
+                .unwrap_or_default()
+        };
+
+        assert_eq!(page("pkg.a.md"), "a class:pkg.a.C function:pkg.a.f");
+        assert_eq!(
+            page("pkg.a.f.md"),
+            "pkg/a.py:11 Summary of f. positional_only:a positional_or_keyword:b=1 var_positional:args keyword_only:c var_keyword:kwargs"
+        );
+        // the class page wasn't overridden
+        assert!(page("pkg.a.C.md").contains("class C"));
+        Ok(())
+    }
+
+    #[test]
+    fn reference_policy_decides_what_happens_to_broken_references() -> Result<()> {
+        use crate::{indexing::object_ref::ReferencePolicy, load_index, render_pages};
+        use assert_fs::prelude::*;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py")
+            .write_str("'''See [[pkg.gone]], [[pkg.missing|the missing one]] and [[pkg]].'''")?;
+        let site = temp_dir.child("site");
+
+        let render_with_policy = |policy| {
+            let config = ConfigBuilder::default()
+                .init_with_defaults()
+                .with_pkg_path(Some(pkg.to_path_buf()))
+                .with_site_root(Some(site.to_path_buf()))
+                .with_externals(Some(Default::default()))
+                .with_offline(Some(true))
+                .with_reference_policy(Some(policy))
+                .build()?;
+            render_pages(&config, load_index(&config)?)
+        };
+
+        let error = render_with_policy(ReferencePolicy::Error)
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        // every broken reference is reported, not just the first
+        assert!(
+            error.starts_with("Found 2 invalid references(s)"),
+            "{error}"
+        );
+        assert!(error.contains("pkg.gone") && error.contains("pkg.missing"));
+
+        for policy in [ReferencePolicy::Warn, ReferencePolicy::Ignore] {
+            let pages = render_with_policy(policy)?;
+            let page = pages
+                .get(&site.join("api").join("pkg.md"))
+                .map(|p| String::from_utf8_lossy(p).to_string())
+                .unwrap_or_default();
+            assert!(
+                page.contains("See pkg.gone, the missing one and [pkg](pkg.md)."),
+                "{page}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_configured_reference_syntaxes_are_expanded() -> Result<()> {
+        use crate::{load_index, render_pages};
+        use assert_fs::prelude::*;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py")
+            .write_str("'''See [the module][pkg.a], [`pkg.a.f`][] and [[pkg.a]].'''")?;
+        pkg.child("a.py")
+            .write_str("'''Module a'''\n\n\ndef f():\n    '''F.'''\n")?;
+        let site = temp_dir.child("site");
+
+        let config = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_site_root(Some(site.to_path_buf()))
+            .with_externals(Some(Default::default()))
+            .with_offline(Some(true))
+            .with_reference_syntax(Some(vec![ReferenceSyntax::Autorefs]))
+            .build()?;
+        let pages = render_pages(&config, load_index(&config)?)?;
+        let page = pages
+            .get(&site.join("api").join("pkg.md"))
+            .map(|p| String::from_utf8_lossy(p).to_string())
+            .unwrap_or_default();
+        assert!(
+            page.contains("See [the module](pkg.a.md), [`pkg.a.f`](pkg.a.f.md) and [[pkg.a]]."),
+            "{page}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn objects_inv_is_served_from_the_site_root() -> Result<()> {
+        use crate::parsing::sphinx::inv_file::parse_objects_inv;
+        use crate::{load_index, render_pages};
+        use assert_fs::prelude::*;
+        use std::io::BufReader;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py").write_str("'''The package'''")?;
+        pkg.child("a.py").write_str(
+            "'''Module a'''\n\n\nclass A:\n    '''A.'''\n\n    def f(self):\n        '''F.'''\n",
+        )?;
+        let site = temp_dir.child("site");
+
+        let config = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_site_root(Some(site.to_path_buf()))
+            .with_externals(Some(Default::default()))
+            .with_offline(Some(true))
+            .with_ssg(Some(SSG::Zola))
+            .build()?;
+        let pages = render_pages(&config, load_index(&config)?)?;
+        let inventory = pages
+            .get(&site.join("static").join("objects.inv"))
+            .ok_or_else(|| eyre!("objects.inv was not rendered"))?;
+        let references = parse_objects_inv(BufReader::new(&inventory[..]))?
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            vec![
+                "pkg py:module 0 api/pkg/ -",
+                "pkg.a py:module 0 api/pkg-a/ -",
+                "pkg.a.A py:class 1 api/pkg-a-a/ -",
+                "pkg.a.A.f py:method 1 api/pkg-a-a-f/ -",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn local_inventories_link_to_the_external_url() -> Result<()> {
+        use crate::config::ExternalIndex;
+        use crate::indexing::object_ref::ReferencePolicy;
+        use crate::{load_index, render_pages};
+        use assert_fs::prelude::*;
+        use std::collections::HashMap;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py")
+            .write_str("'''Returns a [[numpy.ndarray]].'''")?;
+        let site = temp_dir.child("site");
+
+        let inventory = std::fs::canonicalize("tests/sphinx_objects/numpy.inv")?;
+        let config = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_site_root(Some(site.to_path_buf()))
+            .with_externals(Some(HashMap::from([(
+                "numpy".to_string(),
+                ExternalIndex::new(None, "https://numpy.org/doc/stable/".to_string())
+                    .with_inventory(Some(inventory.display().to_string())),
+            )])))
+            .build()?;
+        let pages = render_pages(&config, load_index(&config)?)?;
+        let page = pages
+            .get(&site.join("api").join("pkg.md"))
+            .map(|p| String::from_utf8_lossy(p).to_string())
+            .unwrap_or_default();
+        assert!(
+            page.contains("Returns a [numpy.ndarray](https://numpy.org/doc/stable/reference/generated/numpy.ndarray.html#numpy.ndarray)."),
+            "{page}"
+        );
+
+        // a missing inventory is only skipped if broken references aren't errors
+        let missing = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_externals(Some(HashMap::from([(
+                "numpy".to_string(),
+                ExternalIndex::new(None, "https://numpy.org/doc/stable/".to_string())
+                    .with_inventory(Some(temp_dir.join("gone.inv").display().to_string())),
+            )])))
+            .with_offline(Some(true));
+        let error = load_index(&missing.clone().build()?)
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(
+            error.starts_with("could not read the inventory of numpy"),
+            "{error}"
+        );
+        assert!(
+            load_index(
+                &missing
+                    .with_reference_policy(Some(ReferencePolicy::Warn))
+                    .build()?
+            )
+            .is_ok()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn other_domains_are_only_linked_when_opted_in() -> Result<()> {
+        use crate::config::ExternalIndex;
+        use crate::parsing::sphinx::inv_file::write_objects_inv;
+        use crate::parsing::sphinx::types::ExternalSphinxRef;
+        use crate::{load_index, render_pages};
+        use assert_fs::prelude::*;
+        use std::collections::HashMap;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py").write_str(
+            "'''Takes an :term:`iterable` like [[list]], see [[c:PyObject_GetAttr]].'''",
+        )?;
+        let site = temp_dir.child("site");
+
+        let inventory = temp_dir.join("builtins.inv");
+        let references = [
+            "PyObject_GetAttr c:function 1 c-api/object.html#c.$ -",
+            "iterable std:term -1 glossary.html#term-$ -",
+            "list py:class 1 library/stdtypes.html#$ -",
+            "tut-loops std:label -1 tutorial/controlflow.html#tut-loops More Control Flow",
+        ]
+        .into_iter()
+        .map(ExternalSphinxRef::try_from)
+        .collect::<Result<Vec<_>>>()?;
+        write_objects_inv(
+            std::fs::File::create(&inventory)?,
+            "Python",
+            "3",
+            &references,
+        )?;
+
+        let config = |domains: Vec<String>| {
+            ConfigBuilder::default()
+                .init_with_defaults()
+                .with_pkg_path(Some(pkg.to_path_buf()))
+                .with_site_root(Some(site.to_path_buf()))
+                .with_reference_syntax(Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]))
+                .with_externals(Some(HashMap::from([(
+                    "builtins".to_string(),
+                    ExternalIndex::new(None, "https://docs.python.org/3/".to_string())
+                        .with_inventory(Some(inventory.display().to_string()))
+                        .with_domains(domains),
+                )])))
+                .build()
+        };
+
+        let opted_in = config(vec!["c".to_string(), "std:term".to_string()])?;
+        let index = load_index(&opted_in)?;
+        assert!(!index.external_object_store.contains_key("label:tut-loops"));
+        let pages = render_pages(&opted_in, index)?;
+        let page = pages
+            .get(&site.join("api").join("pkg.md"))
+            .map(|p| String::from_utf8_lossy(p).to_string())
+            .unwrap_or_default();
+        assert!(
+            page.contains("Takes an [iterable](https://docs.python.org/3/glossary.html#term-iterable) like [list](https://docs.python.org/3/library/stdtypes.html#list), see [PyObject_GetAttr](https://docs.python.org/3/c-api/object.html#c.PyObject_GetAttr)."),
+            "{page}"
+        );
+
+        // without opting in only the python objects can be linked
+        let index = load_index(&config(vec![])?)?;
+        let mut known = index.external_object_store.keys().collect::<Vec<_>>();
+        known.sort();
+        assert_eq!(known, vec!["list"]);
+        assert!(render_pages(&config(vec![])?, index).is_err());
+        Ok(())
+    }
+
+    fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+        for entry in WalkDir::new(from) {
+            let entry = entry?;
+            let target = to.join(entry.path().strip_prefix(from)?);
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&target)?;
+            } else {
+                std::fs::copy(entry.path(), &target)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn incremental_builds_match_full_builds() -> Result<()> {
+        use crate::config::ExternalIndex;
+        use assert_fs::prelude::*;
+        use std::{collections::HashMap, fs::read_to_string};
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("test_pkg");
+        copy_dir(Path::new("tests/test_pkg"), pkg.path())?;
+        let inventory = std::fs::canonicalize("tests/sphinx_objects/numpy.inv")?;
+        let config_builder = |site: &str, public_api: bool| -> Result<ConfigBuilder> {
+            let mut config_builder = ConfigBuilder::default()
+                .init_with_defaults()
+                .with_pkg_path(Some(pkg.to_path_buf()))
+                .with_site_root(Some(temp_dir.join(site)))
+                .with_manifest_dir(Some(temp_dir.join("manifests")))
+                .with_notebook_path(Some(PathBuf::from("tests/test_notebooks")))
+                .with_notebook_content_path(Some(PathBuf::from("notebooks")))
+                .with_reference_syntax(Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]))
+                .with_inherited_members(Some(true))
+                .with_class_hierarchy(Some(true))
+                .with_public_api(Some(public_api))
+                .with_skip_private(Some(public_api))
+                // only local inventories, so this doesn't need the network
+                .with_offline(Some(true))
+                .with_externals(Some(HashMap::from([(
+                    "numpy".to_string(),
+                    ExternalIndex::new(
+                        Some("numpy".to_string()),
+                        "https://numpy.org/doc/stable".to_string(),
+                    )
+                    .with_inventory(Some(inventory.display().to_string())),
+                )])));
+            config_builder.exclude_paths(vec![
+                PathBuf::from("test_pkg/excluded_file.py"),
+                PathBuf::from("test_pkg/excluded_module"),
+                PathBuf::from("test_pkg/miss_spelled_ref.py"),
+            ]);
+            Ok(config_builder)
+        };
+        let sites = [("all", false), ("public", true)];
+        for (site, public_api) in sites {
+            render_docs(config_builder(site, public_api)?).await?;
+        }
+
+        let edit = |path: &str, from: &str, to: &str| -> Result<PathBuf> {
+            let file = pkg.child(path);
+            let source = read_to_string(file.path())?;
+            assert!(source.contains(from));
+            file.write_str(&source.replace(from, to))?;
+            Ok(file.path().canonicalize()?)
+        };
+        let bar = pkg.path().canonicalize()?.join("bar.py");
+
+        // nothing other modules can see
+        let one = edit(
+            "sub1/sub2/one.py",
+            "return num % 2 == 0",
+            "return not num % 2",
+        )?;
+        for (n, (site, public_api)) in sites.into_iter().enumerate() {
+            let report = render_docs(config_builder(site, public_api)?).await?;
+            assert!(report.written.is_empty());
+            assert!(report.parsed.contains(&one));
+            if !public_api {
+                assert!(!report.parsed.contains(&bar));
+            }
+
+            let full_site = format!("{site}_full_{n}");
+            render_docs(config_builder(&full_site, public_api)?).await?;
+            assert_dir_trees_equal(temp_dir.join(&full_site), temp_dir.join(site));
+        }
+
+        // a new summary and a method the subclasses of `Greeter` inherit
+        edit(
+            "bar.py",
+            "Greeter class that holds a name and greets.",
+            "Greeter class that holds a name.",
+        )?;
+        edit(
+            "bar.py",
+            "    def greet(self) -> str:\n        \"\"\"\n        Generate a greeting message. This",
+            "    def wave(self) -> None:\n        \"\"\"Wave at the person.\"\"\"\n\n    def greet(self) -> str:\n        \"\"\"\n        Generate a greeting message. This",
+        )?;
+        for (n, (site, public_api)) in sites.into_iter().enumerate() {
+            let report = render_docs(config_builder(site, public_api)?).await?;
+            assert!(report.parsed.contains(&bar));
+            assert!(!report.written.is_empty());
+
+            let full_site = format!("{site}_full_{}", n + sites.len());
+            render_docs(config_builder(&full_site, public_api)?).await?;
+            assert_dir_trees_equal(temp_dir.join(&full_site), temp_dir.join(site));
+        }
+
+        Ok(())
+    }
 }
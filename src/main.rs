@@ -1,12 +1,20 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::{Result, eyre};
-use snakedown::{config::ConfigBuilder, render_docs};
+use snakedown::{
+    config::ConfigBuilder,
+    parsing::sphinx::conf::{discover_sphinx_conf, import_sphinx_conf},
+    render_docs,
+    watch::watch_docs,
+};
 use tracing::subscriber::set_global_default;
 
 mod cli;
 
-use crate::cli::{CliArgs, SubCommand, init::wizard as init_wizard, resolve_runtime_config};
+use crate::cli::{
+    CliArgs, SubCommand, cache::run as run_cache_command, discover_config_file,
+    init::wizard as init_wizard, resolve_runtime_config,
+};
 use clap::Parser;
 
 #[allow(clippy::missing_errors_doc)]
@@ -34,10 +42,52 @@ async fn main() -> Result<()> {
             init_config.to_snakedown_toml(&output_toml_path)?;
         }
 
+        Some(SubCommand::ImportSphinx { conf_path }) => {
+            let output_toml_path = PathBuf::from("snakedown.toml");
+
+            if output_toml_path.exists() {
+                Err(eyre!("`snakedown.toml` already exists, exiting..."))?
+            }
+            let conf_path = conf_path
+                .or_else(|| discover_sphinx_conf(Path::new("")))
+                .ok_or_else(|| eyre!("could not find a `conf.py`, please pass its path"))?;
+            let imported_config = import_sphinx_conf(&conf_path, Path::new(""))?;
+            imported_config.to_snakedown_toml(&output_toml_path)?;
+            println!(
+                "imported {} into {}",
+                conf_path.display(),
+                output_toml_path.display()
+            );
+        }
+
+        Some(SubCommand::Watch) => {
+            let mut config_files: Vec<PathBuf> = discover_config_file(args.config_file.clone())
+                .into_iter()
+                .collect();
+            let pyproject_path = PathBuf::from("pyproject.toml");
+            if pyproject_path.is_file() {
+                config_files.push(pyproject_path);
+            }
+
+            let resolve_config = || {
+                let default_config = ConfigBuilder::default().init_with_defaults();
+                let runtime_config = resolve_runtime_config(&args)?;
+                tracing::debug!("runtime config: {:?}", runtime_config);
+                Ok(default_config.merge(runtime_config))
+            };
+            watch_docs(resolve_config, config_files).await?;
+        }
+
+        Some(SubCommand::Cache { command }) => {
+            let default_config = ConfigBuilder::default().init_with_defaults();
+            let runtime_config = resolve_runtime_config(&args)?;
+            run_cache_command(command, default_config.merge(runtime_config)).await?;
+        }
+
         None => {
             tracing::debug_span!("resolving runtime config");
             let default_config = ConfigBuilder::default().init_with_defaults();
-            let runtime_config = resolve_runtime_config(args)?;
+            let runtime_config = resolve_runtime_config(&args)?;
             tracing::debug!("runtime config: {:?}", runtime_config);
 
             let config_builder = default_config.merge(runtime_config);
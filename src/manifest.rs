@@ -0,0 +1,634 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{create_dir_all, read, read_to_string, remove_file, write},
+    path::{Path, PathBuf, absolute},
+};
+
+use color_eyre::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    RenderedPages,
+    config::{Config, ConfigBuilder},
+    fs::{notebook_sources, package_sources},
+    indexing::external::cache::inventory_path,
+    out_api_path, write_pages,
+};
+
+/// What a previous build was made from and what it wrote, so the next build
+/// can tell what it has to do.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct BuildManifest {
+    /// Hash of the configuration and snakedown version the build was made with
+    pub config_hash: String,
+    /// Hashes of the files every page can depend on, the templates and the
+    /// inventories of the externals
+    pub shared: BTreeMap<PathBuf, String>,
+    /// The modules and notebooks the build was made from
+    pub sources: BTreeMap<PathBuf, SourceEntry>,
+    /// Hashes of all the files the build wrote
+    pub outputs: BTreeMap<PathBuf, String>,
+}
+
+/// What a build knew about a module or notebook, so the next build doesn't
+/// have to parse it again if it didn't change.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct SourceEntry {
+    pub hash: String,
+    /// Hash of what other modules can see of this one, see
+    /// [`crate::indexing::index::RawIndex::interfaces`]
+    #[serde(default)]
+    pub interface: String,
+    /// The modules whose objects the pages of this one use
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub depends_on: BTreeSet<PathBuf>,
+    /// The modules whose summaries the page of this package lists
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub submodules: BTreeSet<PathBuf>,
+    /// The pages rendered from this source
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub pages: BTreeSet<PathBuf>,
+    /// The lines of the `objects.inv` for the objects in this module
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inventory: Vec<String>,
+    /// The classes in this module with their bases in the package,
+    /// for the class hierarchy
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub classes: BTreeMap<String, Vec<String>>,
+}
+
+/// What the next build has to do
+#[derive(Debug, PartialEq, Eq)]
+pub enum BuildPlan {
+    UpToDate,
+    /// Parse and render everything
+    Full,
+    /// Only these sources changed, or lost some of their pages
+    Partial(BTreeSet<PathBuf>),
+}
+
+/// The files a build touched
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BuildReport {
+    pub written: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub unchanged: usize,
+    /// The modules and notebooks that had to be parsed
+    pub parsed: Vec<PathBuf>,
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// There is a manifest for every output location, so projects sharing the
+/// cache, or several sites built from the same project, don't mix them up.
+pub fn manifest_path(config: &Config) -> Result<PathBuf> {
+    let out_path = absolute(out_api_path(config))?;
+    Ok(config
+        .manifest_dir
+        .join(hash_bytes(out_path.display().to_string().as_bytes()))
+        .with_extension("toml"))
+}
+
+/// Everything in the config that can change the output, and the version of snakedown
+pub fn hash_config(config_builder: &ConfigBuilder) -> Result<String> {
+    // tables are sorted, so unlike the hash maps in the builder this is deterministic
+    let value = toml::Value::try_from(config_builder.output_settings())?;
+    let fingerprint = format!(
+        "{}\n{}",
+        env!("CARGO_PKG_VERSION"),
+        toml::to_string(&value)?
+    );
+    Ok(hash_bytes(fingerprint.as_bytes()))
+}
+
+/// Hashes the templates and the external inventories
+pub fn hash_shared(config: &Config, cache_path: &Path) -> Result<BTreeMap<PathBuf, String>> {
+    let mut paths = vec![];
+    if let Some(templates_dir) = &config.templates_dir {
+        for entry in templates_dir.read_dir()? {
+            let path = entry?.path();
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+    }
+    for (key, ext_index) in &config.externals {
+        let inv_path = ext_index
+            .local_inventory()?
+            .unwrap_or_else(|| inventory_path(cache_path, key));
+        if inv_path.exists() {
+            paths.push(inv_path);
+        }
+    }
+    hash_files(paths)
+}
+
+/// Hashes the modules of the package, by the path they are indexed under,
+/// and the notebooks
+pub fn hash_sources(config: &Config) -> Result<BTreeMap<PathBuf, String>> {
+    let mut paths = package_sources(
+        &config.pkg_path.canonicalize()?,
+        config.skip_private && !config.public_api,
+        &config.exclude,
+    )?;
+    if let Some(notebook_path) = &config.notebook_path {
+        paths.extend(notebook_sources(notebook_path)?);
+    }
+    hash_files(paths)
+}
+
+fn hash_files(paths: Vec<PathBuf>) -> Result<BTreeMap<PathBuf, String>> {
+    paths
+        .into_par_iter()
+        .map(|path| {
+            let hash = hash_bytes(&read(&path)?);
+            Ok((path, hash))
+        })
+        .collect()
+}
+
+/// Whether the file at `path` still has the contents with `hash`, as it could
+/// have been changed or removed by something other than us
+fn is_on_disk(path: &Path, hash: Option<&String>) -> bool {
+    hash.is_some_and(|hash| read(path).is_ok_and(|contents| &hash_bytes(&contents) == hash))
+}
+
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "ipynb")
+}
+
+impl BuildManifest {
+    /// Returns `None` if there is no manifest, or it can't be read, in which case
+    /// we just have to build everything.
+    pub fn load(path: &Path) -> Option<Self> {
+        if !path.exists() {
+            return None;
+        }
+        match read_to_string(path)
+            .map_err(color_eyre::Report::from)
+            .and_then(|contents| Ok(toml::from_str(&contents)?))
+        {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                tracing::warn!("ignoring build manifest at {}: {e:#}", path.display());
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Everything has to be built again if the configuration, the templates or the
+    /// inventories changed, or if modules or notebooks were added or removed, because
+    /// that changes what names can refer to. Otherwise only the sources that changed
+    /// and the ones whose pages were changed or removed by something else have to be.
+    pub fn plan(
+        &self,
+        config_hash: &str,
+        shared: &BTreeMap<PathBuf, String>,
+        sources: &BTreeMap<PathBuf, String>,
+    ) -> BuildPlan {
+        if self.config_hash != config_hash
+            || &self.shared != shared
+            || !self.sources.keys().eq(sources.keys())
+        {
+            return BuildPlan::Full;
+        }
+
+        let changed: BTreeSet<PathBuf> = self
+            .sources
+            .iter()
+            .filter(|(path, entry)| {
+                sources.get(*path) != Some(&entry.hash)
+                    || entry
+                        .pages
+                        .iter()
+                        .any(|page| !is_on_disk(page, self.outputs.get(page)))
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        if changed.is_empty()
+            && self
+                .outputs
+                .iter()
+                .all(|(path, hash)| is_on_disk(path, Some(hash)))
+        {
+            BuildPlan::UpToDate
+        } else {
+            BuildPlan::Partial(changed)
+        }
+    }
+
+    /// The sources whose pages can change when what other modules can see of
+    /// the `changed` ones does: the packages listing them and every source that
+    /// depends on them, directly or through other sources.
+    pub fn dependents(&self, changed: &BTreeSet<PathBuf>) -> BTreeSet<PathBuf> {
+        let mut reached = changed.clone();
+        loop {
+            let next: Vec<PathBuf> = self
+                .sources
+                .iter()
+                .filter(|(path, entry)| {
+                    !reached.contains(*path) && !entry.depends_on.is_disjoint(&reached)
+                })
+                .map(|(path, _)| path.clone())
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            reached.extend(next);
+        }
+
+        let listing = self
+            .sources
+            .iter()
+            .filter(|(_, entry)| !entry.submodules.is_disjoint(changed))
+            .map(|(path, _)| path.clone());
+        reached
+            .into_iter()
+            .filter(|path| !changed.contains(path))
+            .chain(listing)
+            .collect()
+    }
+
+    /// Writes the pages whose contents differ from what is on disk and removes the files this manifest wrote that are no longer rendered.
+    /// `kept` are the outputs that weren't rendered again but are still valid.
+    /// Returns the hashes of the new outputs.
+    pub fn apply(
+        &self,
+        pages: &RenderedPages,
+        kept: &BTreeMap<PathBuf, String>,
+    ) -> Result<(BTreeMap<PathBuf, String>, BuildReport)> {
+        let mut report = BuildReport {
+            unchanged: kept.len(),
+            ..Default::default()
+        };
+        let mut outputs = kept.clone();
+        let mut changed = RenderedPages::new();
+
+        for (path, contents) in pages {
+            let hash = hash_bytes(contents);
+            if is_on_disk(path, Some(&hash)) {
+                report.unchanged += 1;
+            } else {
+                changed.insert(path.clone(), contents.clone());
+                report.written.push(path.clone());
+            }
+            outputs.insert(path.clone(), hash);
+        }
+        write_pages(&changed)?;
+
+        for path in self.outputs.keys() {
+            if !outputs.contains_key(path) {
+                if path.exists() {
+                    remove_file(path)?;
+                }
+                report.removed.push(path.clone());
+            }
+        }
+
+        Ok((outputs, report))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        fs::{read_to_string, write},
+        path::PathBuf,
+    };
+
+    use assert_fs::prelude::*;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{BuildManifest, BuildPlan, BuildReport, SourceEntry, hash_bytes, hash_config};
+    use crate::{RenderedPages, config::ConfigBuilder};
+
+    #[test]
+    fn config_hash_is_deterministic() -> Result<()> {
+        let mut config_builder = ConfigBuilder::default().init_with_defaults();
+        for i in 0..10 {
+            config_builder.add_external(format!("ext{i}"), None, format!("https://ext{i}.org"))?;
+        }
+        let hash = hash_config(&config_builder)?;
+        assert_eq!(hash_config(&config_builder.clone())?, hash);
+
+        let changed = config_builder.with_api_content_path(Some("elsewhere".into()));
+        assert_ne!(hash_config(&changed)?, hash);
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_round_trip() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let path = temp_dir.child("manifests").child("site.toml");
+        let manifest = BuildManifest {
+            config_hash: "abc".to_string(),
+            shared: BTreeMap::from([(temp_dir.join("numpy.inv"), hash_bytes(b"z"))]),
+            sources: BTreeMap::from([(
+                temp_dir.join("pkg/__init__.py"),
+                SourceEntry {
+                    hash: hash_bytes(b"x"),
+                    interface: hash_bytes(b"pkg module None"),
+                    depends_on: BTreeSet::from([temp_dir.join("pkg/a.py")]),
+                    submodules: BTreeSet::from([temp_dir.join("pkg/a.py")]),
+                    pages: BTreeSet::from([temp_dir.join("api/pkg.md")]),
+                    inventory: vec!["pkg py:module 0 api/pkg/ -".to_string()],
+                    classes: BTreeMap::from([("pkg.A".to_string(), vec![])]),
+                },
+            )]),
+            outputs: BTreeMap::from([(temp_dir.join("api/pkg.md"), hash_bytes(b"y"))]),
+        };
+        manifest.save(path.path())?;
+
+        assert_eq!(BuildManifest::load(path.path()), Some(manifest));
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_manifest_is_ignored() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let path = temp_dir.child("manifest.toml");
+        path.write_str("this is not a manifest")?;
+
+        assert_eq!(BuildManifest::load(path.path()), None);
+        assert_eq!(BuildManifest::load(&temp_dir.join("missing.toml")), None);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_only_writes_changes() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let kept = temp_dir.join("api/pkg.kept.md");
+        let changed = temp_dir.join("api/pkg.changed.md");
+        let added = temp_dir.join("api/pkg.added.md");
+        let stale = temp_dir.join("api/pkg.stale.md");
+
+        let first = RenderedPages::from([
+            (kept.clone(), b"kept".to_vec()),
+            (changed.clone(), b"before".to_vec()),
+            (stale.clone(), b"stale".to_vec()),
+        ]);
+        let (outputs, report) = BuildManifest::default().apply(&first, &BTreeMap::new())?;
+        assert_eq!(report.written.len(), 3);
+        let manifest = BuildManifest {
+            outputs,
+            ..Default::default()
+        };
+
+        let second = RenderedPages::from([
+            (kept.clone(), b"kept".to_vec()),
+            (changed.clone(), b"after".to_vec()),
+            (added.clone(), b"added".to_vec()),
+        ]);
+        let (_, report) = manifest.apply(&second, &BTreeMap::new())?;
+
+        assert_eq!(
+            report,
+            BuildReport {
+                written: vec![added.clone(), changed.clone()],
+                removed: vec![stale.clone()],
+                unchanged: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(read_to_string(&changed)?, "after");
+        assert_eq!(read_to_string(&added)?, "added");
+        assert!(!stale.exists());
+
+        // pages changed by something else are put right, even though the
+        // manifest says they are up to date
+        write(&kept, "edited by hand")?;
+        let (_, report) = manifest.apply(&second, &BTreeMap::new())?;
+        assert_eq!(report.written, vec![kept.clone()]);
+        assert_eq!(read_to_string(&kept)?, "kept");
+        Ok(())
+    }
+
+    #[test]
+    fn plan_only_rebuilds_what_changed() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let module = temp_dir.join("pkg/a.py");
+        let output = temp_dir.child("api").child("pkg.a.md");
+        output.write_str("pkg.a")?;
+        let inventory = temp_dir.child("objects.inv");
+        inventory.write_str("inventory")?;
+        let manifest = BuildManifest {
+            config_hash: "abc".to_string(),
+            shared: BTreeMap::new(),
+            sources: BTreeMap::from([(
+                module.clone(),
+                SourceEntry {
+                    hash: hash_bytes(b"a"),
+                    pages: BTreeSet::from([output.to_path_buf()]),
+                    ..Default::default()
+                },
+            )]),
+            outputs: BTreeMap::from([
+                (output.to_path_buf(), hash_bytes(b"pkg.a")),
+                (inventory.to_path_buf(), hash_bytes(b"inventory")),
+            ]),
+        };
+        let sources = BTreeMap::from([(module.clone(), hash_bytes(b"a"))]);
+
+        assert_eq!(
+            manifest.plan("abc", &BTreeMap::new(), &sources),
+            BuildPlan::UpToDate
+        );
+        assert_eq!(
+            manifest.plan("def", &BTreeMap::new(), &sources),
+            BuildPlan::Full
+        );
+        let templates = BTreeMap::from([(temp_dir.join("module.md"), hash_bytes(b"t"))]);
+        assert_eq!(manifest.plan("abc", &templates, &sources), BuildPlan::Full);
+        let added = BTreeMap::from([
+            (module.clone(), hash_bytes(b"a")),
+            (temp_dir.join("pkg/b.py"), hash_bytes(b"b")),
+        ]);
+        assert_eq!(
+            manifest.plan("abc", &BTreeMap::new(), &added),
+            BuildPlan::Full
+        );
+
+        let changed = BTreeMap::from([(module.clone(), hash_bytes(b"changed"))]);
+        assert_eq!(
+            manifest.plan("abc", &BTreeMap::new(), &changed),
+            BuildPlan::Partial(BTreeSet::from([module.clone()]))
+        );
+
+        // outputs that were changed or removed by hand are written again
+        output.write_str("edited by hand")?;
+        assert_eq!(
+            manifest.plan("abc", &BTreeMap::new(), &sources),
+            BuildPlan::Partial(BTreeSet::from([module.clone()]))
+        );
+        output.write_str("pkg.a")?;
+        std::fs::remove_file(inventory.path())?;
+        assert_eq!(
+            manifest.plan("abc", &BTreeMap::new(), &sources),
+            BuildPlan::Partial(BTreeSet::new())
+        );
+        std::fs::remove_file(output.path())?;
+        assert_eq!(
+            manifest.plan("abc", &BTreeMap::new(), &sources),
+            BuildPlan::Partial(BTreeSet::from([module]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dependents_are_found_through_other_modules() {
+        let source = |depends_on: &[&str], submodules: &[&str]| SourceEntry {
+            depends_on: depends_on.iter().map(PathBuf::from).collect(),
+            submodules: submodules.iter().map(PathBuf::from).collect(),
+            ..Default::default()
+        };
+        let manifest = BuildManifest {
+            sources: BTreeMap::from([
+                (
+                    PathBuf::from("__init__.py"),
+                    source(&[], &["a.py", "b.py", "c.py", "d.py"]),
+                ),
+                (PathBuf::from("a.py"), source(&["b.py"], &[])),
+                (PathBuf::from("b.py"), source(&["c.py"], &[])),
+                (PathBuf::from("c.py"), source(&[], &[])),
+                (PathBuf::from("d.py"), source(&["__init__.py"], &[])),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            manifest.dependents(&BTreeSet::from([PathBuf::from("c.py")])),
+            BTreeSet::from([
+                PathBuf::from("__init__.py"),
+                PathBuf::from("a.py"),
+                PathBuf::from("b.py"),
+            ])
+        );
+        // the package only lists the summaries of its modules,
+        // so the modules using it don't depend on them
+        assert_eq!(
+            manifest.dependents(&BTreeSet::from([PathBuf::from("a.py")])),
+            BTreeSet::from([PathBuf::from("__init__.py")])
+        );
+    }
+
+    #[test]
+    fn dependents_of_nested_packages_and_cycles() {
+        let source = |depends_on: &[&str], submodules: &[&str]| SourceEntry {
+            depends_on: depends_on.iter().map(PathBuf::from).collect(),
+            submodules: submodules.iter().map(PathBuf::from).collect(),
+            ..Default::default()
+        };
+        let manifest = BuildManifest {
+            sources: BTreeMap::from([
+                (
+                    PathBuf::from("__init__.py"),
+                    source(&[], &["sub/__init__.py"]),
+                ),
+                (
+                    PathBuf::from("sub/__init__.py"),
+                    source(&[], &["sub/a.py", "sub/b.py"]),
+                ),
+                (PathBuf::from("sub/a.py"), source(&["sub/b.py"], &[])),
+                (PathBuf::from("sub/b.py"), source(&["sub/a.py"], &[])),
+                (PathBuf::from("c.py"), source(&["sub/a.py"], &[])),
+            ]),
+            ..Default::default()
+        };
+
+        // only the package directly listing a module shows its summary
+        assert_eq!(
+            manifest.dependents(&BTreeSet::from([PathBuf::from("sub/b.py")])),
+            BTreeSet::from([
+                PathBuf::from("c.py"),
+                PathBuf::from("sub/__init__.py"),
+                PathBuf::from("sub/a.py"),
+            ])
+        );
+        assert_eq!(
+            manifest.dependents(&BTreeSet::from([PathBuf::from("sub/__init__.py")])),
+            BTreeSet::from([PathBuf::from("__init__.py")])
+        );
+        assert_eq!(
+            manifest.dependents(&BTreeSet::from([PathBuf::from("c.py")])),
+            BTreeSet::new()
+        );
+    }
+
+    #[test]
+    fn config_hash_ignores_how_the_docs_are_built() -> Result<()> {
+        let config_builder = ConfigBuilder::default().init_with_defaults();
+        let hash = hash_config(&config_builder)?;
+
+        let built_differently = config_builder
+            .clone()
+            .with_jobs(Some(4))
+            .with_offline(Some(true))
+            .with_cache_ttl(Some(1))
+            .with_manifest_dir(Some(PathBuf::from("manifests")));
+        assert_eq!(hash_config(&built_differently)?, hash);
+        Ok(())
+    }
+
+    #[test]
+    fn local_inventories_are_shared() -> Result<()> {
+        use super::hash_shared;
+        use crate::config::ExternalIndex;
+        use std::collections::HashMap;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py").write_str("'''The package'''")?;
+        let inventory = temp_dir.child("numpy.inv");
+        inventory.write_str("not really an inventory")?;
+
+        let config = ConfigBuilder::default()
+            .init_with_defaults()
+            .with_pkg_path(Some(pkg.to_path_buf()))
+            .with_externals(Some(HashMap::from([(
+                "numpy".to_string(),
+                ExternalIndex::new(None, "https://numpy.org/doc/stable/".to_string())
+                    .with_inventory(Some(inventory.display().to_string())),
+            )])))
+            .build()?;
+        let shared = hash_shared(&config, &temp_dir.join("cache"))?;
+        assert!(shared.contains_key(inventory.path()));
+        Ok(())
+    }
+
+    #[test]
+    fn manifests_are_kept_in_the_manifest_dir() -> Result<()> {
+        use super::manifest_path;
+
+        let temp_dir = assert_fs::TempDir::new()?;
+        let manifest_dir = temp_dir.join("manifests");
+        let site = |site_root: &str| {
+            ConfigBuilder::default()
+                .init_with_defaults()
+                .with_site_root(Some(temp_dir.join(site_root)))
+                .with_manifest_dir(Some(manifest_dir.clone()))
+                .build()
+        };
+
+        let path = manifest_path(&site("site")?)?;
+        assert!(path.starts_with(&manifest_dir));
+        assert_ne!(path, manifest_path(&site("other_site")?)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+use lazy_regex::regex_captures;
+
+use super::{
+    Docstring, DocstringParameter, DocstringRaise, DocstringReturn, SectionKind, dedent, indent_of,
+    join_description, split_items,
+};
+
+/// Google style sections look like `Args:` on a line by themselves at the
+/// same indentation as the rest of the docstring.
+pub(crate) fn section_title(line: &str) -> Option<(String, SectionKind)> {
+    if indent_of(line) != 0 {
+        return None;
+    }
+    let (_, title) = regex_captures!(r"^([A-Za-z][A-Za-z ]*):\s*$", line)?;
+    SectionKind::from_title(title).map(|kind| (title.to_string(), kind))
+}
+
+pub fn parse_google_docstring(docstring: &str) -> Docstring {
+    let mut out = Docstring::default();
+    let lines: Vec<&str> = docstring.lines().collect();
+
+    let mut preamble = vec![];
+    let mut sections: Vec<(String, SectionKind, Vec<&str>)> = vec![];
+
+    for line in lines {
+        if let Some((title, kind)) = section_title(line) {
+            sections.push((title, kind, vec![]));
+        } else if let Some((_, _, body)) = sections.last_mut() {
+            body.push(line);
+        } else {
+            preamble.push(line);
+        }
+    }
+
+    out.set_preamble(&preamble);
+
+    for (title, kind, body) in sections {
+        let body = dedent(&body);
+        match kind {
+            SectionKind::Parameters => out.parameters.extend(parse_parameters(&body)),
+            SectionKind::Attributes => out.attributes.extend(parse_parameters(&body)),
+            SectionKind::Returns => out.returns.extend(parse_return(&body)),
+            SectionKind::Yields => out.yields.extend(parse_return(&body)),
+            SectionKind::Raises => {
+                out.raises
+                    .extend(split_items(&body).into_iter().map(|(head, rest)| {
+                        let (exception, first) = match head.split_once(':') {
+                            Some((exc, desc)) => (exc.trim().to_string(), desc.trim().to_string()),
+                            None => (head.trim().to_string(), String::new()),
+                        };
+                        let mut desc_lines = vec![first];
+                        desc_lines.extend(rest);
+                        DocstringRaise {
+                            exception,
+                            description: join_description(&desc_lines),
+                        }
+                    }));
+            }
+            SectionKind::Examples
+            | SectionKind::SeeAlso
+            | SectionKind::Notes
+            | SectionKind::Warnings
+            | SectionKind::Other => out.set_text_section(kind, &title, body.join("\n")),
+        }
+    }
+
+    out
+}
+
+fn parse_parameters(body: &[String]) -> Vec<DocstringParameter> {
+    split_items(body)
+        .into_iter()
+        .map(|(head, rest)| parse_parameter(&head, &rest))
+        .collect()
+}
+
+fn parse_parameter(head: &str, rest: &[String]) -> DocstringParameter {
+    let (name, type_annotation, first) = if let Some((_, name, ty, desc)) =
+        regex_captures!(r"^(\*{0,2}[\w\.]+)\s*\(([^)]*)\)\s*:\s*(.*)$", head)
+    {
+        (
+            name.to_string(),
+            Some(ty.trim().to_string()),
+            desc.to_string(),
+        )
+    } else if let Some((name, desc)) = head.split_once(':') {
+        (name.trim().to_string(), None, desc.trim().to_string())
+    } else {
+        (head.trim().to_string(), None, String::new())
+    };
+
+    let mut desc_lines = vec![first];
+    desc_lines.extend(rest.iter().cloned());
+
+    DocstringParameter {
+        name,
+        type_annotation: type_annotation.filter(|t| !t.is_empty()),
+        description: join_description(&desc_lines),
+        default: None,
+    }
+}
+
+/// Google style only documents a single return value of the form
+/// `type: description` where the type is optional.
+fn parse_return(body: &[String]) -> Option<DocstringReturn> {
+    let first_idx = body.iter().position(|l| !l.trim().is_empty())?;
+    let first = body[first_idx].trim();
+    let (type_annotation, first_desc) = match first.split_once(':') {
+        Some((ty, desc)) if looks_like_type(ty) => {
+            (Some(ty.trim().to_string()), desc.trim().to_string())
+        }
+        _ => (None, first.to_string()),
+    };
+
+    let mut desc_lines = vec![first_desc];
+    desc_lines.extend(body[first_idx + 1..].iter().cloned());
+
+    Some(DocstringReturn {
+        name: None,
+        type_annotation,
+        description: join_description(&desc_lines),
+    })
+}
+
+/// A type annotation does not contain any whitespace, except
+/// in between brackets, e.g. `Dict[str, int]`
+pub(crate) fn looks_like_type(text: &str) -> bool {
+    let text = text.trim();
+    if text.is_empty() {
+        return false;
+    }
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => return false,
+            _ => (),
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn google_docstring() -> &'static str {
+        r#"Return the sum of two numbers.
+
+This is a longer description
+that spans multiple lines.
+
+Args:
+    a (float): First number.
+    b (Dict[str, int]): Second number, with a description
+        that continues on the next line.
+    *args: Extra arguments.
+
+Returns:
+    float: Sum of a and b.
+
+Raises:
+    ValueError: If the numbers are too large.
+
+Example:
+    >>> add(2.5, 4.5)
+    7.0
+
+Attributes:
+    name (str): Not a parameter.
+
+Todo:
+    * Subtract as well."#
+    }
+
+    #[test]
+    fn parse_google_summary_and_description() {
+        let parsed = parse_google_docstring(google_docstring());
+        assert_eq!(
+            parsed.summary,
+            Some("Return the sum of two numbers.".to_string())
+        );
+        assert_eq!(
+            parsed.description,
+            Some("This is a longer description\nthat spans multiple lines.".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_google_parameters() {
+        let parsed = parse_google_docstring(google_docstring());
+        assert_eq!(
+            parsed.parameters,
+            vec![
+                DocstringParameter {
+                    name: "a".to_string(),
+                    type_annotation: Some("float".to_string()),
+                    description: Some("First number.".to_string()),
+                    default: None,
+                },
+                DocstringParameter {
+                    name: "b".to_string(),
+                    type_annotation: Some("Dict[str, int]".to_string()),
+                    description: Some(
+                        "Second number, with a description that continues on the next line."
+                            .to_string()
+                    ),
+                    default: None,
+                },
+                DocstringParameter {
+                    name: "*args".to_string(),
+                    type_annotation: None,
+                    description: Some("Extra arguments.".to_string()),
+                    default: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_google_returns_raises_and_examples() {
+        let parsed = parse_google_docstring(google_docstring());
+        assert_eq!(
+            parsed.returns,
+            vec![DocstringReturn {
+                name: None,
+                type_annotation: Some("float".to_string()),
+                description: Some("Sum of a and b.".to_string()),
+            }]
+        );
+        assert_eq!(
+            parsed.raises,
+            vec![DocstringRaise {
+                exception: "ValueError".to_string(),
+                description: Some("If the numbers are too large.".to_string()),
+            }]
+        );
+        assert_eq!(parsed.examples, Some(">>> add(2.5, 4.5)\n7.0".to_string()));
+        assert_eq!(
+            parsed.attributes,
+            vec![DocstringParameter {
+                name: "name".to_string(),
+                type_annotation: Some("str".to_string()),
+                description: Some("Not a parameter.".to_string()),
+                default: None,
+            }]
+        );
+        assert_eq!(parsed.other_sections.len(), 1);
+        assert_eq!(parsed.other_sections[0].title, "Todo");
+    }
+
+    #[test]
+    fn parse_google_return_without_type() {
+        let parsed = parse_google_docstring("Summary.\n\nReturns:\n    Sum of a and b.");
+        assert_eq!(
+            parsed.returns,
+            vec![DocstringReturn {
+                name: None,
+                type_annotation: None,
+                description: Some("Sum of a and b.".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn lowercase_section_titles() {
+        let parsed = parse_google_docstring("Summary.\n\nsee also:\n    [[foo.bar]]");
+        assert_eq!(parsed.see_also, Some("[[foo.bar]]".to_string()));
+    }
+}
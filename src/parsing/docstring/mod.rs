@@ -0,0 +1,335 @@
+pub mod google;
+pub mod numpy;
+pub mod rest;
+
+use clap::ValueEnum;
+use lazy_regex::regex_is_match;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+#[derive(
+    Clone, Copy, Debug, Default, Display, ValueEnum, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum DocstringStyle {
+    /// Try to determine the style from the contents of each docstring
+    #[default]
+    Auto,
+    Numpy,
+    Google,
+    /// Sphinx style field lists e.g. `:param foo: bar`
+    Rest,
+    /// Don't parse any sections, only split off the summary
+    Plain,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Docstring {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub parameters: Vec<DocstringParameter>,
+    pub returns: Vec<DocstringReturn>,
+    pub yields: Vec<DocstringReturn>,
+    pub raises: Vec<DocstringRaise>,
+    pub attributes: Vec<DocstringParameter>,
+    pub examples: Option<String>,
+    pub see_also: Option<String>,
+    pub notes: Option<String>,
+    pub warnings: Option<String>,
+    /// Sections we don't attach any meaning to (e.g. `Methods` or `Todo`)
+    /// they are kept in the order they were found in.
+    pub other_sections: Vec<DocstringSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocstringParameter {
+    pub name: String,
+    pub type_annotation: Option<String>,
+    pub description: Option<String>,
+    /// The default from the signature, docstrings don't set this
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocstringReturn {
+    pub name: Option<String>,
+    pub type_annotation: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocstringRaise {
+    pub exception: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocstringSection {
+    pub title: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SectionKind {
+    Parameters,
+    Returns,
+    Yields,
+    Raises,
+    Attributes,
+    Examples,
+    SeeAlso,
+    Notes,
+    Warnings,
+    Other,
+}
+
+impl SectionKind {
+    pub(crate) fn from_title(title: &str) -> Option<Self> {
+        match title.trim().to_lowercase().as_str() {
+            "args" | "arguments" | "parameters" | "params" | "keyword args"
+            | "keyword arguments" | "other parameters" => Some(SectionKind::Parameters),
+            "returns" | "return" => Some(SectionKind::Returns),
+            "yields" | "yield" => Some(SectionKind::Yields),
+            "raises" | "raise" | "exceptions" => Some(SectionKind::Raises),
+            "attributes" => Some(SectionKind::Attributes),
+            "example" | "examples" => Some(SectionKind::Examples),
+            "see also" => Some(SectionKind::SeeAlso),
+            "note" | "notes" => Some(SectionKind::Notes),
+            "warning" | "warnings" => Some(SectionKind::Warnings),
+            "methods" | "references" | "todo" | "warns" | "receives" | "attention" | "caution"
+            | "danger" | "hint" | "important" | "tip" => Some(SectionKind::Other),
+            _ => None,
+        }
+    }
+}
+
+impl Docstring {
+    /// Fills in the summary and extended description from the text
+    /// that precedes the first section of a docstring.
+    pub(crate) fn set_preamble(&mut self, lines: &[&str]) {
+        let text = lines.join("\n");
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        match text.split_once("\n\n") {
+            Some((summary, description)) => {
+                self.summary = Some(summary.trim().to_string());
+                let description = description.trim();
+                if !description.is_empty() {
+                    self.description = Some(description.to_string());
+                }
+            }
+            None => self.summary = Some(text.to_string()),
+        }
+    }
+
+    /// Stores the content of a section that does not contain a list of
+    /// items, such as `Notes` or `Examples`
+    pub(crate) fn set_text_section(&mut self, kind: SectionKind, title: &str, content: String) {
+        let content = content.trim().to_string();
+        if content.is_empty() {
+            return;
+        }
+        let slot = match kind {
+            SectionKind::Examples => &mut self.examples,
+            SectionKind::SeeAlso => &mut self.see_also,
+            SectionKind::Notes => &mut self.notes,
+            SectionKind::Warnings => &mut self.warnings,
+            SectionKind::Parameters
+            | SectionKind::Returns
+            | SectionKind::Yields
+            | SectionKind::Raises
+            | SectionKind::Attributes
+            | SectionKind::Other => {
+                self.other_sections.push(DocstringSection {
+                    title: title.trim().to_string(),
+                    content,
+                });
+                return;
+            }
+        };
+        match slot {
+            Some(existing) => {
+                existing.push_str("\n\n");
+                existing.push_str(&content);
+            }
+            None => *slot = Some(content),
+        }
+    }
+}
+
+/// Parse an already dedented docstring into it's sections.
+pub fn parse_docstring(docstring: &str, style: DocstringStyle) -> Docstring {
+    let style = match style {
+        DocstringStyle::Auto => detect_docstring_style(docstring),
+        s => s,
+    };
+
+    match style {
+        DocstringStyle::Numpy => numpy::parse_numpy_docstring(docstring),
+        DocstringStyle::Google => google::parse_google_docstring(docstring),
+        DocstringStyle::Rest => rest::parse_rest_docstring(docstring),
+        DocstringStyle::Plain | DocstringStyle::Auto => {
+            let mut out = Docstring::default();
+            out.set_preamble(&docstring.lines().collect::<Vec<_>>());
+            out
+        }
+    }
+}
+
+pub fn detect_docstring_style(docstring: &str) -> DocstringStyle {
+    let lines: Vec<&str> = docstring.lines().collect();
+
+    if lines
+        .windows(2)
+        .any(|w| numpy::is_section_header(w[0], w[1]))
+    {
+        return DocstringStyle::Numpy;
+    }
+
+    if lines
+        .iter()
+        .any(|l| regex_is_match!(r"^\s*:(param|parameter|arg|argument|key|keyword|type|ivar|cvar|var|vartype|returns?|rtype|raises?|yields?|ytype)\b[^:]*:", l))
+    {
+        return DocstringStyle::Rest;
+    }
+
+    if lines.iter().any(|l| google::section_title(l).is_some()) {
+        return DocstringStyle::Google;
+    }
+
+    DocstringStyle::Plain
+}
+
+/// The number of leading spaces and tabs. Other whitespace is counted as
+/// content, so the result is always safe to slice at.
+pub(crate) fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+/// Strip the common leading whitespace of all non-empty lines
+pub(crate) fn dedent(lines: &[&str]) -> Vec<String> {
+    let common = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| indent_of(l))
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|l| {
+            if l.trim().is_empty() {
+                String::new()
+            } else {
+                l[common..].trim_end().to_string()
+            }
+        })
+        .collect()
+}
+
+/// Joins the (continuation) lines of an item description into a single string
+/// preserving paragraph breaks.
+pub(crate) fn join_description(lines: &[String]) -> Option<String> {
+    let text = lines
+        .split(|l| l.trim().is_empty())
+        .map(|paragraph| {
+            paragraph
+                .iter()
+                .map(|l| l.trim())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    if text.is_empty() { None } else { Some(text) }
+}
+
+/// Splits a list of lines into items, where each item starts with a line
+/// at the base indentation level and any more indented lines are continuations
+pub(crate) fn split_items(lines: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut items: Vec<(String, Vec<String>)> = vec![];
+    for line in lines {
+        if line.trim().is_empty() {
+            if let Some((_, continuation)) = items.last_mut() {
+                continuation.push(String::new());
+            }
+        } else if indent_of(line) == 0 || items.is_empty() {
+            items.push((line.trim().to_string(), vec![]));
+        } else if let Some((_, continuation)) = items.last_mut() {
+            continuation.push(line.clone());
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn detect_numpy() {
+        let docstring = "Summary.\n\nParameters\n----------\nx : int\n    The x.";
+        assert_eq!(detect_docstring_style(docstring), DocstringStyle::Numpy);
+    }
+
+    #[test]
+    fn detect_google() {
+        let docstring = "Summary.\n\nArgs:\n    x (int): The x.";
+        assert_eq!(detect_docstring_style(docstring), DocstringStyle::Google);
+    }
+
+    #[test]
+    fn detect_rest() {
+        let docstring = "Summary.\n\n:param x: The x.\n:type x: int";
+        assert_eq!(detect_docstring_style(docstring), DocstringStyle::Rest);
+    }
+
+    #[test]
+    fn detect_plain() {
+        let docstring = "Summary.\n\nJust some more text: nothing special here.";
+        assert_eq!(detect_docstring_style(docstring), DocstringStyle::Plain);
+    }
+
+    #[test]
+    fn plain_summary_and_description() {
+        let parsed = parse_docstring(
+            "Summary line.\n\nMore text\nover two lines.\n\nAnd another paragraph.",
+            DocstringStyle::Plain,
+        );
+        assert_eq!(parsed.summary, Some("Summary line.".to_string()));
+        assert_eq!(
+            parsed.description,
+            Some("More text\nover two lines.\n\nAnd another paragraph.".to_string())
+        );
+        assert!(parsed.parameters.is_empty());
+    }
+
+    #[test]
+    fn forced_style_overrides_detection() {
+        let docstring = "Summary.\n\nArgs:\n    x (int): The x.";
+        let parsed = parse_docstring(docstring, DocstringStyle::Plain);
+        assert!(parsed.parameters.is_empty());
+        assert_eq!(
+            parsed.description,
+            Some("Args:\n    x (int): The x.".to_string())
+        );
+    }
+
+    #[test]
+    fn dedent_keeps_unicode_whitespace() {
+        let lines = [" x (int): The x.", "\u{3000}y"];
+        assert_eq!(dedent(&lines), vec![" x (int): The x.", "\u{3000}y"]);
+
+        let lines = ["    x (int): The x.", "     y", "    \u{3000}z"];
+        assert_eq!(dedent(&lines), vec!["x (int): The x.", " y", "\u{3000}z"]);
+
+        let docstring = "Summary.\n\nArgs:\n x (int): The x.\n\u{3000}More.";
+        let parsed = parse_docstring(docstring, DocstringStyle::Google);
+        assert_eq!(parsed.parameters[0].name, "x");
+        assert_eq!(parsed.parameters[0].description, Some("The x.".to_string()));
+    }
+}
@@ -0,0 +1,238 @@
+use super::{
+    Docstring, DocstringParameter, DocstringRaise, DocstringReturn, SectionKind, dedent, indent_of,
+    join_description, split_items,
+};
+
+/// Numpy style sections are a title underlined with dashes e.g.
+/// ```text
+/// Parameters
+/// ----------
+/// ```
+pub(crate) fn is_section_header(title: &str, underline: &str) -> bool {
+    let underline = underline.trim();
+    !title.trim().is_empty()
+        && indent_of(title) == 0
+        && underline.len() >= 3
+        && underline.chars().all(|c| c == '-')
+        && SectionKind::from_title(title).is_some()
+}
+
+pub fn parse_numpy_docstring(docstring: &str) -> Docstring {
+    let mut out = Docstring::default();
+    let lines: Vec<&str> = docstring.lines().collect();
+
+    let mut preamble = vec![];
+    let mut sections: Vec<(String, SectionKind, Vec<&str>)> = vec![];
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        let next = lines.get(idx + 1).copied().unwrap_or_default();
+        if is_section_header(line, next)
+            && let Some(kind) = SectionKind::from_title(line)
+        {
+            sections.push((line.trim().to_string(), kind, vec![]));
+            // skip the underline as well
+            idx += 2;
+            continue;
+        }
+        if let Some((_, _, body)) = sections.last_mut() {
+            body.push(line);
+        } else {
+            preamble.push(line);
+        }
+        idx += 1;
+    }
+
+    out.set_preamble(&preamble);
+
+    for (title, kind, body) in sections {
+        let body = dedent(&body);
+        match kind {
+            SectionKind::Parameters => out.parameters.extend(parse_parameters(&body)),
+            SectionKind::Attributes => out.attributes.extend(parse_parameters(&body)),
+            SectionKind::Returns => out.returns.extend(parse_returns(&body)),
+            SectionKind::Yields => out.yields.extend(parse_returns(&body)),
+            SectionKind::Raises => {
+                out.raises
+                    .extend(
+                        split_items(&body)
+                            .into_iter()
+                            .map(|(head, rest)| DocstringRaise {
+                                exception: head,
+                                description: join_description(&rest),
+                            }),
+                    );
+            }
+            SectionKind::Examples
+            | SectionKind::SeeAlso
+            | SectionKind::Notes
+            | SectionKind::Warnings
+            | SectionKind::Other => out.set_text_section(kind, &title, body.join("\n")),
+        }
+    }
+
+    out
+}
+
+fn parse_parameters(body: &[String]) -> Vec<DocstringParameter> {
+    split_items(body)
+        .into_iter()
+        .map(|(head, rest)| {
+            let (name, type_annotation) = split_name_and_type(&head);
+            DocstringParameter {
+                name,
+                type_annotation,
+                description: join_description(&rest),
+                default: None,
+            }
+        })
+        .collect()
+}
+
+/// Numpy style return values may be named (`name : type`) or
+/// only contain a type.
+fn parse_returns(body: &[String]) -> Vec<DocstringReturn> {
+    split_items(body)
+        .into_iter()
+        .map(|(head, rest)| {
+            let (name, type_annotation) = match head.split_once(" : ") {
+                Some((name, ty)) => (Some(name.trim().to_string()), Some(ty.trim().to_string())),
+                None => (None, Some(head.trim().to_string())),
+            };
+            DocstringReturn {
+                name,
+                type_annotation,
+                description: join_description(&rest),
+            }
+        })
+        .collect()
+}
+
+fn split_name_and_type(head: &str) -> (String, Option<String>) {
+    match head.split_once(':') {
+        Some((name, ty)) if !ty.trim().is_empty() => {
+            (name.trim().to_string(), Some(ty.trim().to_string()))
+        }
+        Some((name, _)) => (name.trim().to_string(), None),
+        None => (head.trim().to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn numpy_docstring() -> &'static str {
+        r#"Determine whether a number is odd.
+
+Parameters
+----------
+i : int
+    The number to check.
+strict : bool, optional
+    Whether to be strict about it.
+    Defaults to False.
+
+Returns
+-------
+bool
+    True iff input number is odd
+
+Raises
+------
+TypeError
+    If `i` is not an integer.
+
+See Also
+--------
+is_even : the opposite.
+
+Examples
+--------
+>>> is_odd(3)
+True"#
+    }
+
+    #[test]
+    fn parse_numpy_parameters() {
+        let parsed = parse_numpy_docstring(numpy_docstring());
+        assert_eq!(
+            parsed.summary,
+            Some("Determine whether a number is odd.".to_string())
+        );
+        assert_eq!(parsed.description, None);
+        assert_eq!(
+            parsed.parameters,
+            vec![
+                DocstringParameter {
+                    name: "i".to_string(),
+                    type_annotation: Some("int".to_string()),
+                    description: Some("The number to check.".to_string()),
+                    default: None,
+                },
+                DocstringParameter {
+                    name: "strict".to_string(),
+                    type_annotation: Some("bool, optional".to_string()),
+                    description: Some(
+                        "Whether to be strict about it. Defaults to False.".to_string()
+                    ),
+                    default: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_numpy_returns_and_raises() {
+        let parsed = parse_numpy_docstring(numpy_docstring());
+        assert_eq!(
+            parsed.returns,
+            vec![DocstringReturn {
+                name: None,
+                type_annotation: Some("bool".to_string()),
+                description: Some("True iff input number is odd".to_string()),
+            }]
+        );
+        assert_eq!(
+            parsed.raises,
+            vec![DocstringRaise {
+                exception: "TypeError".to_string(),
+                description: Some("If `i` is not an integer.".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_numpy_text_sections() {
+        let parsed = parse_numpy_docstring(numpy_docstring());
+        assert_eq!(parsed.see_also, Some("is_even : the opposite.".to_string()));
+        assert_eq!(parsed.examples, Some(">>> is_odd(3)\nTrue".to_string()));
+    }
+
+    #[test]
+    fn parse_numpy_attributes() {
+        let parsed = parse_numpy_docstring(
+            "Summary.\n\nAttributes\n----------\ncount : int\n    The current count.",
+        );
+        assert_eq!(
+            parsed.attributes,
+            vec![DocstringParameter {
+                name: "count".to_string(),
+                type_annotation: Some("int".to_string()),
+                description: Some("The current count.".to_string()),
+                default: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_numpy_named_returns() {
+        let parsed = parse_numpy_docstring(
+            "Summary.\n\nReturns\n-------\nquotient : int\n    The quotient.\nremainder : int\n    The remainder.",
+        );
+        assert_eq!(parsed.returns.len(), 2);
+        assert_eq!(parsed.returns[1].name, Some("remainder".to_string()));
+    }
+}
@@ -0,0 +1,275 @@
+use lazy_regex::regex_captures;
+
+use super::{
+    Docstring, DocstringParameter, DocstringRaise, DocstringReturn, SectionKind, dedent, indent_of,
+    join_description,
+};
+
+enum Block<'a> {
+    Field {
+        tag: String,
+        argument: Option<String>,
+        lines: Vec<&'a str>,
+    },
+    Directive {
+        name: String,
+        lines: Vec<&'a str>,
+    },
+}
+
+/// Parses Sphinx style docstrings i.e. reStructuredText field lists like
+/// `:param foo: description` and admonitions like `.. note::`
+pub fn parse_rest_docstring(docstring: &str) -> Docstring {
+    let mut out = Docstring::default();
+    let mut preamble = vec![];
+    let mut blocks: Vec<Block> = vec![];
+    let mut trailing = vec![];
+
+    for line in docstring.lines() {
+        if indent_of(line) == 0
+            && let Some((_, tag, argument, rest)) =
+                regex_captures!(r"^:(\w+)(?:\s+([^:]+))?:\s*(.*)$", line)
+        {
+            blocks.push(Block::Field {
+                tag: tag.to_lowercase(),
+                argument: Some(argument.trim().to_string()).filter(|a| !a.is_empty()),
+                lines: vec![rest],
+            });
+        } else if indent_of(line) == 0
+            && let Some((_, name, rest)) = regex_captures!(r"^\.\.\s+([\w-]+)::\s*(.*)$", line)
+        {
+            blocks.push(Block::Directive {
+                name: name.to_lowercase(),
+                lines: vec![rest],
+            });
+        } else if indent_of(line) == 0 && !line.trim().is_empty() {
+            // a non indented line ends any field or directive, so we
+            // add it to the description. This allows for text after field lists
+            if blocks.is_empty() {
+                preamble.push(line);
+            } else {
+                trailing.push(line);
+            }
+        } else {
+            match blocks.last_mut() {
+                Some(Block::Field { lines, .. }) | Some(Block::Directive { lines, .. }) => {
+                    lines.push(line)
+                }
+                None => preamble.push(line),
+            }
+        }
+    }
+
+    out.set_preamble(&preamble);
+    if !trailing.is_empty() {
+        let trailing = trailing.join("\n");
+        out.description = Some(match out.description.take() {
+            Some(description) => format!("{description}\n\n{trailing}"),
+            None => trailing,
+        });
+    }
+
+    for block in blocks {
+        match block {
+            Block::Field {
+                tag,
+                argument,
+                lines,
+            } => add_field(&mut out, &tag, argument, &lines),
+            Block::Directive { name, lines } => {
+                let kind = match name.as_str() {
+                    "note" => SectionKind::Notes,
+                    "warning" => SectionKind::Warnings,
+                    "seealso" => SectionKind::SeeAlso,
+                    _ => SectionKind::Other,
+                };
+                let content = dedent(&lines[1..]).join("\n");
+                let content = format!("{}\n{}", lines[0], content);
+                out.set_text_section(kind, &name, content);
+            }
+        }
+    }
+
+    out
+}
+
+fn add_field(out: &mut Docstring, tag: &str, argument: Option<String>, lines: &[&str]) {
+    let mut desc_lines = vec![lines[0].trim().to_string()];
+    desc_lines.extend(dedent(&lines[1..]));
+    let description = join_description(&desc_lines);
+
+    match tag {
+        "param" | "parameter" | "arg" | "argument" | "key" | "keyword" => {
+            add_parameter(&mut out.parameters, argument, description)
+        }
+        "type" => add_parameter_type(&mut out.parameters, argument, description),
+        "ivar" | "cvar" | "var" => add_parameter(&mut out.attributes, argument, description),
+        "vartype" => add_parameter_type(&mut out.attributes, argument, description),
+        "returns" | "return" => set_return(&mut out.returns, None, description),
+        "rtype" => set_return(&mut out.returns, description, None),
+        "yields" | "yield" => set_return(&mut out.yields, None, description),
+        "ytype" => set_return(&mut out.yields, description, None),
+        "raises" | "raise" | "except" | "exception" => out.raises.push(DocstringRaise {
+            exception: argument.unwrap_or_default(),
+            description,
+        }),
+        _ => out.set_text_section(SectionKind::Other, tag, description.unwrap_or_default()),
+    }
+}
+
+fn add_parameter(
+    parameters: &mut Vec<DocstringParameter>,
+    argument: Option<String>,
+    description: Option<String>,
+) {
+    let Some(argument) = argument else { return };
+    // `:param int foo:` is allowed as shorthand for specifying the type
+    let (type_annotation, name) = match argument.rsplit_once(char::is_whitespace) {
+        Some((ty, name)) => (Some(ty.trim().to_string()), name.trim().to_string()),
+        None => (None, argument),
+    };
+    match parameters.iter_mut().find(|p| p.name == name) {
+        Some(existing) => {
+            existing.description = description;
+            if type_annotation.is_some() {
+                existing.type_annotation = type_annotation;
+            }
+        }
+        None => parameters.push(DocstringParameter {
+            name,
+            type_annotation,
+            description,
+            default: None,
+        }),
+    }
+}
+
+/// `:type foo:` may come before or after `:param foo:`
+fn add_parameter_type(
+    parameters: &mut Vec<DocstringParameter>,
+    argument: Option<String>,
+    type_annotation: Option<String>,
+) {
+    let Some(name) = argument else { return };
+    match parameters.iter_mut().find(|p| p.name == name) {
+        Some(existing) => existing.type_annotation = type_annotation,
+        None => parameters.push(DocstringParameter {
+            name,
+            type_annotation,
+            description: None,
+            default: None,
+        }),
+    }
+}
+
+/// `:returns:` and `:rtype:` describe the same return value so they are merged
+fn set_return(
+    returns: &mut Vec<DocstringReturn>,
+    type_annotation: Option<String>,
+    description: Option<String>,
+) {
+    match returns.first_mut() {
+        Some(existing) => {
+            if type_annotation.is_some() {
+                existing.type_annotation = type_annotation;
+            }
+            if description.is_some() {
+                existing.description = description;
+            }
+        }
+        None => returns.push(DocstringReturn {
+            name: None,
+            type_annotation,
+            description,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rest_docstring() -> &'static str {
+        r#"Fetch rows from a table.
+
+Retrieves rows pertaining to the given keys.
+
+:param table: The table to fetch from.
+:type table: Table
+:param list keys: The keys to fetch,
+    can be spread over multiple lines.
+:returns: A dict mapping keys to rows.
+:rtype: dict
+:raises IOError: An error occurred accessing the table.
+:ivar connection: The connection that was used.
+:vartype connection: Connection
+
+.. note::
+    This is a note."#
+    }
+
+    #[test]
+    fn parse_rest_parameters() {
+        let parsed = parse_rest_docstring(rest_docstring());
+        assert_eq!(parsed.summary, Some("Fetch rows from a table.".to_string()));
+        assert_eq!(
+            parsed.description,
+            Some("Retrieves rows pertaining to the given keys.".to_string())
+        );
+        assert_eq!(
+            parsed.parameters,
+            vec![
+                DocstringParameter {
+                    name: "table".to_string(),
+                    type_annotation: Some("Table".to_string()),
+                    description: Some("The table to fetch from.".to_string()),
+                    default: None,
+                },
+                DocstringParameter {
+                    name: "keys".to_string(),
+                    type_annotation: Some("list".to_string()),
+                    description: Some(
+                        "The keys to fetch, can be spread over multiple lines.".to_string()
+                    ),
+                    default: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_rest_returns_and_raises() {
+        let parsed = parse_rest_docstring(rest_docstring());
+        assert_eq!(
+            parsed.returns,
+            vec![DocstringReturn {
+                name: None,
+                type_annotation: Some("dict".to_string()),
+                description: Some("A dict mapping keys to rows.".to_string()),
+            }]
+        );
+        assert_eq!(
+            parsed.raises,
+            vec![DocstringRaise {
+                exception: "IOError".to_string(),
+                description: Some("An error occurred accessing the table.".to_string()),
+            }]
+        );
+        assert_eq!(parsed.notes, Some("This is a note.".to_string()));
+    }
+
+    #[test]
+    fn parse_rest_attributes() {
+        let parsed = parse_rest_docstring(rest_docstring());
+        assert_eq!(
+            parsed.attributes,
+            vec![DocstringParameter {
+                name: "connection".to_string(),
+                type_annotation: Some("Connection".to_string()),
+                description: Some("The connection that was used.".to_string()),
+                default: None,
+            }]
+        );
+    }
+}
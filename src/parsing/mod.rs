@@ -1,3 +1,4 @@
+pub mod docstring;
 pub mod python;
 pub mod sphinx;
 
@@ -0,0 +1,363 @@
+use rustpython_parser::ast::{Expr, Stmt};
+
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax, extract_object_refs};
+
+use super::{
+    decorator::Decorator, function::FunctionDocumentation, utils::extract_docstring_from_body,
+};
+
+/// An attribute of a class, either assigned in the class body or
+/// on `self` in the `__init__` method.
+#[derive(Debug, Clone)]
+pub struct AttributeDocumentation {
+    pub name: String,
+    pub type_annotation: Option<Expr>,
+    pub default: Option<Expr>,
+    /// The string literal directly following the assignment, if any
+    pub docstring: Option<String>,
+    /// Set if this attribute is defined through `@property` or `@cached_property`
+    pub property: Option<PropertyAccessors>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PropertyAccessors {
+    pub cached: bool,
+    pub setter: bool,
+    pub deleter: bool,
+}
+
+impl AttributeDocumentation {
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
+        self.docstring
+            .as_ref()
+            .map(|s| (s.clone(), extract_object_refs(s, syntaxes)))
+    }
+
+    /// Fill in any information we didn't have yet from another definition
+    /// of the same attribute. The first definition wins.
+    fn merge(&mut self, other: AttributeDocumentation) {
+        if self.type_annotation.is_none() {
+            self.type_annotation = other.type_annotation;
+        }
+        if self.default.is_none() {
+            self.default = other.default;
+        }
+        if self.docstring.is_none() {
+            self.docstring = other.docstring;
+        }
+        if self.property.is_none() {
+            self.property = other.property;
+        }
+    }
+}
+
+pub fn is_private_attribute(attr_doc: &AttributeDocumentation) -> bool {
+    attr_doc.name.starts_with("_")
+}
+
+/// Adds attributes to the list, merging them with any existing
+/// attribute of the same name.
+pub(crate) fn add_attributes(
+    attributes: &mut Vec<AttributeDocumentation>,
+    new: Vec<AttributeDocumentation>,
+) {
+    for attr in new {
+        match attributes.iter_mut().find(|a| a.name == attr.name) {
+            Some(existing) => existing.merge(attr),
+            None => attributes.push(attr),
+        }
+    }
+}
+
+/// Extracts attributes that are assigned directly in a (class) body e.g.
+/// ```python
+/// x: int = 3
+/// """attribute docstring"""
+/// ```
+pub(crate) fn extract_attributes_from_body(
+    body: &[Stmt],
+    indent_level: usize,
+) -> Vec<AttributeDocumentation> {
+    extract_assignments(body, indent_level, |target| {
+        target.as_name_expr().map(|n| n.id.to_string())
+    })
+}
+
+/// Extracts attributes that are assigned on the instance, e.g. `self.x = 3`.
+/// Only top level statements of the body are considered. What is assigned
+/// is usually computed from the arguments, so it isn't kept as a default.
+pub(crate) fn extract_instance_attributes_from_body(
+    body: &[Stmt],
+    indent_level: usize,
+    self_name: &str,
+) -> Vec<AttributeDocumentation> {
+    extract_assignments(body, indent_level, |target| {
+        let attr = target.as_attribute_expr()?;
+        match attr.value.as_name_expr() {
+            Some(n) if n.id.as_str() == self_name => Some(attr.attr.to_string()),
+            _ => None,
+        }
+    })
+    .into_iter()
+    .map(|attr| AttributeDocumentation {
+        default: None,
+        ..attr
+    })
+    .collect()
+}
+
+/// Turns `@property` and `@cached_property` methods into attributes and merges their
+/// setters and deleters into them. Returns the remaining methods and the properties.
+pub(crate) fn extract_properties(
+    methods: Vec<FunctionDocumentation>,
+) -> (Vec<FunctionDocumentation>, Vec<AttributeDocumentation>) {
+    let mut remaining = vec![];
+    let mut properties: Vec<AttributeDocumentation> = vec![];
+
+    for method in methods {
+        let cached = method.has_decorator(&Decorator::CachedProperty);
+        if cached || method.has_decorator(&Decorator::Property) {
+            properties.push(AttributeDocumentation {
+                name: method.name,
+                type_annotation: method.return_type,
+                default: None,
+                docstring: method.docstring,
+                property: Some(PropertyAccessors {
+                    cached,
+                    ..Default::default()
+                }),
+            });
+            continue;
+        }
+
+        let accessor = method.decorators.iter().find_map(|d| match d {
+            Decorator::Setter(name) | Decorator::Deleter(name) => Some((name, d)),
+            _ => None,
+        });
+        let getter = accessor.and_then(|(name, decorator)| {
+            properties
+                .iter_mut()
+                .find(|p| &p.name == name)
+                .map(|p| (p, decorator))
+        });
+        match getter {
+            Some((prop, decorator)) => {
+                if let Some(accessors) = prop.property.as_mut() {
+                    match decorator {
+                        Decorator::Setter(_) => accessors.setter = true,
+                        _ => accessors.deleter = true,
+                    }
+                }
+                if prop.docstring.is_none() {
+                    prop.docstring = method.docstring;
+                }
+            }
+            None => remaining.push(method),
+        }
+    }
+
+    (remaining, properties)
+}
+
+fn extract_assignments<F>(
+    body: &[Stmt],
+    indent_level: usize,
+    target_name: F,
+) -> Vec<AttributeDocumentation>
+where
+    F: Fn(&Expr) -> Option<String>,
+{
+    let mut attributes = vec![];
+    for (idx, statement) in body.iter().enumerate() {
+        let (name, type_annotation, default) = match statement {
+            Stmt::AnnAssign(ann_assign) => match target_name(&ann_assign.target) {
+                Some(name) => (
+                    name,
+                    Some(*ann_assign.annotation.clone()),
+                    ann_assign.value.as_ref().map(|v| *v.clone()),
+                ),
+                None => continue,
+            },
+            // we don't attempt to document things like `a = b = 3` or `a, b = 1, 2`
+            Stmt::Assign(assign) if assign.targets.len() == 1 => {
+                match assign.targets.first().and_then(&target_name) {
+                    Some(name) => (name, None, Some(*assign.value.clone())),
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        let docstring = extract_docstring_from_body(&body[idx + 1..], indent_level);
+        add_attributes(
+            &mut attributes,
+            vec![AttributeDocumentation {
+                name,
+                type_annotation,
+                default,
+                docstring,
+                property: None,
+            }],
+        );
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::PropertyAccessors;
+    use crate::{
+        parsing::python::{module::extract_module_documentation, utils::parse_python_str},
+        render::expr::render_expr,
+    };
+
+    fn test_python_class_attributes() -> &'static str {
+        r#"
+class Point:
+    '''A point in 2D space.'''
+
+    dims: int = 2
+    '''The number of dimensions.
+
+    Always two.
+    '''
+
+    origin = (0, 0)
+    label: str
+
+    def __init__(self, x: float, y: float = 0.0):
+        '''Create a point.'''
+        self.x: float = x
+        '''The x coordinate'''
+        self.y = y
+        self.label = f"({x}, {y})"
+        '''A human readable label'''
+        other.z = 3
+        if x > 0:
+            self.positive = True
+
+    def norm(self) -> float:
+        self.cached = 1
+        return 1
+"#
+    }
+
+    #[test]
+    fn extract_class_attributes() -> Result<()> {
+        let program = parse_python_str(test_python_class_attributes())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+
+        let names: Vec<&str> = class.attributes.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["dims", "origin", "label", "x", "y"]);
+
+        let dims = &class.attributes[0];
+        assert_eq!(
+            dims.type_annotation.clone().map(render_expr),
+            Some("int".to_string())
+        );
+        assert_eq!(dims.default.clone().map(render_expr), Some("2".to_string()));
+        assert_eq!(
+            dims.docstring,
+            Some("The number of dimensions.\n\nAlways two.".to_string())
+        );
+
+        let origin = &class.attributes[1];
+        assert_eq!(origin.type_annotation, None);
+        assert_eq!(origin.docstring, None);
+
+        Ok(())
+    }
+
+    fn test_python_class_properties() -> &'static str {
+        r#"
+class Person:
+    def __init__(self, name):
+        self._name = name
+
+    @property
+    def name(self) -> str:
+        '''The name of the person'''
+        return self._name
+
+    @name.setter
+    def name(self, value: str):
+        self._name = value
+
+    @functools.cached_property
+    def initials(self) -> str:
+        return self._name[0]
+
+    @staticmethod
+    def create() -> "Person":
+        return Person("")
+"#
+    }
+
+    #[test]
+    fn properties_are_attributes() -> Result<()> {
+        let program = parse_python_str(test_python_class_properties())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+
+        let methods: Vec<&str> = class.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(methods, vec!["__init__", "create"]);
+
+        let names: Vec<&str> = class.attributes.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["_name", "name", "initials"]);
+
+        let name = &class.attributes[1];
+        assert_eq!(
+            name.property,
+            Some(PropertyAccessors {
+                cached: false,
+                setter: true,
+                deleter: false,
+            })
+        );
+        assert_eq!(name.docstring, Some("The name of the person".to_string()));
+        assert_eq!(
+            name.type_annotation.clone().map(render_expr),
+            Some("str".to_string())
+        );
+        assert_eq!(class.attributes[2].property.map(|p| p.cached), Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn instance_attributes_are_merged_with_class_attributes() -> Result<()> {
+        let program = parse_python_str(test_python_class_attributes())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+
+        let label = &class.attributes[2];
+        assert_eq!(
+            label.type_annotation.clone().map(render_expr),
+            Some("str".to_string())
+        );
+        assert_eq!(label.docstring, Some("A human readable label".to_string()));
+        assert!(label.default.is_none());
+
+        let x = &class.attributes[3];
+        assert_eq!(
+            x.type_annotation.clone().map(render_expr),
+            Some("float".to_string())
+        );
+        assert!(x.default.is_none());
+        assert_eq!(x.docstring, Some("The x coordinate".to_string()));
+
+        Ok(())
+    }
+}
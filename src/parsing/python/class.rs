@@ -1,35 +1,170 @@
-use rustpython_parser::ast::{Identifier, StmtClassDef};
+use std::collections::HashMap;
 
-use crate::indexing::object_ref::{ObjectRef, extract_object_refs};
+use rustpython_parser::{
+    ast::{Expr, Identifier, Keyword, Stmt, StmtClassDef},
+    text_size::TextRange,
+};
 
-use super::{function::FunctionDocumentation, utils::extract_docstring_from_body};
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax, extract_object_refs};
+
+use super::{
+    attribute::{
+        AttributeDocumentation, add_attributes, extract_attributes_from_body,
+        extract_instance_attributes_from_body, extract_properties,
+    },
+    decorator::{Decorator, extract_decorators},
+    function::{FunctionDocumentation, merge_overloads},
+    object::Member,
+    utils::extract_docstring_from_body,
+};
 
 #[derive(Debug, Clone)]
 pub struct ClassDocumentation {
     pub name: Identifier,
     pub docstring: Option<String>,
     pub methods: Vec<FunctionDocumentation>,
+    pub attributes: Vec<AttributeDocumentation>,
+    pub decorators: Vec<Decorator>,
+    pub bases: Vec<BaseClass>,
+    /// Class keywords such as `metaclass=ABCMeta`
+    pub keywords: Vec<Keyword>,
+    /// Methods defined on internal base classes, in method resolution order.
+    /// Only filled in when inherited members are requested.
+    pub inherited_members: Vec<InheritedMembers>,
+    /// Methods, attributes and nested classes that have a page of their own, see
+    /// [`crate::indexing::index::RawIndex::collect_members`]
+    pub members: Vec<Member>,
+    /// Where the definition is in the source file
+    pub range: TextRange,
+    /// The line the definition starts on, see
+    /// [`crate::parsing::python::module::ModuleDocumentation::locate`]
+    pub line: Option<usize>,
+    /// Where the names used in the annotations and defaults of the attributes
+    /// link to, see [`crate::indexing::index::RawIndex::resolve_annotations`]
+    pub annotation_targets: HashMap<String, String>,
+}
+
+/// A base class as written in the class definition
+#[derive(Debug, Clone)]
+pub struct BaseClass {
+    pub expr: Expr,
+    /// Either the fully qualified name of an indexed class or the url of an
+    /// external one. Set once the whole package has been indexed.
+    pub target: Option<String>,
+}
+
+impl BaseClass {
+    /// The dotted name the base refers to, ignoring any subscript,
+    /// e.g. `typing.Generic` for `typing.Generic[T]`
+    pub fn dotted_name(&self) -> Option<String> {
+        dotted_name(&self.expr)
+    }
+}
+
+fn dotted_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Name(name) => Some(name.id.to_string()),
+        Expr::Attribute(attr) => {
+            dotted_name(&attr.value).map(|prefix| format!("{}.{}", prefix, attr.attr))
+        }
+        Expr::Subscript(subscript) => dotted_name(&subscript.value),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InheritedMembers {
+    /// Fully qualified name of the base class the methods are inherited from
+    pub base: String,
+    pub methods: Vec<String>,
 }
 
 impl ClassDocumentation {
     pub fn from_class_statements(value: &StmtClassDef, body_indent_level: usize) -> Self {
+        let methods = merge_overloads(
+            value
+                .body
+                .iter()
+                .filter_map(|s| FunctionDocumentation::from_statements(s, body_indent_level))
+                .collect(),
+        );
+        let (methods, properties) = extract_properties(methods);
+
+        let mut attributes = extract_class_attributes(&value.body, body_indent_level);
+        add_attributes(&mut attributes, properties);
+
         Self {
             name: value.name.clone(),
             docstring: extract_docstring_from_body(&value.body, body_indent_level),
-            methods: value
-                .body
+            methods,
+            attributes,
+            decorators: extract_decorators(&value.decorator_list),
+            bases: value
+                .bases
                 .iter()
-                .filter_map(|s| FunctionDocumentation::from_statements(s, body_indent_level))
+                .map(|expr| BaseClass {
+                    expr: expr.clone(),
+                    target: None,
+                })
                 .collect(),
+            keywords: value.keywords.clone(),
+            inherited_members: vec![],
+            members: vec![],
+            range: value.range,
+            line: None,
+            annotation_targets: HashMap::new(),
         }
     }
-    pub fn extract_used_references(&self) -> Option<(String, Vec<ObjectRef>)> {
+
+    pub fn metaclass(&self) -> Option<&Expr> {
+        self.keywords
+            .iter()
+            .find(|k| k.arg.as_ref().is_some_and(|a| a.as_str() == "metaclass"))
+            .map(|k| &k.value)
+    }
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
         self.docstring
             .as_ref()
-            .map(|s| (s.clone(), extract_object_refs(s)))
+            .map(|s| (s.clone(), extract_object_refs(s, syntaxes)))
     }
 }
 
+/// Collects the attributes defined in the class body, followed by
+/// those assigned on `self` in `__init__`
+fn extract_class_attributes(
+    body: &[Stmt],
+    body_indent_level: usize,
+) -> Vec<AttributeDocumentation> {
+    let mut attributes = extract_attributes_from_body(body, body_indent_level);
+
+    let init = body.iter().find_map(|s| match s {
+        Stmt::FunctionDef(f) if f.name.as_str() == "__init__" => Some((&f.body, &f.args)),
+        Stmt::AsyncFunctionDef(f) if f.name.as_str() == "__init__" => Some((&f.body, &f.args)),
+        _ => None,
+    });
+
+    if let Some((init_body, init_args)) = init
+        && let Some(self_arg) = init_args
+            .posonlyargs
+            .first()
+            .or_else(|| init_args.args.first())
+    {
+        add_attributes(
+            &mut attributes,
+            extract_instance_attributes_from_body(
+                init_body,
+                body_indent_level + 1,
+                self_arg.def.arg.as_str(),
+            ),
+        );
+    }
+
+    attributes
+}
+
 pub fn is_private_class(class_doc: &ClassDocumentation) -> bool {
     class_doc.name.starts_with("_")
 }
@@ -41,9 +176,12 @@ mod test {
     use color_eyre::Result;
     use std::{fs::File, io::Write};
 
-    use crate::parsing::{
-        python::module::extract_module_documentation,
-        python::utils::{parse_python_file, parse_python_str},
+    use crate::{
+        parsing::{
+            python::module::extract_module_documentation,
+            python::utils::{parse_python_file, parse_python_str},
+        },
+        render::expr::render_expr,
     };
 
     fn test_python_class() -> &'static str {
@@ -122,4 +260,30 @@ fn parse_test_python_file_on_disk() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn parse_class_bases_and_metaclass() -> Result<()> {
+        let program = parse_python_str(
+            r#"
+class Registry(base.Plugin, Generic[T], metaclass=abc.ABCMeta, frozen=True):
+    pass
+"#,
+        )?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+
+        let bases: Vec<Option<String>> = class.bases.iter().map(|b| b.dotted_name()).collect();
+        assert_eq!(
+            bases,
+            vec![Some("base.Plugin".to_string()), Some("Generic".to_string())]
+        );
+        assert_eq!(class.keywords.len(), 2);
+        assert_eq!(
+            class.metaclass().cloned().map(render_expr),
+            Some("abc.ABCMeta".to_string())
+        );
+        Ok(())
+    }
 }
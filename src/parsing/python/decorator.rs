@@ -0,0 +1,107 @@
+use rustpython_parser::ast::Expr;
+
+/// Decorators we attach a special meaning to get their own variant,
+/// anything else is kept as is so it can be rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decorator {
+    Property,
+    CachedProperty,
+    /// `@name.setter`
+    Setter(String),
+    /// `@name.deleter`
+    Deleter(String),
+    StaticMethod,
+    ClassMethod,
+    AbstractMethod,
+    Overload,
+    Other(Expr),
+}
+
+impl Decorator {
+    pub fn from_expr(expr: &Expr) -> Self {
+        match expr {
+            Expr::Attribute(attr) => match (attr.attr.as_str(), attr.value.as_name_expr()) {
+                ("setter", Some(name)) => Decorator::Setter(name.id.to_string()),
+                ("deleter", Some(name)) => Decorator::Deleter(name.id.to_string()),
+                // things like `functools.cached_property` or `abc.abstractmethod`
+                (attr_name, _) => {
+                    Self::from_name(attr_name).unwrap_or_else(|| Decorator::Other(expr.clone()))
+                }
+            },
+            Expr::Name(name) => {
+                Self::from_name(name.id.as_str()).unwrap_or_else(|| Decorator::Other(expr.clone()))
+            }
+            _ => Decorator::Other(expr.clone()),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "property" => Some(Decorator::Property),
+            "cached_property" => Some(Decorator::CachedProperty),
+            "staticmethod" => Some(Decorator::StaticMethod),
+            "classmethod" => Some(Decorator::ClassMethod),
+            "abstractmethod" => Some(Decorator::AbstractMethod),
+            "overload" => Some(Decorator::Overload),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn extract_decorators(decorator_list: &[Expr]) -> Vec<Decorator> {
+    decorator_list.iter().map(Decorator::from_expr).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use rustpython_parser::{Mode, ast::Mod, parse};
+
+    use super::*;
+
+    fn get_expr(s: &str) -> Result<Expr> {
+        let parsed = parse(s, Mode::Expression, "<embedded>")?;
+        if let Mod::Expression(mod_expr) = parsed {
+            Ok(*mod_expr.body)
+        } else {
+            panic!()
+        }
+    }
+
+    #[test]
+    fn semantic_decorators() -> Result<()> {
+        assert_eq!(
+            Decorator::from_expr(&get_expr("property")?),
+            Decorator::Property
+        );
+        assert_eq!(
+            Decorator::from_expr(&get_expr("functools.cached_property")?),
+            Decorator::CachedProperty
+        );
+        assert_eq!(
+            Decorator::from_expr(&get_expr("abc.abstractmethod")?),
+            Decorator::AbstractMethod
+        );
+        assert_eq!(
+            Decorator::from_expr(&get_expr("typing.overload")?),
+            Decorator::Overload
+        );
+        assert_eq!(
+            Decorator::from_expr(&get_expr("name.setter")?),
+            Decorator::Setter("name".to_string())
+        );
+        assert_eq!(
+            Decorator::from_expr(&get_expr("name.deleter")?),
+            Decorator::Deleter("name".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn other_decorators_are_kept() -> Result<()> {
+        let expr = get_expr("app.route('/', methods=['GET'])")?;
+        assert_eq!(Decorator::from_expr(&expr), Decorator::Other(expr));
+        Ok(())
+    }
+}
@@ -1,10 +1,16 @@
-use rustpython_parser::ast::{
-    Arguments, Expr, Stmt, StmtAsyncFunctionDef, StmtFunctionDef, TypeParam,
+use std::collections::HashMap;
+
+use rustpython_parser::{
+    ast::{Arguments, Expr, Stmt, StmtAsyncFunctionDef, StmtFunctionDef, TypeParam},
+    text_size::TextRange,
 };
 
-use crate::indexing::object_ref::{ObjectRef, extract_object_refs};
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax, extract_object_refs};
 
-use super::utils::extract_docstring_from_body;
+use super::{
+    decorator::{Decorator, extract_decorators},
+    utils::extract_docstring_from_body,
+};
 
 #[derive(Debug, Clone)]
 pub struct FunctionDocumentation {
@@ -13,13 +19,32 @@ pub struct FunctionDocumentation {
     pub return_type: Option<Expr>,
     pub args: Arguments,
     pub generics: Vec<TypeParam>,
+    pub is_async: bool,
+    pub decorators: Vec<Decorator>,
+    /// The `@overload` decorated signatures of this function
+    pub overloads: Vec<FunctionDocumentation>,
+    /// Where the definition is in the source file
+    pub range: TextRange,
+    /// The line the definition starts on, see
+    /// [`crate::parsing::python::module::ModuleDocumentation::locate`]
+    pub line: Option<usize>,
+    /// Where the names used in the annotations and defaults of the parameters
+    /// and the return annotation link to, see
+    /// [`crate::indexing::index::RawIndex::resolve_annotations`]
+    pub annotation_targets: HashMap<String, String>,
 }
 
 impl FunctionDocumentation {
-    pub fn extract_used_references(&self) -> Option<(String, Vec<ObjectRef>)> {
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
         self.docstring
             .as_ref()
-            .map(|s| (s.clone(), extract_object_refs(s)))
+            .map(|s| (s.clone(), extract_object_refs(s, syntaxes)))
+    }
+    pub fn has_decorator(&self, decorator: &Decorator) -> bool {
+        self.decorators.contains(decorator)
     }
     pub fn from_statements(value: &Stmt, body_indent_level: usize) -> Option<Self> {
         match value {
@@ -49,6 +74,12 @@ pub fn from_async_function_statements(
             return_type: value.returns.as_ref().map(|r| *r.clone()),
             args: *value.args.clone(),
             generics: value.type_params.clone(),
+            is_async: true,
+            decorators: extract_decorators(&value.decorator_list),
+            overloads: vec![],
+            range: value.range,
+            line: None,
+            annotation_targets: HashMap::new(),
         }
     }
     pub fn from_function_statements(value: &StmtFunctionDef, body_indent_level: usize) -> Self {
@@ -59,8 +90,58 @@ pub fn from_function_statements(value: &StmtFunctionDef, body_indent_level: usiz
             return_type: value.returns.as_ref().map(|r| *r.clone()),
             args: *value.args.clone(),
             generics: value.type_params.clone(),
+            is_async: false,
+            decorators: extract_decorators(&value.decorator_list),
+            overloads: vec![],
+            range: value.range,
+            line: None,
+            annotation_targets: HashMap::new(),
+        }
+    }
+}
+
+/// Attaches `@overload` decorated signatures to the implementation that follows
+/// them. If there is no implementation (e.g. in stub files) the first overload
+/// takes its place.
+pub(crate) fn merge_overloads(functions: Vec<FunctionDocumentation>) -> Vec<FunctionDocumentation> {
+    let mut merged: Vec<FunctionDocumentation> = vec![];
+    let mut pending: Vec<FunctionDocumentation> = vec![];
+
+    for function in functions {
+        if function.has_decorator(&Decorator::Overload) {
+            if pending.first().is_some_and(|p| p.name != function.name) {
+                merged.extend(into_implementation(std::mem::take(&mut pending)));
+            }
+            pending.push(function);
+        } else if pending.first().is_some_and(|p| p.name == function.name) {
+            let mut implementation = function;
+            if implementation.docstring.is_none() {
+                implementation.docstring = pending.iter().find_map(|p| p.docstring.clone());
+            }
+            implementation.overloads = std::mem::take(&mut pending);
+            merged.push(implementation);
+        } else {
+            merged.extend(into_implementation(std::mem::take(&mut pending)));
+            merged.push(function);
         }
     }
+    merged.extend(into_implementation(pending));
+
+    merged
+}
+
+fn into_implementation(mut overloads: Vec<FunctionDocumentation>) -> Option<FunctionDocumentation> {
+    if overloads.is_empty() {
+        return None;
+    }
+    let mut first = overloads.remove(0);
+    let first_overload = first.clone();
+    first.overloads = vec![first_overload];
+    first.overloads.extend(overloads);
+    if first.docstring.is_none() {
+        first.docstring = first.overloads.iter().find_map(|p| p.docstring.clone());
+    }
+    Some(first)
 }
 
 pub fn is_private_function(fn_doc: &FunctionDocumentation) -> bool {
@@ -73,7 +154,8 @@ mod test {
     use color_eyre::Result;
 
     use crate::parsing::{
-        python::module::extract_module_documentation, python::utils::parse_python_str,
+        python::decorator::Decorator, python::module::extract_module_documentation,
+        python::utils::parse_python_str,
     };
 
     fn test_python_func_no_types() -> &'static str {
@@ -155,6 +237,56 @@ fn test_python_func_annotated() -> &'static str {
         "
     }
 
+    fn test_python_overloads() -> &'static str {
+        "
+@overload
+def double(x: int) -> int: ...
+@overload
+def double(x: str) -> str: ...
+def double(x):
+    '''Double the input.'''
+    return x * 2
+
+@typing.overload
+def stub(x: int) -> int:
+    '''A stub without implementation.'''
+@typing.overload
+def stub(x: str) -> str: ...
+
+@functools.cache
+def cached(x):
+    return x
+        "
+    }
+
+    #[test]
+    fn merge_overloads_with_implementation() -> Result<()> {
+        let program = parse_python_str(test_python_overloads())?;
+        let documentation = extract_module_documentation(&program, false, false);
+        assert_eq!(documentation.functions.len(), 3);
+
+        let double = &documentation.functions[0];
+        assert_eq!(double.name, "double");
+        assert_eq!(double.overloads.len(), 2);
+        assert_eq!(double.docstring, Some("Double the input.".to_string()));
+
+        let stub = &documentation.functions[1];
+        assert_eq!(stub.name, "stub");
+        assert_eq!(stub.overloads.len(), 2);
+        assert_eq!(
+            stub.docstring,
+            Some("A stub without implementation.".to_string())
+        );
+
+        let cached = &documentation.functions[2];
+        assert!(cached.overloads.is_empty());
+        assert!(matches!(
+            cached.decorators.as_slice(),
+            [Decorator::Other(_)]
+        ));
+        Ok(())
+    }
+
     #[test]
     fn parse_doesnt_extract_lambda() -> Result<()> {
         let program = parse_python_str(test_python_lambda())?;
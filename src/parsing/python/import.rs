@@ -0,0 +1,162 @@
+use rustpython_parser::ast::Stmt;
+
+/// A name bound by an import statement e.g. `from .x import Y as Z` binds
+/// `Z` to `.x.Y` and `import a.b` binds `a` to `a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedName {
+    /// The name the import is bound to in the importing module
+    pub name: String,
+    /// The dotted path of the imported object. Relative imports keep their
+    /// leading dots until they are resolved with [`ImportedName::resolve`].
+    pub target: String,
+}
+
+impl ImportedName {
+    /// Makes the target of a relative import absolute. `module` is the import path
+    /// of the importing module and `is_package` whether that module is an `__init__.py`.
+    /// Returns `None` if the import reaches beyond the top level package.
+    pub fn resolve(&self, module: &str, is_package: bool) -> Option<ImportedName> {
+        let level = self.target.chars().take_while(|c| *c == '.').count();
+        if level == 0 {
+            return Some(self.clone());
+        }
+
+        let mut components: Vec<&str> = module.split('.').collect();
+        // for a plain module the first dot refers to the package containing it
+        let to_strip = if is_package { level - 1 } else { level };
+        if to_strip >= components.len() {
+            return None;
+        }
+        components.truncate(components.len() - to_strip);
+
+        let rest = &self.target[level..];
+        let target = if rest.is_empty() {
+            components.join(".")
+        } else {
+            format!("{}.{}", components.join("."), rest)
+        };
+        Some(ImportedName {
+            name: self.name.clone(),
+            target,
+        })
+    }
+}
+
+/// Collects the names bound by the top level import statements of a body.
+/// Star imports can't be followed without executing code so they are ignored.
+pub(crate) fn extract_imports_from_body(body: &[Stmt]) -> Vec<ImportedName> {
+    let mut imports = vec![];
+    for statement in body {
+        match statement {
+            Stmt::Import(import) => {
+                for alias in &import.names {
+                    let (name, target) = if let Some(asname) = &alias.asname {
+                        (asname.to_string(), alias.name.to_string())
+                    } else {
+                        // `import a.b` only binds `a`
+                        let top_level = alias.name.split('.').next().unwrap_or_default();
+                        (top_level.to_string(), top_level.to_string())
+                    };
+                    imports.push(ImportedName { name, target });
+                }
+            }
+            Stmt::ImportFrom(import_from) => {
+                let level = import_from.level.map(|l| l.to_u32()).unwrap_or(0) as usize;
+                let module = import_from
+                    .module
+                    .as_ref()
+                    .map(|m| m.to_string())
+                    .unwrap_or_default();
+                let prefix = format!("{}{}", ".".repeat(level), module);
+                for alias in &import_from.names {
+                    if alias.name.as_str() == "*" {
+                        tracing::debug!("ignoring star import from {prefix}");
+                        continue;
+                    }
+                    let name = alias.asname.as_ref().unwrap_or(&alias.name).to_string();
+                    let target = if module.is_empty() {
+                        format!("{}{}", prefix, alias.name)
+                    } else {
+                        format!("{}.{}", prefix, alias.name)
+                    };
+                    imports.push(ImportedName { name, target });
+                }
+            }
+            _ => (),
+        }
+    }
+    imports
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::ImportedName;
+    use crate::parsing::python::{module::extract_module_documentation, utils::parse_python_str};
+
+    fn imported(name: &str, target: &str) -> ImportedName {
+        ImportedName {
+            name: name.to_string(),
+            target: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn extract_imports() -> Result<()> {
+        let program = parse_python_str(
+            r#"
+import os
+import numpy as np
+import os.path
+from . import sibling
+from .impl import Thing, other as alias
+from ..up import Parent
+from typing import *
+from pkg.mod import absolute
+"#,
+        )?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        assert_eq!(
+            documentation.imports,
+            vec![
+                imported("os", "os"),
+                imported("np", "numpy"),
+                imported("os", "os"),
+                imported("sibling", ".sibling"),
+                imported("Thing", ".impl.Thing"),
+                imported("alias", ".impl.other"),
+                imported("Parent", "..up.Parent"),
+                imported("absolute", "pkg.mod.absolute"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_relative_imports() {
+        assert_eq!(
+            imported("Thing", ".impl.Thing").resolve("pkg.sub", true),
+            Some(imported("Thing", "pkg.sub.impl.Thing"))
+        );
+        assert_eq!(
+            imported("Thing", ".impl.Thing").resolve("pkg.sub.mod", false),
+            Some(imported("Thing", "pkg.sub.impl.Thing"))
+        );
+        assert_eq!(
+            imported("Parent", "..up.Parent").resolve("pkg.sub.mod", false),
+            Some(imported("Parent", "pkg.up.Parent"))
+        );
+        assert_eq!(
+            imported("sibling", ".sibling").resolve("pkg", true),
+            Some(imported("sibling", "pkg.sibling"))
+        );
+        assert_eq!(
+            imported("np", "numpy").resolve("pkg.mod", false),
+            Some(imported("np", "numpy"))
+        );
+        assert_eq!(imported("x", "...x").resolve("pkg.mod", false), None);
+    }
+}
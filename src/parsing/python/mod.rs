@@ -1,6 +1,11 @@
+pub mod attribute;
 pub mod class;
+pub mod decorator;
 pub mod function;
+pub mod import;
 pub mod jupyter;
 pub mod module;
 pub mod object;
+pub mod type_alias;
 pub mod utils;
+pub mod variable;
@@ -1,14 +1,22 @@
 use std::path::PathBuf;
 
 use color_eyre::{Result, eyre::eyre};
-use rustpython_parser::ast::{Mod, Stmt, StmtAssign};
+use rustpython_parser::{
+    ast::{Mod, Stmt, StmtAssign},
+    source_code::LineIndex,
+    text_size::TextRange,
+};
 
-use crate::indexing::object_ref::{ObjectRef, extract_object_refs};
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax, extract_object_refs};
 
 use super::{
     class::{ClassDocumentation, is_private_class},
-    function::{FunctionDocumentation, is_private_function},
+    function::{FunctionDocumentation, is_private_function, merge_overloads},
+    import::{ImportedName, extract_imports_from_body},
+    object::Member,
+    type_alias::{TypeAliasDocumentation, extract_type_aliases_from_body, is_private_type_alias},
     utils::extract_docstring_from_body,
+    variable::{VariableDocumentation, extract_variables_from_body, is_private_variable},
 };
 
 #[derive(Default, Debug, Clone)]
@@ -16,8 +24,14 @@ pub struct ModuleDocumentation {
     pub docstring: Option<String>,
     pub functions: Vec<FunctionDocumentation>,
     pub classes: Vec<ClassDocumentation>,
+    pub variables: Vec<VariableDocumentation>,
+    pub type_aliases: Vec<TypeAliasDocumentation>,
     pub sub_modules: Option<Vec<PathBuf>>,
     pub exports: Option<Vec<String>>,
+    pub imports: Vec<ImportedName>,
+    /// Sub modules and objects that have a page of their own, see
+    /// [`crate::indexing::index::RawIndex::collect_members`]
+    pub members: Vec<Member>,
 }
 
 #[derive(Debug)]
@@ -27,10 +41,29 @@ pub struct ModuleReference {
 }
 
 impl ModuleDocumentation {
-    pub fn extract_used_references(&self) -> Option<(String, Vec<ObjectRef>)> {
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
         self.docstring
             .as_ref()
-            .map(|s| (s.clone(), extract_object_refs(s)))
+            .map(|s| (s.clone(), extract_object_refs(s, syntaxes)))
+    }
+
+    /// Fills in the lines the classes and functions start on, using the
+    /// source the module was parsed from.
+    pub fn locate(&mut self, source: &str) {
+        let line_index = LineIndex::from_source_text(source);
+        let line = |range: TextRange| Some(line_index.line_index(range.start()).get() as usize);
+        for class in &mut self.classes {
+            class.line = line(class.range);
+            for method in &mut class.methods {
+                method.line = line(method.range);
+            }
+        }
+        for function in &mut self.functions {
+            function.line = line(function.range);
+        }
     }
 }
 
@@ -60,16 +93,17 @@ fn extract_exports_from_statement(statement: &StmtAssign) -> Result<Vec<String>>
     {
         return Err(eyre!("target of assignment was not __all__"));
     };
-    match &*statement.value.clone() {
-        rustpython_parser::ast::Expr::List(expr_list) => Ok(expr_list
-            .elts
-            .iter()
-            .filter_map(|e| e.as_constant_expr())
-            .filter_map(|c| c.value.as_str())
-            .cloned()
-            .collect::<Vec<String>>()),
-        _ => Err(eyre!("__all__ assignment was not list")),
-    }
+    let elements = match &*statement.value {
+        rustpython_parser::ast::Expr::List(expr_list) => &expr_list.elts,
+        rustpython_parser::ast::Expr::Tuple(expr_tuple) => &expr_tuple.elts,
+        _ => return Err(eyre!("__all__ assignment was not a list or tuple")),
+    };
+    Ok(elements
+        .iter()
+        .filter_map(|e| e.as_constant_expr())
+        .filter_map(|c| c.value.as_str())
+        .cloned()
+        .collect::<Vec<String>>())
 }
 
 fn extract_documentation_from_statements(
@@ -94,69 +128,108 @@ fn extract_documentation_from_statements(
             }
         }
         if let Stmt::FunctionDef(stmt_function_def) = statement {
-            let function_doc: FunctionDocumentation =
-                FunctionDocumentation::from_function_statements(stmt_function_def, 1);
-            if function_doc.docstring.is_none() && skip_undoc {
+            free_functions.push(FunctionDocumentation::from_function_statements(
+                stmt_function_def,
+                1,
+            ));
+        }
+        if let Stmt::AsyncFunctionDef(stmt_async_function_def) = statement {
+            free_functions.push(FunctionDocumentation::from_async_function_statements(
+                stmt_async_function_def,
+                1,
+            ));
+        }
+        if let Stmt::ClassDef(stmt_class_def) = statement {
+            let class_doc: ClassDocumentation =
+                ClassDocumentation::from_class_statements(stmt_class_def, 1);
+            if is_private_class(&class_doc) && skip_private {
+                tracing::debug!("skipping class {} because it is private", class_doc.name,);
+                continue;
+            }
+            if class_doc.docstring.is_none() && skip_undoc {
                 tracing::debug!(
                     "skipping function {} because it is undocumented",
-                    function_doc.name,
+                    class_doc.name,
                 );
                 continue;
             };
-
-            if is_private_function(&function_doc) && skip_private {
-                tracing::debug!(
-                    "skipping function {} because it is private",
-                    function_doc.name,
-                );
-                continue;
-            }
-            free_functions.push(function_doc);
+            class_definitions.push(class_doc);
         }
-        if let Stmt::AsyncFunctionDef(stmt_async_function_def) = statement {
-            let function_doc: FunctionDocumentation =
-                FunctionDocumentation::from_async_function_statements(stmt_async_function_def, 1);
+    }
+
+    // overloads have to be merged before filtering, since they are
+    // usually not documented themselves
+    let free_functions = merge_overloads(free_functions)
+        .into_iter()
+        .filter(|function_doc| {
             if function_doc.docstring.is_none() && skip_undoc {
                 tracing::debug!(
                     "skipping function {} because it is undocumented",
                     function_doc.name,
                 );
-                continue;
+                return false;
             };
 
-            if is_private_function(&function_doc) && skip_private {
+            if is_private_function(function_doc) && skip_private {
                 tracing::debug!(
                     "skipping function {} because it is private",
                     function_doc.name,
                 );
-                continue;
+                return false;
             }
-            free_functions.push(function_doc);
-        }
-        if let Stmt::ClassDef(stmt_class_def) = statement {
-            let class_doc: ClassDocumentation =
-                ClassDocumentation::from_class_statements(stmt_class_def, 1);
-            if is_private_class(&class_doc) && skip_private {
-                tracing::debug!("skipping class {} because it is private", class_doc.name,);
-                continue;
+            true
+        })
+        .collect();
+
+    let variables = extract_variables_from_body(statements, 0)
+        .into_iter()
+        .filter(|var_doc| {
+            if var_doc.docstring.is_none() && skip_undoc {
+                tracing::debug!(
+                    "skipping variable {} because it is undocumented",
+                    var_doc.name
+                );
+                return false;
             }
-            if class_doc.docstring.is_none() && skip_undoc {
+            if is_private_variable(var_doc) && skip_private {
+                tracing::debug!("skipping variable {} because it is private", var_doc.name);
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    let type_aliases = extract_type_aliases_from_body(statements, 0)
+        .into_iter()
+        .filter(|alias_doc| {
+            if alias_doc.docstring.is_none() && skip_undoc {
                 tracing::debug!(
-                    "skipping function {} because it is undocumented",
-                    class_doc.name,
+                    "skipping type alias {} because it is undocumented",
+                    alias_doc.name
                 );
-                continue;
-            };
-            class_definitions.push(class_doc);
-        }
-    }
+                return false;
+            }
+            if is_private_type_alias(alias_doc) && skip_private {
+                tracing::debug!(
+                    "skipping type alias {} because it is private",
+                    alias_doc.name
+                );
+                return false;
+            }
+            true
+        })
+        .collect();
 
     ModuleDocumentation {
         docstring,
         functions: free_functions,
         classes: class_definitions,
+        variables,
+        type_aliases,
         sub_modules: None,
         exports,
+        imports: extract_imports_from_body(statements),
+        members: vec![],
     }
 }
 
@@ -1,14 +1,47 @@
-use crate::indexing::object_ref::ObjectRef;
+use serde::Serialize;
 
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax};
+
+use super::attribute::AttributeDocumentation;
 use super::class::ClassDocumentation;
 use super::function::FunctionDocumentation;
 use super::module::ModuleDocumentation;
+use super::type_alias::TypeAliasDocumentation;
+use super::variable::VariableDocumentation;
+
+/// An object that is documented on a page of its own, as listed on the
+/// page of the module or class it is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Member {
+    pub name: String,
+    pub fully_qualified_name: String,
+    pub kind: MemberKind,
+    /// The first paragraph of the docstring
+    pub summary: Option<String>,
+}
 
-#[derive(Debug)]
+/// The order of the variants is the order members are listed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberKind {
+    Module,
+    Class,
+    Function,
+    Method,
+    Attribute,
+    Constant,
+    Variable,
+    TypeAlias,
+}
+
+#[derive(Debug, Clone)]
 pub enum ObjectDocumentation {
     Module(ModuleDocumentation),
     Class(ClassDocumentation),
     Function(FunctionDocumentation),
+    Attribute(AttributeDocumentation),
+    Variable(VariableDocumentation),
+    TypeAlias(TypeAliasDocumentation),
 }
 
 impl ObjectDocumentation {
@@ -23,18 +56,39 @@ pub fn docstring(&self) -> Option<String> {
             ObjectDocumentation::Function(function_documentation) => {
                 function_documentation.docstring.clone()
             }
+            ObjectDocumentation::Attribute(attribute_documentation) => {
+                attribute_documentation.docstring.clone()
+            }
+            ObjectDocumentation::Variable(variable_documentation) => {
+                variable_documentation.docstring.clone()
+            }
+            ObjectDocumentation::TypeAlias(type_alias_documentation) => {
+                type_alias_documentation.docstring.clone()
+            }
         }
     }
-    pub fn extract_used_references(&self) -> Option<(String, Vec<ObjectRef>)> {
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
         match self {
             ObjectDocumentation::Module(module_documentation) => {
-                module_documentation.extract_used_references()
+                module_documentation.extract_used_references(syntaxes)
             }
             ObjectDocumentation::Class(class_documentation) => {
-                class_documentation.extract_used_references()
+                class_documentation.extract_used_references(syntaxes)
             }
             ObjectDocumentation::Function(function_documentation) => {
-                function_documentation.extract_used_references()
+                function_documentation.extract_used_references(syntaxes)
+            }
+            ObjectDocumentation::Attribute(attribute_documentation) => {
+                attribute_documentation.extract_used_references(syntaxes)
+            }
+            ObjectDocumentation::Variable(variable_documentation) => {
+                variable_documentation.extract_used_references(syntaxes)
+            }
+            ObjectDocumentation::TypeAlias(type_alias_documentation) => {
+                type_alias_documentation.extract_used_references(syntaxes)
             }
         }
     }
@@ -50,6 +104,15 @@ pub(crate) fn replace_docstring(&mut self, object_docstring: Option<String>) {
             ObjectDocumentation::Function(function_documentation) => {
                 function_documentation.docstring = object_docstring;
             }
+            ObjectDocumentation::Attribute(attribute_documentation) => {
+                attribute_documentation.docstring = object_docstring;
+            }
+            ObjectDocumentation::Variable(variable_documentation) => {
+                variable_documentation.docstring = object_docstring;
+            }
+            ObjectDocumentation::TypeAlias(type_alias_documentation) => {
+                type_alias_documentation.docstring = object_docstring;
+            }
         }
     }
 }
@@ -0,0 +1,119 @@
+use rustpython_parser::ast::{Expr, Stmt, TypeParam};
+
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax, extract_object_refs};
+
+use super::{utils::extract_docstring_from_body, variable::is_type_alias_annotation};
+
+/// Either a PEP 695 `type Foo = int` statement or
+/// a PEP 613 `Foo: TypeAlias = int` annotation
+#[derive(Debug, Clone)]
+pub struct TypeAliasDocumentation {
+    pub name: String,
+    pub generics: Vec<TypeParam>,
+    pub value: Expr,
+    /// The string literal directly following the alias, if any
+    pub docstring: Option<String>,
+}
+
+impl TypeAliasDocumentation {
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
+        self.docstring
+            .as_ref()
+            .map(|s| (s.clone(), extract_object_refs(s, syntaxes)))
+    }
+}
+
+pub fn is_private_type_alias(alias_doc: &TypeAliasDocumentation) -> bool {
+    alias_doc.name.starts_with("_")
+}
+
+pub(crate) fn extract_type_aliases_from_body(
+    body: &[Stmt],
+    indent_level: usize,
+) -> Vec<TypeAliasDocumentation> {
+    let mut type_aliases = vec![];
+    for (idx, statement) in body.iter().enumerate() {
+        let (name, generics, value) = match statement {
+            Stmt::TypeAlias(type_alias) => match type_alias.name.as_name_expr() {
+                Some(n) => (
+                    n.id.to_string(),
+                    type_alias.type_params.clone(),
+                    *type_alias.value.clone(),
+                ),
+                None => continue,
+            },
+            Stmt::AnnAssign(ann_assign) if is_type_alias_annotation(&ann_assign.annotation) => {
+                match (ann_assign.target.as_name_expr(), &ann_assign.value) {
+                    (Some(n), Some(value)) => (n.id.to_string(), vec![], *value.clone()),
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        type_aliases.push(TypeAliasDocumentation {
+            name,
+            generics,
+            value,
+            docstring: extract_docstring_from_body(&body[idx + 1..], indent_level),
+        });
+    }
+    type_aliases
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        parsing::python::{module::extract_module_documentation, utils::parse_python_str},
+        render::expr::render_expr,
+    };
+
+    fn test_python_type_aliases() -> &'static str {
+        r#"
+from typing import TypeAlias
+import typing
+
+type Number = int | float
+'''Anything we can do arithmetic with'''
+
+type Pair[T] = tuple[T, T]
+Vector: TypeAlias = list[float]
+Matrix: typing.TypeAlias = list[Vector]
+'''A list of rows'''
+"#
+    }
+
+    #[test]
+    fn extract_type_aliases() -> Result<()> {
+        let program = parse_python_str(test_python_type_aliases())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        let names: Vec<&str> = documentation
+            .type_aliases
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Number", "Pair", "Vector", "Matrix"]);
+        assert!(documentation.variables.is_empty());
+
+        let number = &documentation.type_aliases[0];
+        assert_eq!(render_expr(number.value.clone()), "int | float");
+        assert_eq!(
+            number.docstring,
+            Some("Anything we can do arithmetic with".to_string())
+        );
+
+        assert_eq!(documentation.type_aliases[1].generics.len(), 1);
+        assert_eq!(
+            documentation.type_aliases[3].docstring,
+            Some("A list of rows".to_string())
+        );
+
+        Ok(())
+    }
+}
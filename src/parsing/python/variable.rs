@@ -0,0 +1,174 @@
+use rustpython_parser::ast::{Expr, Stmt};
+
+use crate::indexing::object_ref::{ObjectRef, ReferenceSyntax, extract_object_refs};
+
+use super::attribute::{AttributeDocumentation, extract_attributes_from_body};
+
+/// A module level variable or constant e.g.
+/// ```python
+/// MAX_RETRIES: int = 3
+/// """How often we try before giving up"""
+/// ```
+#[derive(Debug, Clone)]
+pub struct VariableDocumentation {
+    pub name: String,
+    pub type_annotation: Option<Expr>,
+    pub value: Option<Expr>,
+    /// The string literal directly following the assignment, if any
+    pub docstring: Option<String>,
+}
+
+impl VariableDocumentation {
+    pub fn extract_used_references(
+        &self,
+        syntaxes: &[ReferenceSyntax],
+    ) -> Option<(String, Vec<ObjectRef>)> {
+        self.docstring
+            .as_ref()
+            .map(|s| (s.clone(), extract_object_refs(s, syntaxes)))
+    }
+
+    /// Python has no real constants, so we follow the conventions of
+    /// PEP 8 (`UPPER_CASE` names) and PEP 591 (`Final` annotations)
+    pub fn is_constant(&self) -> bool {
+        let is_final = match &self.type_annotation {
+            Some(Expr::Name(n)) => n.id.as_str() == "Final",
+            Some(Expr::Attribute(a)) => a.attr.as_str() == "Final",
+            Some(Expr::Subscript(s)) => match &*s.value {
+                Expr::Name(n) => n.id.as_str() == "Final",
+                Expr::Attribute(a) => a.attr.as_str() == "Final",
+                _ => false,
+            },
+            _ => false,
+        };
+        let is_upper_case = self.name.chars().any(|c| c.is_alphabetic())
+            && !self.name.chars().any(|c| c.is_lowercase());
+
+        is_final || is_upper_case
+    }
+}
+
+impl From<AttributeDocumentation> for VariableDocumentation {
+    fn from(value: AttributeDocumentation) -> Self {
+        Self {
+            name: value.name,
+            type_annotation: value.type_annotation,
+            value: value.default,
+            docstring: value.docstring,
+        }
+    }
+}
+
+pub fn is_private_variable(var_doc: &VariableDocumentation) -> bool {
+    var_doc.name.starts_with("_")
+}
+
+/// Module level variables are looked up the same way as class attributes,
+/// except that `__all__` and type aliases are handled separately.
+pub(crate) fn extract_variables_from_body(
+    body: &[Stmt],
+    indent_level: usize,
+) -> Vec<VariableDocumentation> {
+    extract_attributes_from_body(body, indent_level)
+        .into_iter()
+        .filter(|a| a.name != "__all__")
+        .filter(|a| {
+            !a.type_annotation
+                .as_ref()
+                .is_some_and(is_type_alias_annotation)
+        })
+        .map(VariableDocumentation::from)
+        .collect()
+}
+
+/// PEP 613 explicit type aliases look like `Foo: TypeAlias = int`
+pub(crate) fn is_type_alias_annotation(annotation: &Expr) -> bool {
+    match annotation {
+        Expr::Name(n) => n.id.as_str() == "TypeAlias",
+        Expr::Attribute(a) => a.attr.as_str() == "TypeAlias",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        parsing::python::{module::extract_module_documentation, utils::parse_python_str},
+        render::expr::render_expr,
+    };
+
+    fn test_python_variables() -> &'static str {
+        r#"
+'''Module docstring'''
+from typing import Final, TypeAlias
+
+__all__ = ["MAX_RETRIES"]
+
+MAX_RETRIES: int = 3
+'''How often we try before giving up'''
+
+timeout: Final = 2.5
+verbose = False
+_cache = {}
+Number: TypeAlias = int | float
+"#
+    }
+
+    #[test]
+    fn extract_module_variables() -> Result<()> {
+        let program = parse_python_str(test_python_variables())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        let names: Vec<&str> = documentation
+            .variables
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["MAX_RETRIES", "timeout", "verbose", "_cache"]);
+
+        let max_retries = &documentation.variables[0];
+        assert_eq!(
+            max_retries.type_annotation.clone().map(render_expr),
+            Some("int".to_string())
+        );
+        assert_eq!(
+            max_retries.value.clone().map(render_expr),
+            Some("3".to_string())
+        );
+        assert_eq!(
+            max_retries.docstring,
+            Some("How often we try before giving up".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_constants() -> Result<()> {
+        let program = parse_python_str(test_python_variables())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        let constants: Vec<bool> = documentation
+            .variables
+            .iter()
+            .map(|v| v.is_constant())
+            .collect();
+        assert_eq!(constants, vec![true, true, false, false]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn skip_private_and_undocumented_variables() -> Result<()> {
+        let program = parse_python_str(test_python_variables())?;
+        let documentation = extract_module_documentation(&program, true, true);
+
+        assert_eq!(documentation.variables.len(), 1);
+        assert_eq!(documentation.variables[0].name, "MAX_RETRIES");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,521 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use rustpython_parser::ast::{Constant, Expr, ExprCall, ExprConstant, Mod, Stmt};
+use url::Url;
+
+use crate::{
+    config::{ConfigBuilder, ExternalIndex},
+    indexing::object_ref::ReferenceSyntax,
+    parsing::python::utils::{parse_python_file, parse_python_str},
+};
+
+/// The settings of a Sphinx `conf.py` that have a snakedown equivalent.
+///
+/// `conf.py` is never executed, only the assignments of literal values at the
+/// top level are read. Anything computed at runtime is skipped with a warning.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SphinxConf {
+    pub project: Option<String>,
+    /// `intersphinx_mapping`, inventories are relative to the directory of `conf.py`
+    pub externals: HashMap<String, ExternalIndex>,
+    pub exclude_patterns: Vec<String>,
+    /// Whether each of the `autodoc_default_options` is turned on
+    pub autodoc_default_options: Option<HashMap<String, bool>>,
+}
+
+impl SphinxConf {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let program = parse_python_file(path)
+            .map_err(|e| e.wrap_err(format!("could not parse {}", path.display())))?;
+        Ok(Self::from_module(&program))
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(Self::from_module(&parse_python_str(source)?))
+    }
+
+    fn from_module(program: &Mod) -> Self {
+        let mut conf = Self::default();
+        let Mod::Module(module) = program else {
+            return conf;
+        };
+        for stmt in &module.body {
+            match stmt {
+                Stmt::Assign(assign) => {
+                    for target in &assign.targets {
+                        conf.assign(target, &assign.value);
+                    }
+                }
+                Stmt::AnnAssign(assign) => {
+                    if let Some(value) = &assign.value {
+                        conf.assign(&assign.target, value);
+                    }
+                }
+                // intersphinx_mapping.update({...})
+                Stmt::Expr(expr) => {
+                    if let Expr::Call(call) = &*expr.value
+                        && let Expr::Attribute(attr) = &*call.func
+                        && attr.attr.as_str() == "update"
+                        && name_of(&attr.value) == Some("intersphinx_mapping")
+                    {
+                        for arg in &call.args {
+                            conf.add_intersphinx_mapping(arg);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        conf
+    }
+
+    fn assign(&mut self, target: &Expr, value: &Expr) {
+        match target {
+            Expr::Name(name) => match name.id.as_str() {
+                "project" => {
+                    self.project = string_of(value);
+                    if self.project.is_none() {
+                        tracing::warn!("skipping `project`, it is not a string literal");
+                    }
+                }
+                "intersphinx_mapping" => {
+                    self.externals.clear();
+                    self.add_intersphinx_mapping(value);
+                }
+                "exclude_patterns" => {
+                    if let Some(patterns) = strings_of(value) {
+                        self.exclude_patterns = patterns;
+                    } else {
+                        tracing::warn!(
+                            "skipping `exclude_patterns`, it is not a list of string literals"
+                        );
+                    }
+                }
+                "autodoc_default_options" => {
+                    self.autodoc_default_options = dict_items(value).map(|items| {
+                        items
+                            .into_iter()
+                            .map(|(option, value)| (option, !is_false(value)))
+                            .collect()
+                    });
+                    if self.autodoc_default_options.is_none() {
+                        tracing::warn!("skipping `autodoc_default_options`, it is not a dict");
+                    }
+                }
+                _ => {}
+            },
+            // intersphinx_mapping["numpy"] = (...)
+            Expr::Subscript(subscript)
+                if name_of(&subscript.value) == Some("intersphinx_mapping") =>
+            {
+                if let Some(key) = string_of(&subscript.slice) {
+                    self.add_intersphinx_entry(key, value);
+                } else {
+                    tracing::warn!(
+                        "skipping an intersphinx mapping, its key is not a string literal"
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_intersphinx_mapping(&mut self, mapping: &Expr) {
+        let Some(items) = dict_items(mapping) else {
+            tracing::warn!("skipping `intersphinx_mapping`, it is not a dict");
+            return;
+        };
+        for (key, value) in items {
+            self.add_intersphinx_entry(key, value);
+        }
+    }
+
+    /// Entries look like `"numpy": ("https://numpy.org/doc/stable/", None)`,
+    /// where the second item is where to find the inventory if it isn't next to
+    /// the docs. It can also be a tuple of places to try.
+    fn add_intersphinx_entry(&mut self, key: String, value: &Expr) {
+        if Url::parse(&key).is_ok_and(|url| url.has_host()) {
+            tracing::warn!(
+                "skipping the intersphinx mapping of {key}, the format without a name is not supported, add a name for it"
+            );
+            return;
+        }
+        let items = match value {
+            Expr::Tuple(tuple) => &tuple.elts,
+            Expr::List(list) => &list.elts,
+            _ => {
+                tracing::warn!("skipping the intersphinx mapping of {key}, it is not a tuple");
+                return;
+            }
+        };
+        let Some(url) = items.first().and_then(string_of) else {
+            tracing::warn!(
+                "skipping the intersphinx mapping of {key}, its url is not a string literal"
+            );
+            return;
+        };
+        let inventories = match items.get(1) {
+            Some(Expr::Tuple(tuple)) => tuple.elts.iter().filter_map(string_of).collect(),
+            Some(Expr::List(list)) => list.elts.iter().filter_map(string_of).collect(),
+            Some(inventory) => string_of(inventory).into_iter().collect(),
+            None => vec![],
+        };
+        // snakedown always downloads `objects.inv` next to the docs, so only
+        // inventories on disk can be used
+        let inventory = inventories.into_iter().find(|inventory| {
+            let remote = Url::parse(inventory).is_ok_and(|url| url.scheme() != "file");
+            if remote {
+                tracing::warn!(
+                    "ignoring the inventory of {key} at {inventory}, only inventories on disk are supported"
+                );
+            }
+            !remote
+        });
+        self.externals
+            .insert(key, ExternalIndex::new(None, url).with_inventory(inventory));
+    }
+
+    fn autodoc_option(&self, option: &str) -> Option<bool> {
+        self.autodoc_default_options
+            .as_ref()
+            .map(|options| options.get(option).copied().unwrap_or(false))
+    }
+
+    /// Autodoc leaves out undocumented members unless `undoc-members` is set
+    pub fn skip_undoc(&self) -> Option<bool> {
+        self.autodoc_option("undoc-members").map(|on| !on)
+    }
+
+    pub fn skip_private(&self) -> Option<bool> {
+        self.autodoc_option("private-members").map(|on| !on)
+    }
+
+    pub fn inherited_members(&self) -> Option<bool> {
+        self.autodoc_option("inherited-members")
+    }
+
+    /// Looks for a package named after the project in `root` or `root/src`.
+    /// The path that is returned is relative to `root`.
+    pub fn guess_pkg_path(&self, root: &Path) -> Option<PathBuf> {
+        let name = self
+            .project
+            .as_ref()?
+            .to_lowercase()
+            .replace(['-', ' '], "_");
+        [PathBuf::from(&name), PathBuf::from("src").join(&name)]
+            .into_iter()
+            .find(|candidate| root.join(candidate).join("__init__.py").is_file())
+    }
+
+    /// The externals with inventory paths relative to where snakedown is run
+    /// instead of to `conf_dir`, the directory `conf.py` is in
+    pub fn resolved_externals(&self, conf_dir: &Path) -> HashMap<String, ExternalIndex> {
+        self.externals
+            .iter()
+            .map(|(key, external)| {
+                let inventory = external.inventory.as_ref().map(|inventory| {
+                    let is_relative_path =
+                        Url::parse(inventory).is_err() && Path::new(inventory).is_relative();
+                    if is_relative_path {
+                        conf_dir.join(inventory).to_string_lossy().to_string()
+                    } else {
+                        inventory.clone()
+                    }
+                });
+                (key.clone(), external.clone().with_inventory(inventory))
+            })
+            .collect()
+    }
+
+    /// Translates the settings to a config, `conf_dir` is the directory `conf.py`
+    /// is in and `root` the directory snakedown will be run from.
+    pub fn to_config_builder(&self, conf_dir: &Path, root: &Path) -> ConfigBuilder {
+        let externals = self.resolved_externals(conf_dir);
+
+        // snakedown's exclude doesn't support globs (yet)
+        let (globs, paths): (Vec<_>, Vec<_>) = self
+            .exclude_patterns
+            .iter()
+            .partition(|pattern| pattern.contains(['*', '?', '[']));
+        for glob in globs {
+            tracing::warn!("skipping the exclude pattern {glob}, globs are not supported");
+        }
+        let exclude = paths
+            .into_iter()
+            .map(|path| conf_dir.join(path))
+            .collect::<Vec<_>>();
+
+        ConfigBuilder::default()
+            .with_pkg_path(self.guess_pkg_path(root))
+            .with_externals((!externals.is_empty()).then_some(externals))
+            .with_exclude((!exclude.is_empty()).then_some(exclude))
+            .with_skip_undoc(self.skip_undoc())
+            .with_skip_private(self.skip_private())
+            .with_inherited_members(self.inherited_members())
+            // the docstrings of a Sphinx project are written with roles
+            .with_reference_syntax(Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]))
+    }
+}
+
+/// The places a Sphinx project usually keeps its `conf.py`, relative to `root`
+pub fn discover_sphinx_conf(root: &Path) -> Option<PathBuf> {
+    ["docs", "doc"]
+        .into_iter()
+        .flat_map(|dir| [PathBuf::from(dir), PathBuf::from(dir).join("source")])
+        .map(|dir| dir.join("conf.py"))
+        .find(|candidate| root.join(candidate).is_file())
+}
+
+fn name_of(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Name(name) => Some(name.id.as_str()),
+        _ => None,
+    }
+}
+
+fn string_of(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Constant(ExprConstant {
+            value: Constant::Str(s),
+            ..
+        }) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn strings_of(expr: &Expr) -> Option<Vec<String>> {
+    let items = match expr {
+        Expr::List(list) => &list.elts,
+        Expr::Tuple(tuple) => &tuple.elts,
+        _ => return None,
+    };
+    items.iter().map(string_of).collect()
+}
+
+fn is_false(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Constant(ExprConstant {
+            value: Constant::Bool(false),
+            ..
+        })
+    )
+}
+
+/// The items of a dict literal or a `dict(key=value)` call with string keys.
+/// Items with keys that aren't string literals are skipped.
+fn dict_items(expr: &Expr) -> Option<Vec<(String, &Expr)>> {
+    match expr {
+        Expr::Dict(dict) => Some(
+            dict.keys
+                .iter()
+                .zip(&dict.values)
+                .filter_map(|(key, value)| {
+                    let key = key.as_ref().and_then(string_of);
+                    if key.is_none() {
+                        tracing::warn!("skipping a dict item, its key is not a string literal");
+                    }
+                    Some((key?, value))
+                })
+                .collect(),
+        ),
+        Expr::Call(ExprCall { func, keywords, .. }) if name_of(func) == Some("dict") => Some(
+            keywords
+                .iter()
+                .filter_map(|keyword| Some((keyword.arg.as_ref()?.to_string(), &keyword.value)))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Parses a `conf.py` and translates it, see [`SphinxConf::to_config_builder`]
+pub fn import_sphinx_conf(conf_path: &Path, root: &Path) -> Result<ConfigBuilder> {
+    let conf_dir = conf_path
+        .parent()
+        .ok_or_else(|| eyre!("{} is not a file", conf_path.display()))?;
+    Ok(SphinxConf::from_path(&root.join(conf_path))?.to_config_builder(conf_dir, root))
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::TempDir;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::config::{ConfigBuilder, ExternalIndex};
+    use crate::indexing::object_ref::ReferenceSyntax;
+    use crate::parsing::sphinx::conf::{SphinxConf, discover_sphinx_conf, import_sphinx_conf};
+
+    const CONF_PY: &str = r#"
+import os
+import sys
+
+sys.path.insert(0, os.path.abspath(".."))
+
+project = "test-pkg"
+author = "Snakedown"
+release = get_version()
+
+extensions = ["sphinx.ext.autodoc", "sphinx.ext.intersphinx"]
+
+exclude_patterns = ["_build", "drafts/*.rst", "notes.rst"]
+
+autodoc_default_options = {
+    "members": True,
+    "undoc-members": True,
+    "inherited-members": False,
+}
+
+intersphinx_mapping = {
+    "python": ("https://docs.python.org/3", None),
+    "numpy": ("https://numpy.org/doc/stable/", ("inventories/numpy.inv", None)),
+    "scipy": ("https://docs.scipy.org/doc/scipy/", "https://example.com/scipy/objects.inv"),
+    "computed": (os.environ["DOCS_URL"], None),
+}
+intersphinx_mapping["pandas"] = ("https://pandas.pydata.org/docs/", None)
+intersphinx_mapping.update(dict(xarray=["https://docs.xarray.dev/en/stable/", None]))
+"#;
+
+    fn external(url: &str, inventory: Option<&str>) -> ExternalIndex {
+        ExternalIndex::new(None, url.to_string()).with_inventory(inventory.map(str::to_string))
+    }
+
+    #[test]
+    fn literal_settings_are_read() -> Result<()> {
+        let conf = SphinxConf::parse(CONF_PY)?;
+
+        assert_eq!(conf.project, Some("test-pkg".to_string()));
+        assert_eq!(
+            conf.exclude_patterns,
+            vec!["_build", "drafts/*.rst", "notes.rst"]
+        );
+        assert_eq!(
+            conf.externals,
+            HashMap::from([
+                (
+                    "python".to_string(),
+                    external("https://docs.python.org/3/", None)
+                ),
+                (
+                    "numpy".to_string(),
+                    external(
+                        "https://numpy.org/doc/stable/",
+                        Some("inventories/numpy.inv")
+                    )
+                ),
+                (
+                    "scipy".to_string(),
+                    external("https://docs.scipy.org/doc/scipy/", None)
+                ),
+                (
+                    "pandas".to_string(),
+                    external("https://pandas.pydata.org/docs/", None)
+                ),
+                (
+                    "xarray".to_string(),
+                    external("https://docs.xarray.dev/en/stable/", None)
+                ),
+            ])
+        );
+        assert_eq!(conf.skip_undoc(), Some(false));
+        assert_eq!(conf.skip_private(), Some(true));
+        assert_eq!(conf.inherited_members(), Some(false));
+        Ok(())
+    }
+
+    #[test]
+    fn autodoc_settings_are_left_alone_without_default_options() -> Result<()> {
+        let conf = SphinxConf::parse("project = 'test-pkg'\n")?;
+
+        assert_eq!(conf.skip_undoc(), None);
+        assert_eq!(conf.skip_private(), None);
+        assert_eq!(conf.inherited_members(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn mappings_without_a_name_are_skipped() -> Result<()> {
+        let conf = SphinxConf::parse(
+            r#"
+intersphinx_mapping = {
+    "https://docs.python.org/3": None,
+    "numpy": ("https://numpy.org/doc/stable/", None),
+}
+"#,
+        )?;
+
+        assert_eq!(
+            conf.externals.keys().collect::<Vec<_>>(),
+            vec![&"numpy".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn conf_py_is_translated_to_a_config() -> Result<()> {
+        let root = TempDir::new()?;
+        fs::create_dir_all(root.join("docs"))?;
+        fs::create_dir_all(root.join("src/test_pkg"))?;
+        fs::write(root.join("src/test_pkg/__init__.py"), "")?;
+        fs::write(root.join("docs/conf.py"), CONF_PY)?;
+
+        let conf_path = discover_sphinx_conf(&root).unwrap_or_default();
+        assert_eq!(conf_path, PathBuf::from("docs/conf.py"));
+
+        let imported = import_sphinx_conf(&conf_path, &root)?;
+        let mut externals = SphinxConf::parse(CONF_PY)?.externals;
+        externals.insert(
+            "numpy".to_string(),
+            external(
+                "https://numpy.org/doc/stable/",
+                Some(
+                    &Path::new("docs")
+                        .join("inventories/numpy.inv")
+                        .to_string_lossy(),
+                ),
+            ),
+        );
+
+        assert_eq!(
+            imported,
+            ConfigBuilder::default()
+                .with_pkg_path(Some(PathBuf::from("src/test_pkg")))
+                .with_externals(Some(externals))
+                .with_exclude(Some(vec![
+                    PathBuf::from("docs/_build"),
+                    PathBuf::from("docs/notes.rst"),
+                ]))
+                .with_skip_undoc(Some(false))
+                .with_skip_private(Some(true))
+                .with_inherited_members(Some(false))
+                .with_reference_syntax(Some(vec![ReferenceSyntax::Wiki, ReferenceSyntax::Sphinx]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn no_conf_py_is_found_outside_the_usual_places() -> Result<()> {
+        let root = TempDir::new()?;
+        fs::create_dir_all(root.join("documentation"))?;
+        fs::write(root.join("documentation/conf.py"), CONF_PY)?;
+
+        assert_eq!(discover_sphinx_conf(&root), None);
+
+        fs::create_dir_all(root.join("doc/source"))?;
+        fs::write(root.join("doc/source/conf.py"), CONF_PY)?;
+
+        assert_eq!(
+            discover_sphinx_conf(&root),
+            Some(PathBuf::from("doc/source/conf.py"))
+        );
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 use color_eyre::{Result, eyre::eyre};
-use flate2::bufread::ZlibDecoder;
+use flate2::{Compression, bufread::ZlibDecoder, write::ZlibEncoder};
 use std::{
     fs::File,
-    io::{BufRead, BufReader, Read},
+    io::{BufRead, BufReader, Read, Write},
     path::Path,
 };
 use tracing::error;
@@ -31,33 +31,43 @@ fn parse_inv_version<R: Read>(reader: &mut BufReader<R>) -> Result<SphinxInvVers
     }
 }
 
-fn parse_inv_project_name<R: Read>(reader: &mut BufReader<R>) -> Result<String> {
+/// Reads a header line like `# Project: NumPy` and returns what comes after the prefix
+fn parse_inv_header_field<R: Read>(reader: &mut BufReader<R>, prefix: &str) -> Result<String> {
     let mut line = String::new();
     reader.read_line(&mut line)?;
-    // this is how sphinx itself does it https://github.com/sphinx-doc/sphinx/blob/ac3f74a3e0fbb326f73989a16dfa369e072064ca/sphinx/util/inventory.py#L126
-    let (_, proj_name) = line.split_at(11);
+    // sphinx skips the first 11 characters, see https://github.com/sphinx-doc/sphinx/blob/ac3f74a3e0fbb326f73989a16dfa369e072064ca/sphinx/util/inventory.py#L126
+    // but that doesn't tell us when the header isn't what we expect
+    let line = line.trim_end_matches(['\r', '\n']);
+    let value = line
+        .strip_prefix(prefix)
+        .or_else(|| line.strip_prefix(prefix.trim_end()))
+        .ok_or_else(|| {
+            eyre!(
+                "expected `{}` in inventory header, found `{line}`",
+                prefix.trim()
+            )
+        })?;
+    Ok(value.trim().to_string())
+}
 
-    Ok(proj_name.trim().to_string())
+fn parse_inv_project_name<R: Read>(reader: &mut BufReader<R>) -> Result<String> {
+    parse_inv_header_field(reader, "# Project: ")
 }
 
 fn parse_inv_project_version<R: Read>(reader: &mut BufReader<R>) -> Result<String> {
-    let mut line = String::new();
-    reader.read_line(&mut line)?;
-    // this is how sphinx itself does it https://github.com/sphinx-doc/sphinx/blob/ac3f74a3e0fbb326f73989a16dfa369e072064ca/sphinx/util/inventory.py#L126
-    let (_, proj_version) = line.split_at(11);
-
-    Ok(proj_version.trim().to_string())
+    parse_inv_header_field(reader, "# Version: ")
 }
 
 fn parse_sphinx_inv_header<R: Read>(
     reader: &mut BufReader<R>,
 ) -> Result<(SphinxInvVersion, String, String)> {
     let inv_version = parse_inv_version(reader)?;
-    if inv_version != SphinxInvVersion::V2 {
-        return Err(eyre!("Inventory file format not supported"));
-    }
     let inv_project_name = parse_inv_project_name(reader)?;
     let inv_project_version = parse_inv_project_version(reader)?;
+    // version 1 inventories aren't compressed
+    if inv_version == SphinxInvVersion::V1 {
+        return Ok((inv_version, inv_project_name, inv_project_version));
+    }
     let mut warning_header = String::new();
     reader.read_line(&mut warning_header)?;
 
@@ -82,12 +92,21 @@ fn decompress_remaining_zlib_data<R: Read>(reader: &mut BufReader<R>) -> Result<
 
 pub fn parse_objects_inv<R: Read>(mut reader: BufReader<R>) -> Result<Vec<ExternalSphinxRef>> {
     let mut references = vec![];
-    let (_inv_ver, _proj_name, _proj_ver) = parse_sphinx_inv_header(&mut reader)?;
+    let (inv_ver, _proj_name, _proj_ver) = parse_sphinx_inv_header(&mut reader)?;
 
-    let decompressed = decompress_remaining_zlib_data(&mut reader)?;
+    let (contents, parse_line): (String, fn(&str) -> Result<ExternalSphinxRef>) = match inv_ver {
+        SphinxInvVersion::V1 => {
+            let mut contents = String::new();
+            reader.read_to_string(&mut contents)?;
+            (contents, ExternalSphinxRef::from_v1_line)
+        }
+        SphinxInvVersion::V2 => (decompress_remaining_zlib_data(&mut reader)?, |line| {
+            ExternalSphinxRef::try_from(line)
+        }),
+    };
 
-    for line in decompressed.lines() {
-        match ExternalSphinxRef::try_from(line) {
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        match parse_line(line) {
             Ok(sr) => references.push(sr),
             Err(e) => error!("Error {} occurred while parsing line: {}", e, line),
         }
@@ -101,6 +120,30 @@ pub fn parse_objects_inv_file(path: &Path) -> Result<Vec<ExternalSphinxRef>> {
     parse_objects_inv(reader)
 }
 
+/// Writes a version 2 inventory in the format sphinx uses for `objects.inv`,
+/// so the references can be read by intersphinx or [`parse_objects_inv`].
+pub fn write_objects_inv<W: Write>(
+    mut writer: W,
+    project_name: &str,
+    project_version: &str,
+    references: &[ExternalSphinxRef],
+) -> Result<()> {
+    writeln!(writer, "# Sphinx inventory version 2")?;
+    writeln!(writer, "# Project: {project_name}")?;
+    writeln!(writer, "# Version: {project_version}")?;
+    writeln!(
+        writer,
+        "# The remainder of this file is compressed using zlib."
+    )?;
+
+    let mut encoder = ZlibEncoder::new(writer, Compression::default());
+    for reference in references {
+        writeln!(encoder, "{reference}")?;
+    }
+    encoder.finish()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use assert_fs::TempDir;
@@ -110,8 +153,8 @@ mod test {
     use std::path::PathBuf;
 
     use crate::parsing::sphinx::inv_file::{
-        SphinxInvVersion, decompress_remaining_zlib_data, parse_inv_version,
-        parse_objects_inv_file, parse_sphinx_inv_header,
+        SphinxInvVersion, decompress_remaining_zlib_data, parse_inv_version, parse_objects_inv,
+        parse_objects_inv_file, parse_sphinx_inv_header, write_objects_inv,
     };
     use crate::parsing::sphinx::types::ExternalSphinxRef;
 
@@ -169,6 +212,28 @@ fn test_short_header() -> Result<()> {
         assert!(result.is_err());
         Ok(())
     }
+    #[test]
+    fn test_truncated_header() -> Result<()> {
+        for header in [
+            "# Sphinx inventory version 2\n# Proj\n# Version: 2.3\n",
+            "# Sphinx inventory version 2\n# Project: NumPy\n# Ver\n",
+            "# Sphinx inventory version 2\n# Project: NumPy\n# Versión: 2.3\n",
+            "# Sphinx inventory version 2\n",
+        ] {
+            let mut reader = BufReader::new(header.as_bytes());
+            let result = parse_sphinx_inv_header(&mut reader);
+            assert!(result.is_err(), "{header}");
+        }
+
+        // an empty project name is fine, it is what sphinx writes without one
+        let mut reader =
+            BufReader::new("# Sphinx inventory version 1\n# Project:\n# Version: 2.3\n".as_bytes());
+        let (_, proj_name, proj_ver) = parse_sphinx_inv_header(&mut reader)?;
+        assert_eq!(proj_name, "");
+        assert_eq!(proj_ver, "2.3");
+        Ok(())
+    }
+
     #[test]
     fn test_no_zlib_header() -> Result<()> {
         let header = "# Sphinx inventory version 2
@@ -247,4 +312,98 @@ fn load_numpy_file_pub_func() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn written_inventory_can_be_read_back() -> Result<()> {
+        let references = [
+            "my_pkg py:module 0 api/my_pkg.md -",
+            "my_pkg.Greeter py:class 1 api/my_pkg.Greeter.md -",
+            "my_pkg.Greeter.greet py:method 1 api/my_pkg.Greeter.greet.md -",
+        ]
+        .into_iter()
+        .map(ExternalSphinxRef::try_from)
+        .collect::<Result<Vec<_>>>()?;
+
+        let mut inventory = vec![];
+        write_objects_inv(&mut inventory, "my_pkg", "1.0", &references)?;
+
+        let mut reader = BufReader::new(&inventory[..]);
+        let (inv_ver, proj_name, proj_ver) = parse_sphinx_inv_header(&mut reader)?;
+        assert_eq!(inv_ver, SphinxInvVersion::V2);
+        assert_eq!(proj_name, "my_pkg");
+        assert_eq!(proj_ver, "1.0");
+
+        let read_back = parse_objects_inv(BufReader::new(&inventory[..]))?;
+        assert_eq!(
+            read_back.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+            references.iter().map(|r| r.to_string()).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn v1_inventory() -> Result<()> {
+        let (_temp_dir, path) = write_test_header(
+            "# Sphinx inventory version 1
+# Project: Old
+# Version: 0.1
+old mod old.html
+old.thing class old.html
+
+old.thing.do method old.html
+",
+        )?;
+        let references = parse_objects_inv_file(&path)?
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            vec![
+                "old py:module 1 old.html#module-old -",
+                "old.thing py:class 1 old.html#old.thing -",
+                "old.thing.do py:method 1 old.html#old.thing.do -",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn odd_lines_dont_spoil_the_inventory() -> Result<()> {
+        use flate2::{Compression, write::ZlibEncoder};
+
+        let mut inventory = "# Sphinx inventory version 2
+# Project: Odd
+# Version: 1.0
+# The remainder of this file is compressed using zlib.
+"
+        .as_bytes()
+        .to_vec();
+        let mut encoder = ZlibEncoder::new(&mut inventory, Compression::default());
+        encoder.write_all(
+            "odd.Model py:pydantic_model 1 odd.html#$ -
+odd.Alias py:type 1 odd.html#$ -
+a label std:label -1 odd.html#a-label A label
+garbage
+odd.f py:function 1 odd.html#$ -
+"
+            .as_bytes(),
+        )?;
+        encoder.finish()?;
+
+        let references = parse_objects_inv(BufReader::new(&inventory[..]))?
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            vec![
+                "odd.Model py:pydantic_model 1 odd.html#odd.Model -",
+                "odd.Alias py:type 1 odd.html#odd.Alias -",
+                "a label std:label -1 odd.html#a-label A label",
+                "odd.f py:function 1 odd.html#odd.f -",
+            ]
+        );
+        Ok(())
+    }
 }
@@ -1,2 +1,3 @@
+pub mod conf;
 pub mod inv_file;
 pub mod types;
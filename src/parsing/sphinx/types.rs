@@ -1,8 +1,8 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{fmt::Display, path::PathBuf, str::FromStr};
 
 use color_eyre::eyre::eyre;
 use lazy_regex::regex_captures;
-use strum::EnumString;
+use strum::{Display as StrumDisplay, EnumString};
 
 #[derive(Debug, PartialEq)]
 pub enum SphinxPriority {
@@ -26,6 +26,18 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
     }
 }
 
+impl Display for SphinxPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let priority = match self {
+            SphinxPriority::Omit => "-1",
+            SphinxPriority::Standard => "1",
+            SphinxPriority::High => "0",
+            SphinxPriority::Low => "2",
+        };
+        write!(f, "{priority}")
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SphinxType {
     Std(StdRole),
@@ -35,29 +47,74 @@ pub enum SphinxType {
     JavaScript(JsRole),
     Mathematics(MathRole),
     ReStructuredText(RstRole),
+    /// Domains and roles we don't know about, e.g. those added by extensions.
+    /// They are kept so the rest of the inventory can still be used.
+    Other {
+        domain: String,
+        role: String,
+    },
 }
 
 impl TryFrom<&str> for SphinxType {
     type Error = color_eyre::Report;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value.split_once(":") {
-            Some((domain, role)) => match domain {
-                "std" => Ok(SphinxType::Std(StdRole::from_str(role)?)),
-                "c" => Ok(SphinxType::C(CRole::from_str(role)?)),
-                "cpp" => Ok(SphinxType::Cpp(CppRole::from_str(role)?)),
-                "py" => Ok(SphinxType::Python(PyRole::from_str(role)?)),
-                "js" => Ok(SphinxType::JavaScript(JsRole::from_str(role)?)),
-                "math" => Ok(SphinxType::Mathematics(MathRole::from_str(role)?)),
-                "rst" => Ok(SphinxType::ReStructuredText(RstRole::from_str(role)?)),
-                _ => Err(eyre!("Unknown domain: {}", domain)),
-            },
-            None => Err(eyre!("sphinx ref type did not contain :")),
+        let Some((domain, role)) = value.split_once(":") else {
+            return Err(eyre!("sphinx ref type did not contain :"));
+        };
+        let known = match domain {
+            "std" => StdRole::from_str(role).map(SphinxType::Std),
+            "c" => CRole::from_str(role).map(SphinxType::C),
+            "cpp" => CppRole::from_str(role).map(SphinxType::Cpp),
+            "py" => PyRole::from_str(role).map(SphinxType::Python),
+            "js" => JsRole::from_str(role).map(SphinxType::JavaScript),
+            "math" => MathRole::from_str(role).map(SphinxType::Mathematics),
+            "rst" => RstRole::from_str(role).map(SphinxType::ReStructuredText),
+            _ => Err(strum::ParseError::VariantNotFound),
+        };
+        Ok(known.unwrap_or_else(|_| SphinxType::Other {
+            domain: domain.to_string(),
+            role: role.to_string(),
+        }))
+    }
+}
+
+impl SphinxType {
+    /// What references to objects of this type are prefixed with, like the `c`
+    /// in `[[c:PyObject_GetAttr]]` or the `term` in `[[term:iterable]]`. Python
+    /// objects and documents are referenced by their name alone.
+    pub fn namespace(&self) -> Option<String> {
+        match self {
+            SphinxType::Python(_) | SphinxType::Std(StdRole::Doc) => None,
+            SphinxType::Other { domain, .. } if domain == "py" => None,
+            SphinxType::Std(role) => Some(role.to_string()),
+            SphinxType::Other { domain, role } if domain == "std" => Some(role.clone()),
+            SphinxType::Other { domain, .. } => Some(domain.clone()),
+            SphinxType::C(_) => Some("c".to_string()),
+            SphinxType::Cpp(_) => Some("cpp".to_string()),
+            SphinxType::JavaScript(_) => Some("js".to_string()),
+            SphinxType::Mathematics(_) => Some("math".to_string()),
+            SphinxType::ReStructuredText(_) => Some("rst".to_string()),
+        }
+    }
+}
+
+impl Display for SphinxType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SphinxType::Std(role) => write!(f, "std:{role}"),
+            SphinxType::C(role) => write!(f, "c:{role}"),
+            SphinxType::Python(role) => write!(f, "py:{role}"),
+            SphinxType::Cpp(role) => write!(f, "cpp:{role}"),
+            SphinxType::JavaScript(role) => write!(f, "js:{role}"),
+            SphinxType::Mathematics(role) => write!(f, "math:{role}"),
+            SphinxType::ReStructuredText(role) => write!(f, "rst:{role}"),
+            SphinxType::Other { domain, role } => write!(f, "{domain}:{role}"),
         }
     }
 }
 
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "kebab-case")]
 pub enum StdRole {
     Doc,
@@ -70,7 +127,7 @@ pub enum StdRole {
     MonitoringEvent,
     Envvar,
 }
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "camelCase")]
 pub enum CRole {
     Enum,
@@ -84,7 +141,7 @@ pub enum CRole {
     Struct,
     Union,
 }
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "camelCase")]
 pub enum CppRole {
     Class,
@@ -93,7 +150,7 @@ pub enum CppRole {
     Member,
     TemplateParam,
 }
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "camelCase")]
 pub enum JsRole {
     Module,
@@ -102,12 +159,12 @@ pub enum JsRole {
     Class,
     Data,
 }
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "camelCase")]
 pub enum MathRole {
     Numref,
 }
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "camelCase")]
 pub enum PyRole {
     Attribute,
@@ -115,11 +172,16 @@ pub enum PyRole {
     Exception,
     Function,
     Method,
+    #[strum(serialize = "classmethod")]
+    ClassMethod,
+    #[strum(serialize = "staticmethod")]
+    StaticMethod,
     Module,
     Property,
     Class,
+    Type,
 }
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, EnumString, StrumDisplay)]
 #[strum(serialize_all = "camelCase")]
 pub enum RstRole {}
 
@@ -133,19 +195,56 @@ pub struct ExternalSphinxRef {
     pub dispname: String,
 }
 
+impl ExternalSphinxRef {
+    /// Parses a line of a version 1 inventory, which only contains python
+    /// objects and leaves out the anchors on the page.
+    /// See https://github.com/sphinx-doc/sphinx/blob/ac3f74a3e0fbb326f73989a16dfa369e072064ca/sphinx/util/inventory.py#L95
+    pub fn from_v1_line(line: &str) -> color_eyre::Result<Self> {
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(kind), Some(location)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(eyre!("failed to parse line"));
+        };
+        let (sphinx_type, location) = if kind == "mod" {
+            (
+                SphinxType::Python(PyRole::Module),
+                format!("{location}#module-{name}"),
+            )
+        } else {
+            (
+                SphinxType::try_from(format!("py:{kind}").as_str())?,
+                format!("{location}#{name}"),
+            )
+        };
+        Ok(ExternalSphinxRef {
+            name: name.to_string(),
+            sphinx_type,
+            priority: SphinxPriority::Standard,
+            location,
+            dispname: name.to_string(),
+        })
+    }
+}
+
 impl TryFrom<&str> for ExternalSphinxRef {
     type Error = color_eyre::Report;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // names can contain spaces (e.g. labels), but the type always has a
+        // domain so we can tell where the name stops
         if let Some((_whole, name, sphinx_type, priority, location, dispname)) =
-            regex_captures!(r"(.+?)\s+(\S+)\s+(-?\d+)\s+?(\S*)\s+(.*)", &value)
+            regex_captures!(r"(.+?)\s+([^\s:]+:\S+)\s+(-?\d+)\s+?(\S*)\s+(.*)", &value)
         {
             let display_name = if dispname == "-" {
                 name.to_string()
             } else {
                 dispname.to_string()
             };
-            let location = location.replace("$", name);
+            // `$` is short for the name, but only at the end of the location
+            let location = match location.strip_suffix('$') {
+                Some(location) => format!("{location}{name}"),
+                None => location.to_string(),
+            };
             Ok(ExternalSphinxRef {
                 name: name.to_owned(),
                 sphinx_type: SphinxType::try_from(sphinx_type)?,
@@ -159,6 +258,22 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
     }
 }
 
+impl Display for ExternalSphinxRef {
+    /// Formats the reference as a line of an inventory, the inverse of parsing it
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dispname = if self.dispname == self.name {
+            "-"
+        } else {
+            &self.dispname
+        };
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.name, self.sphinx_type, self.priority, self.location, dispname
+        )
+    }
+}
+
 pub type Dispname = String;
 pub type RelPath = PathBuf;
 
@@ -311,4 +426,104 @@ fn test_sphinx_type_parsing_std() -> Result<()> {
         );
         Ok(())
     }
+
+    #[test]
+    fn sphinx_refs_format_as_inventory_lines() -> Result<()> {
+        for line in [
+            "numpy.ndarray py:class 1 reference/generated/numpy.ndarray.html -",
+            "numpy.char.add py:function 1 reference/generated/numpy.char.add.html -",
+            "monitoring-event std:label -1 library/sys.monitoring.html Monitoring events",
+            "c.PyObject_Call c:function 1 c-api/call.html -",
+        ] {
+            assert_eq!(ExternalSphinxRef::try_from(line)?.to_string(), line);
+        }
+        assert_eq!(
+            SphinxType::Std(StdRole::MonitoringEvent).to_string(),
+            "std:monitoring-event"
+        );
+        assert_eq!(SphinxPriority::High.to_string(), "0");
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_domains_and_roles_are_kept() -> Result<()> {
+        assert_eq!(
+            SphinxType::try_from("py:type")?,
+            SphinxType::Python(PyRole::Type)
+        );
+        assert_eq!(
+            SphinxType::try_from("py:classmethod")?,
+            SphinxType::Python(PyRole::ClassMethod)
+        );
+        assert_eq!(
+            SphinxType::try_from("py:pydantic_model")?,
+            SphinxType::Other {
+                domain: "py".to_string(),
+                role: "pydantic_model".to_string()
+            }
+        );
+        assert_eq!(
+            SphinxType::try_from("http:get")?,
+            SphinxType::Other {
+                domain: "http".to_string(),
+                role: "get".to_string()
+            }
+        );
+        assert!(SphinxType::try_from("nodomain").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn odd_inventory_lines() -> Result<()> {
+        let label = ExternalSphinxRef::try_from(
+            "installing packages std:label -1 install.html#installing-packages Installing packages",
+        )?;
+        assert_eq!(label.name, "installing packages");
+        assert_eq!(label.sphinx_type, SphinxType::Std(StdRole::Label));
+        assert_eq!(label.location, "install.html#installing-packages");
+        assert_eq!(label.dispname, "Installing packages");
+
+        let shorthand = ExternalSphinxRef::try_from("numpy.ndarray py:class 1 api.html#$ -")?;
+        assert_eq!(shorthand.location, "api.html#numpy.ndarray");
+        // only a trailing `$` is short for the name
+        let dollar = ExternalSphinxRef::try_from("price py:data 1 $/prices.html -")?;
+        assert_eq!(dollar.location, "$/prices.html");
+
+        let get = ExternalSphinxRef::try_from("/users http:get 1 api.html#get--users GET /users")?;
+        assert_eq!(get.name, "/users");
+        assert_eq!(get.dispname, "GET /users");
+        Ok(())
+    }
+
+    #[test]
+    fn v1_inventory_lines() -> Result<()> {
+        let module = ExternalSphinxRef::from_v1_line("foo.bar mod foo/bar.html")?;
+        assert_eq!(module.sphinx_type, SphinxType::Python(PyRole::Module));
+        assert_eq!(module.location, "foo/bar.html#module-foo.bar");
+
+        let function = ExternalSphinxRef::from_v1_line("foo.bar.baz function foo/bar.html")?;
+        assert_eq!(function.sphinx_type, SphinxType::Python(PyRole::Function));
+        assert_eq!(function.location, "foo/bar.html#foo.bar.baz");
+        assert_eq!(function.dispname, "foo.bar.baz");
+
+        assert!(ExternalSphinxRef::from_v1_line("foo.bar").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn only_python_objects_and_documents_have_no_namespace() -> Result<()> {
+        let namespace = |sphinx_type: &str| -> Result<Option<String>> {
+            Ok(SphinxType::try_from(sphinx_type)?.namespace())
+        };
+        assert_eq!(namespace("py:function")?, None);
+        assert_eq!(namespace("py:pydantic_model")?, None);
+        assert_eq!(namespace("std:doc")?, None);
+        assert_eq!(namespace("std:term")?, Some("term".to_string()));
+        assert_eq!(namespace("std:label")?, Some("label".to_string()));
+        assert_eq!(namespace("std:confval")?, Some("confval".to_string()));
+        assert_eq!(namespace("c:function")?, Some("c".to_string()));
+        assert_eq!(namespace("cpp:class")?, Some("cpp".to_string()));
+        assert_eq!(namespace("np:kernel")?, Some("np".to_string()));
+        Ok(())
+    }
 }
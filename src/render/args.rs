@@ -1,55 +1,122 @@
 use rustpython_parser::ast::{Arg, ArgWithDefault, Arguments};
+use serde::Serialize;
 
-use super::expr::render_expr;
+use super::expr::{annotation_names, render_expr};
 
-pub(crate) fn render_args(args: Arguments) -> String {
-    let mut out = String::new();
+/// A parameter of a function, as it is handed to the templates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Parameter {
+    pub name: String,
+    pub kind: ParameterKind,
+    pub annotation: Option<String>,
+    /// The dotted names used in the annotation, e.g. `typing.Dict` and `str`
+    /// for `typing.Dict[str, int]`, for templates that want to link them
+    pub annotation_names: Vec<String>,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterKind {
+    PositionalOnly,
+    PositionalOrKeyword,
+    VarPositional,
+    KeywordOnly,
+    VarKeyword,
+}
 
-    out.push_str(
-        &args
-            .posonlyargs
-            .into_iter()
-            .map(render_arg_with_default)
-            .collect::<Vec<_>>()
-            .join(", "),
+/// Lists the parameters in the order they appear in the signature
+pub(crate) fn parameters(args: &Arguments) -> Vec<Parameter> {
+    let with_default = |arg: &ArgWithDefault, kind| Parameter {
+        name: arg.def.arg.to_string(),
+        kind,
+        annotation: arg.def.annotation.clone().map(|a| render_expr(*a)),
+        annotation_names: arg
+            .def
+            .annotation
+            .as_deref()
+            .map(annotation_names)
+            .unwrap_or_default(),
+        default: arg.default.clone().map(|d| render_expr(*d)),
+    };
+    let variadic = |arg: &Arg, kind| Parameter {
+        name: arg.arg.to_string(),
+        kind,
+        annotation: arg.annotation.clone().map(|a| render_expr(*a)),
+        annotation_names: arg
+            .annotation
+            .as_deref()
+            .map(annotation_names)
+            .unwrap_or_default(),
+        default: None,
+    };
+
+    let mut params: Vec<Parameter> = args
+        .posonlyargs
+        .iter()
+        .map(|a| with_default(a, ParameterKind::PositionalOnly))
+        .collect();
+    params.extend(
+        args.args
+            .iter()
+            .map(|a| with_default(a, ParameterKind::PositionalOrKeyword)),
     );
-    out.push_str(
-        &args
-            .args
-            .into_iter()
-            .map(render_arg_with_default)
-            .collect::<Vec<_>>()
-            .join(", "),
+    if let Some(vararg) = &args.vararg {
+        params.push(variadic(vararg, ParameterKind::VarPositional));
+    }
+    params.extend(
+        args.kwonlyargs
+            .iter()
+            .map(|a| with_default(a, ParameterKind::KeywordOnly)),
     );
-    if let Some(varg) = &args.vararg {
-        out.push_str(", *");
-        out.push_str(&render_arg(*varg.clone()));
+    if let Some(kwarg) = &args.kwarg {
+        params.push(variadic(kwarg, ParameterKind::VarKeyword));
     }
+    params
+}
 
-    if !args.kwonlyargs.is_empty() {
-        out.push_str(", ");
-        out.push_str(
-            &args
-                .kwonlyargs
-                .into_iter()
-                .map(render_arg_with_default)
-                .collect::<Vec<_>>()
-                .join(", "),
-        );
+pub(crate) fn render_args(args: Arguments) -> String {
+    render_parameters(args).join(", ")
+}
+
+/// Renders every parameter on its own, together with the `/` (PEP 570)
+/// and bare `*` (PEP 3102) markers where the signature needs them
+pub(crate) fn render_parameters(args: Arguments) -> Vec<String> {
+    let mut params: Vec<String> = args
+        .posonlyargs
+        .into_iter()
+        .map(render_arg_with_default)
+        .collect();
+    if !params.is_empty() {
+        params.push(String::from("/"));
     }
-    if let Some(kwarg) = args.kwarg {
-        out.push_str(", **");
-        out.push_str(&render_arg(*kwarg));
+    params.extend(args.args.into_iter().map(render_arg_with_default));
+
+    match args.vararg {
+        Some(varg) => params.push(format!("*{}", render_arg(*varg))),
+        None if !args.kwonlyargs.is_empty() => params.push(String::from("*")),
+        None => {}
     }
+    params.extend(args.kwonlyargs.into_iter().map(render_arg_with_default));
 
-    out
+    if let Some(kwarg) = args.kwarg {
+        params.push(format!("**{}", render_arg(*kwarg)));
+    }
+    params
 }
+
 pub(crate) fn render_arg_with_default(arg: ArgWithDefault) -> String {
     let mut out = String::new();
 
+    // PEP 8 only puts spaces around the `=` if the parameter is annotated
+    let separator = if arg.def.annotation.is_some() {
+        " = "
+    } else {
+        "="
+    };
     out.push_str(&render_arg(arg.def));
     if let Some(default) = arg.default {
-        out.push_str(" = ");
+        out.push_str(separator);
         out.push_str(&render_expr(*default));
     }
 
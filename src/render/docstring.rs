@@ -0,0 +1,254 @@
+use crate::{
+    parsing::docstring::{Docstring, DocstringParameter, DocstringReturn},
+    render::formats::Renderer,
+};
+
+pub fn render_docstring<R: Renderer>(docstring: &Docstring, renderer: &R) -> String {
+    let mut blocks: Vec<String> = vec![];
+
+    if let Some(summary) = &docstring.summary {
+        blocks.push(summary.clone());
+    }
+    if let Some(description) = &docstring.description {
+        blocks.push(description.clone());
+    }
+
+    if !docstring.parameters.is_empty() {
+        blocks.push(renderer.render_header("Parameters", 2));
+        blocks.push(render_parameters_table(&docstring.parameters));
+    }
+
+    if !docstring.returns.is_empty() {
+        blocks.push(renderer.render_header("Returns", 2));
+        blocks.push(render_returns_table(&docstring.returns));
+    }
+
+    if !docstring.yields.is_empty() {
+        blocks.push(renderer.render_header("Yields", 2));
+        blocks.push(render_returns_table(&docstring.yields));
+    }
+
+    if !docstring.raises.is_empty() {
+        blocks.push(renderer.render_header("Raises", 2));
+        blocks.push(render_table(
+            &["Exception", "Description"],
+            docstring
+                .raises
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.exception.clone(),
+                        r.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+
+    if !docstring.attributes.is_empty() {
+        blocks.push(renderer.render_header("Attributes", 2));
+        blocks.push(render_parameters_table(&docstring.attributes));
+    }
+
+    if let Some(examples) = &docstring.examples {
+        blocks.push(renderer.render_header("Examples", 2));
+        blocks.push(fence_doctests(examples));
+    }
+
+    for (title, content) in [
+        ("See Also", &docstring.see_also),
+        ("Notes", &docstring.notes),
+        ("Warnings", &docstring.warnings),
+    ] {
+        if let Some(c) = content {
+            blocks.push(renderer.render_header(title, 2));
+            blocks.push(c.clone());
+        }
+    }
+
+    for section in &docstring.other_sections {
+        blocks.push(renderer.render_header(&section.title, 2));
+        blocks.push(section.content.clone());
+    }
+
+    blocks.join("\n\n")
+}
+
+fn render_parameters_table(parameters: &[DocstringParameter]) -> String {
+    let any_default = parameters.iter().any(|p| p.default.is_some());
+    let headers: &[&str] = if any_default {
+        &["Name", "Type", "Default", "Description"]
+    } else {
+        &["Name", "Type", "Description"]
+    };
+    render_table(
+        headers,
+        parameters
+            .iter()
+            .map(|p| {
+                let mut row = vec![
+                    format!("`{}`", p.name),
+                    p.type_annotation.clone().unwrap_or_default(),
+                ];
+                if any_default {
+                    row.push(p.default.clone().unwrap_or_default());
+                }
+                row.push(p.description.clone().unwrap_or_default());
+                row
+            })
+            .collect(),
+    )
+}
+
+fn render_returns_table(returns: &[DocstringReturn]) -> String {
+    let any_named = returns.iter().any(|r| r.name.is_some());
+    if any_named {
+        render_table(
+            &["Name", "Type", "Description"],
+            returns
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.name
+                            .as_ref()
+                            .map(|n| format!("`{n}`"))
+                            .unwrap_or_default(),
+                        r.type_annotation.clone().unwrap_or_default(),
+                        r.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        )
+    } else {
+        render_table(
+            &["Type", "Description"],
+            returns
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.type_annotation.clone().unwrap_or_default(),
+                        r.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        )
+    }
+}
+
+pub(crate) fn render_table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut lines = vec![
+        format!("| {} |", headers.join(" | ")),
+        format!(
+            "| {} |",
+            headers
+                .iter()
+                .map(|h| "-".repeat(h.len()))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+    ];
+    for row in rows {
+        lines.push(format!(
+            "| {} |",
+            row.iter()
+                .map(|cell| escape_table_cell(cell))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+    }
+    lines.join("\n")
+}
+
+fn escape_table_cell(cell: &str) -> String {
+    // markdown tables can't contain newlines and use pipes as separators
+    cell.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// Wrap doctest style examples (lines starting with `>>>` and their output)
+/// in python code blocks so they get highlighted properly
+pub(crate) fn fence_doctests(text: &str) -> String {
+    if text.contains("```") {
+        return text.to_string();
+    }
+    let mut out: Vec<String> = vec![];
+    let mut in_block = false;
+    for line in text.lines() {
+        if !in_block && line.trim_start().starts_with(">>>") {
+            out.push("```python".to_string());
+            in_block = true;
+        } else if in_block && line.trim().is_empty() {
+            out.push("```".to_string());
+            in_block = false;
+        }
+        out.push(line.to_string());
+    }
+    if in_block {
+        out.push("```".to_string());
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        parsing::docstring::{DocstringStyle, parse_docstring},
+        render::formats::md::MdRenderer,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render_google_docstring() {
+        let docstring = parse_docstring(
+            r#"Return the sum of two numbers.
+
+Args:
+    a (float): First number.
+    b (float): Second | number.
+
+Returns:
+    float: Sum of a and b.
+
+Example:
+    >>> add(2.5, 4.5)
+    7.0"#,
+            DocstringStyle::Auto,
+        );
+
+        let expected = r#"Return the sum of two numbers.
+
+## Parameters
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| `a` | float | First number. |
+| `b` | float | Second \| number. |
+
+## Returns
+
+| Type | Description |
+| ---- | ----------- |
+| float | Sum of a and b. |
+
+## Examples
+
+```python
+>>> add(2.5, 4.5)
+7.0
+```"#;
+
+        assert_eq!(render_docstring(&docstring, &MdRenderer::new()), expected);
+    }
+
+    #[test]
+    fn fence_multiple_doctests() {
+        let text = "Some prose.\n>>> a = 1\n>>> a\n1\n\nMore prose.\n>>> b";
+        assert_eq!(
+            fence_doctests(text),
+            "Some prose.\n```python\n>>> a = 1\n>>> a\n1\n```\n\nMore prose.\n```python\n>>> b\n```"
+        );
+    }
+}
@@ -1,4 +1,14 @@
-use rustpython_parser::ast::{CmpOp, Comprehension, Constant, Expr, Keyword, Operator, UnaryOp};
+use rustpython_parser::{
+    Mode,
+    ast::{
+        CmpOp, Comprehension, Constant, ConversionFlag, Expr, ExprConstant, ExprContext, ExprName,
+        Identifier, Keyword, Mod, Operator, TypeParam, UnaryOp,
+    },
+    parse,
+    text_size::TextRange,
+};
+
+use color_eyre::Result;
 
 use super::args::render_args;
 
@@ -171,6 +181,7 @@ pub fn render_expr(expr: Expr) -> String {
                 });
         }
         Expr::Call(expr_call) => {
+            let has_args = !expr_call.args.is_empty();
             out.push_str(&render_expr(*expr_call.func));
             out.push('(');
             out.push_str(
@@ -182,7 +193,9 @@ pub fn render_expr(expr: Expr) -> String {
                     .join(", "),
             );
             if !expr_call.keywords.is_empty() {
-                out.push_str(", ");
+                if has_args {
+                    out.push_str(", ");
+                }
                 out.push_str(
                     &expr_call
                         .keywords
@@ -194,8 +207,19 @@ pub fn render_expr(expr: Expr) -> String {
             }
             out.push(')');
         }
-        Expr::FormattedValue(expr_formatted_value) => todo!(),
-        Expr::JoinedStr(expr_joined_str) => todo!(),
+        Expr::FormattedValue(expr_formatted_value) => {
+            out.push_str(&format!(
+                "f\"{}\"",
+                render_fstring_part(Expr::FormattedValue(expr_formatted_value))
+            ));
+        }
+        Expr::JoinedStr(expr_joined_str) => {
+            out.push_str("f\"");
+            for value in expr_joined_str.values {
+                out.push_str(&render_fstring_part(value));
+            }
+            out.push('"');
+        }
         Expr::Constant(expr_constant) => out.push_str(&render_constant(expr_constant.value)),
         Expr::Attribute(expr_attribute) => {
             out.push_str(&format!(
@@ -259,6 +283,41 @@ pub fn render_expr(expr: Expr) -> String {
     out.trim().to_string()
 }
 
+/// Renders a part of an f-string without the surrounding quotes
+fn render_fstring_part(expr: Expr) -> String {
+    match expr {
+        Expr::Constant(ExprConstant {
+            value: Constant::Str(s),
+            ..
+        }) => s.replace('{', "{{").replace('}', "}}"),
+        Expr::FormattedValue(formatted) => {
+            let mut out = String::from("{");
+            out.push_str(&render_expr(*formatted.value));
+            match formatted.conversion {
+                ConversionFlag::None => (),
+                ConversionFlag::Str => out.push_str("!s"),
+                ConversionFlag::Ascii => out.push_str("!a"),
+                ConversionFlag::Repr => out.push_str("!r"),
+            }
+            if let Some(spec) = formatted.format_spec {
+                out.push(':');
+                match *spec {
+                    Expr::JoinedStr(joined) => {
+                        for value in joined.values {
+                            out.push_str(&render_fstring_part(value));
+                        }
+                    }
+                    other => out.push_str(&render_fstring_part(other)),
+                }
+            }
+            out.push('}');
+            out
+        }
+        Expr::JoinedStr(joined) => joined.values.into_iter().map(render_fstring_part).collect(),
+        other => render_expr(other),
+    }
+}
+
 fn render_comprehension(comp: Comprehension) -> String {
     let mut out = String::new();
     out.push_str("for ");
@@ -292,7 +351,7 @@ pub(crate) fn render_constant(constant: Constant) -> String {
             }
         }
         Constant::Str(s) => format!("\"{s}\""),
-        Constant::Bytes(_) => todo!(),
+        Constant::Bytes(bytes) => format!("b\"{}\"", bytes.escape_ascii()),
         Constant::Int(big_int) => format!("{big_int}"),
         Constant::Tuple(constants) => format!(
             "({})",
@@ -302,7 +361,8 @@ pub(crate) fn render_constant(constant: Constant) -> String {
                 .collect::<Vec<_>>()
                 .join(", ")
         ),
-        Constant::Float(f) => format!("{f}"),
+        // `{f}` would drop the `.0` of whole numbers, making them look like ints
+        Constant::Float(f) => format!("{f:?}"),
         Constant::Complex { real, imag } => {
             if real.abs() < f64::EPSILON {
                 format!("{imag}j")
@@ -316,6 +376,265 @@ pub(crate) fn render_constant(constant: Constant) -> String {
     }
 }
 
+/// The dotted names an annotation refers to, in the order they appear.
+/// String annotations (forward references) are parsed as well.
+pub(crate) fn annotation_names(expr: &Expr) -> Vec<String> {
+    let mut names = vec![];
+    collect_names(expr, true, &mut names);
+    names
+}
+
+/// The dotted names a value such as a default refers to, e.g. `Color.RED`
+/// and `Path` for `Path(Color.RED)`. Strings are left alone.
+pub(crate) fn value_names(expr: &Expr) -> Vec<String> {
+    let mut names = vec![];
+    collect_names(expr, false, &mut names);
+    names
+}
+
+fn collect_names(expr: &Expr, forward_refs: bool, names: &mut Vec<String>) {
+    match expr {
+        Expr::Name(_) | Expr::Attribute(_) => {
+            if let Some(name) = dotted_name(expr)
+                && !names.contains(&name)
+            {
+                names.push(name);
+            }
+        }
+        Expr::Subscript(subscript) => {
+            collect_names(&subscript.value, forward_refs, names);
+            collect_names(&subscript.slice, forward_refs, names);
+        }
+        Expr::Tuple(tuple) => tuple
+            .elts
+            .iter()
+            .for_each(|e| collect_names(e, forward_refs, names)),
+        Expr::List(list) => list
+            .elts
+            .iter()
+            .for_each(|e| collect_names(e, forward_refs, names)),
+        Expr::BinOp(bin_op) => {
+            collect_names(&bin_op.left, forward_refs, names);
+            collect_names(&bin_op.right, forward_refs, names);
+        }
+        Expr::UnaryOp(unary_op) => collect_names(&unary_op.operand, forward_refs, names),
+        Expr::Call(call) => {
+            collect_names(&call.func, forward_refs, names);
+            call.args
+                .iter()
+                .chain(call.keywords.iter().map(|k| &k.value))
+                .for_each(|e| collect_names(e, forward_refs, names));
+        }
+        Expr::Constant(ExprConstant {
+            value: Constant::Str(forward_ref),
+            ..
+        }) if forward_refs => {
+            if let Ok(Mod::Expression(parsed)) =
+                parse(forward_ref, Mode::Expression, "<annotation>")
+            {
+                collect_names(&parsed.body, forward_refs, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn dotted_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Name(name) => Some(name.id.to_string()),
+        Expr::Attribute(attr) => {
+            dotted_name(&attr.value).map(|prefix| format!("{}.{}", prefix, attr.attr))
+        }
+        _ => None,
+    }
+}
+
+/// Replaces the strings in an annotation (forward references) with the
+/// expressions they contain, e.g. `list["Node"]` becomes `list[Node]`. The
+/// strings of `Literal` and the metadata of `Annotated` are left alone.
+pub(crate) fn unquote_forward_refs(expr: Expr) -> Expr {
+    match expr {
+        Expr::Constant(ExprConstant {
+            value: Constant::Str(ref forward_ref),
+            ..
+        }) => match parse(forward_ref, Mode::Expression, "<annotation>") {
+            Ok(Mod::Expression(parsed)) => unquote_forward_refs(*parsed.body),
+            _ => expr,
+        },
+        Expr::Subscript(mut subscript) => {
+            let name = dotted_name(&subscript.value).unwrap_or_default();
+            let generic = name.rsplit('.').next().unwrap_or_default();
+            match (generic, *subscript.slice) {
+                ("Literal", slice) => subscript.slice = Box::new(slice),
+                ("Annotated", Expr::Tuple(mut tuple)) if !tuple.elts.is_empty() => {
+                    let annotated = tuple.elts.remove(0);
+                    tuple.elts.insert(0, unquote_forward_refs(annotated));
+                    subscript.slice = Box::new(Expr::Tuple(tuple));
+                }
+                (_, slice) => subscript.slice = Box::new(unquote_forward_refs(slice)),
+            }
+            Expr::Subscript(subscript)
+        }
+        Expr::Tuple(mut tuple) => {
+            tuple.elts = tuple.elts.into_iter().map(unquote_forward_refs).collect();
+            Expr::Tuple(tuple)
+        }
+        Expr::List(mut list) => {
+            list.elts = list.elts.into_iter().map(unquote_forward_refs).collect();
+            Expr::List(list)
+        }
+        Expr::BinOp(mut bin_op) => {
+            bin_op.left = Box::new(unquote_forward_refs(*bin_op.left));
+            bin_op.right = Box::new(unquote_forward_refs(*bin_op.right));
+            Expr::BinOp(bin_op)
+        }
+        _ => expr,
+    }
+}
+
+/// Renders an expression as markdown, with the dotted names in it replaced by
+/// whatever `link` returns for them. Only names in the code itself are linked,
+/// not the contents of strings. Everything that isn't a link is escaped, so
+/// e.g. `Dict[str, int]` isn't mistaken for a link and the underscores of
+/// `_private` aren't read as emphasis. Returns `None` if nothing was linked.
+pub(crate) fn link_names<F>(expr: Expr, mut link: F) -> Result<Option<String>>
+where
+    F: FnMut(&str) -> Result<Option<String>>,
+{
+    let mut links = vec![];
+    let expr = replace_linked_names(expr, &mut link, &mut links)?;
+    if links.is_empty() {
+        return Ok(None);
+    }
+
+    let mut rendered = escape_markdown(&render_expr(expr));
+    for (i, linked) in links.iter().enumerate() {
+        rendered = rendered.replace(&link_placeholder(i), linked);
+    }
+    Ok(Some(rendered))
+}
+
+/// Stands in for a link while the expression is rendered. These characters
+/// can't appear in a python name and are left alone by [`escape_markdown`].
+fn link_placeholder(i: usize) -> String {
+    format!("\u{E000}{i}\u{E001}")
+}
+
+fn replace_linked_names<F>(expr: Expr, link: &mut F, links: &mut Vec<String>) -> Result<Expr>
+where
+    F: FnMut(&str) -> Result<Option<String>>,
+{
+    if let Some(name) = dotted_name(&expr)
+        && let Some(linked) = link(&name)?
+    {
+        links.push(linked);
+        return Ok(Expr::Name(ExprName {
+            range: TextRange::default(),
+            id: Identifier::new(link_placeholder(links.len() - 1)),
+            ctx: ExprContext::Load,
+        }));
+    }
+
+    let mut replace = |e: Expr| replace_linked_names(e, link, links);
+    Ok(match expr {
+        // e.g. the `Path(...)` of `Path(...).parent`
+        Expr::Attribute(mut attr) if dotted_name(&attr.value).is_none() => {
+            attr.value = Box::new(replace(*attr.value)?);
+            Expr::Attribute(attr)
+        }
+        Expr::Subscript(mut subscript) => {
+            subscript.value = Box::new(replace(*subscript.value)?);
+            subscript.slice = Box::new(replace(*subscript.slice)?);
+            Expr::Subscript(subscript)
+        }
+        Expr::Tuple(mut tuple) => {
+            tuple.elts = tuple.elts.into_iter().map(replace).collect::<Result<_>>()?;
+            Expr::Tuple(tuple)
+        }
+        Expr::List(mut list) => {
+            list.elts = list.elts.into_iter().map(replace).collect::<Result<_>>()?;
+            Expr::List(list)
+        }
+        Expr::Set(mut set) => {
+            set.elts = set.elts.into_iter().map(replace).collect::<Result<_>>()?;
+            Expr::Set(set)
+        }
+        Expr::Dict(mut dict) => {
+            dict.keys = dict
+                .keys
+                .into_iter()
+                .map(|k| k.map(&mut replace).transpose())
+                .collect::<Result<_>>()?;
+            dict.values = dict
+                .values
+                .into_iter()
+                .map(replace)
+                .collect::<Result<_>>()?;
+            Expr::Dict(dict)
+        }
+        Expr::BinOp(mut bin_op) => {
+            bin_op.left = Box::new(replace(*bin_op.left)?);
+            bin_op.right = Box::new(replace(*bin_op.right)?);
+            Expr::BinOp(bin_op)
+        }
+        Expr::UnaryOp(mut unary_op) => {
+            unary_op.operand = Box::new(replace(*unary_op.operand)?);
+            Expr::UnaryOp(unary_op)
+        }
+        Expr::Call(mut call) => {
+            call.func = Box::new(replace(*call.func)?);
+            call.args = call
+                .args
+                .into_iter()
+                .map(&mut replace)
+                .collect::<Result<_>>()?;
+            call.keywords = call
+                .keywords
+                .into_iter()
+                .map(|mut keyword| {
+                    keyword.value = replace(keyword.value)?;
+                    Ok(keyword)
+                })
+                .collect::<Result<_>>()?;
+            Expr::Call(call)
+        }
+        _ => expr,
+    })
+}
+
+/// Escapes the characters markdown would otherwise interpret in code that is
+/// shown as text. Pipes are left to the tables, see [`crate::render::docstring::render_table`].
+pub(crate) fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Renders PEP 695 type parameters e.g. `[T: int, *Ts, **P]`
+pub(crate) fn render_type_params(type_params: Vec<TypeParam>) -> String {
+    if type_params.is_empty() {
+        return String::new();
+    }
+    let params = type_params
+        .into_iter()
+        .map(|p| match p {
+            TypeParam::TypeVar(type_var) => match type_var.bound {
+                Some(bound) => format!("{}: {}", type_var.name, render_expr(*bound)),
+                None => type_var.name.to_string(),
+            },
+            TypeParam::ParamSpec(param_spec) => format!("**{}", param_spec.name),
+            TypeParam::TypeVarTuple(type_var_tuple) => format!("*{}", type_var_tuple.name),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{params}]")
+}
+
 fn render_operator(op: Operator) -> &'static str {
     match op {
         Operator::Add => "+",
@@ -382,6 +701,56 @@ fn get_expr(s: &str) -> Result<Expr> {
         }
     }
 
+    #[test]
+    fn test_render_call_with_only_keywords() -> Result<()> {
+        let expr = get_expr("dataclass(frozen=True)")?;
+
+        let rendered = render_expr(expr);
+
+        assert_eq!(rendered, "dataclass(frozen=True)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_fstring() -> Result<()> {
+        let expr = get_expr(r#"f"({x!r}, {y:>{width}}) {{literal}}""#)?;
+
+        let rendered = render_expr(expr);
+
+        assert_eq!(rendered, r#"f"({x!r}, {y:>{width}}) {{literal}}""#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_bytes() -> Result<()> {
+        let expr = get_expr(r#"b"ab\x00c""#)?;
+
+        let rendered = render_expr(expr);
+
+        assert_eq!(rendered, r#"b"ab\x00c""#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_type_params() -> Result<()> {
+        let parsed = parse(
+            "type Alias[T: int, *Ts, **P] = int",
+            Mode::Module,
+            "<embedded>",
+        )?;
+        let Mod::Module(module) = parsed else {
+            panic!()
+        };
+        let Some(rustpython_parser::ast::Stmt::TypeAlias(alias)) = module.body.first() else {
+            panic!()
+        };
+
+        let rendered = render_type_params(alias.type_params.clone());
+
+        assert_eq!(rendered, "[T: int, *Ts, **P]");
+        Ok(())
+    }
+
     #[test]
     fn test_render_name() -> Result<()> {
         let s = "a";
@@ -1032,4 +1401,82 @@ fn test_generator_exp() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn test_render_floats() -> Result<()> {
+        for s in ["0.0", "1.5", "x * 2.0"] {
+            let expr = get_expr(s)?;
+            assert_eq!(render_expr(expr), s);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_names_skip_strings() -> Result<()> {
+        let expr = get_expr("Path(Color.RED, \"Other\", -offset)")?;
+        assert_eq!(value_names(&expr), vec!["Path", "Color.RED", "offset"]);
+        let expr = get_expr("Optional[\"Other\"]")?;
+        assert_eq!(annotation_names(&expr), vec!["Optional", "Other"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_link_names() -> Result<()> {
+        let link = |name: &str| -> Result<Option<String>> {
+            Ok(["np.ndarray", "Point", "Color.RED"]
+                .contains(&name)
+                .then(|| format!("[{name}]({name}.md)")))
+        };
+
+        for (expr, expected) in [
+            (
+                "Dict[str, np.ndarray]",
+                Some("Dict\\[str, [np.ndarray](np.ndarray.md)\\]"),
+            ),
+            (
+                "Point(1.5, \"Point\", 'Point')",
+                Some("[Point](Point.md)(1.5, \"Point\", \"Point\")"),
+            ),
+            (
+                "_make(Color.RED, max_size=2**_LIMIT)",
+                Some("\\_make([Color.RED](Color.RED.md), max\\_size=2 \\*\\* \\_LIMIT)"),
+            ),
+            (
+                "{'it\\'s Point': Point}",
+                Some("{\"it's Point\": [Point](Point.md)}"),
+            ),
+            ("Point(1).x", Some("[Point](Point.md)(1).x")),
+            ("list[int]", None),
+            ("\"Point\"", None),
+        ] {
+            assert_eq!(
+                link_names(get_expr(expr)?, link)?.as_deref(),
+                expected,
+                "{expr}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_unquote_forward_refs() -> Result<()> {
+        for (annotation, expected) in [
+            ("\"Greeter\"", "Greeter"),
+            ("list[\"Node\"] | None", "list[Node] | None"),
+            ("dict[str, \"list[Node]\"]", "dict[str, list[Node]]"),
+            ("Literal[\"a\", \"b\"]", "Literal[\"a\", \"b\"]"),
+            (
+                "typing.Annotated[\"Node\", \"some metadata\"]",
+                "typing.Annotated[Node, \"some metadata\"]",
+            ),
+            ("\"not valid python(\"", "\"not valid python(\""),
+        ] {
+            assert_eq!(
+                render_expr(unquote_forward_refs(get_expr(annotation)?)),
+                expected,
+                "{annotation}"
+            );
+        }
+        Ok(())
+    }
 }
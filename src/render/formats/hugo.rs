@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::render::formats::{Renderer, url_path};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HugoFrontMatterFormat {
+    #[default]
+    Toml,
+    Yaml,
+}
+
+#[derive(Default)]
+pub struct HugoRenderer {
+    front_matter_format: HugoFrontMatterFormat,
+}
+
+impl HugoRenderer {
+    pub fn new(front_matter_format: HugoFrontMatterFormat) -> Self {
+        Self {
+            front_matter_format,
+        }
+    }
+}
+
+impl Renderer for HugoRenderer {
+    fn render_header(&self, content: &str, level: usize) -> String {
+        format!("{} {}", &"#".repeat(level), content.trim())
+    }
+
+    fn render_front_matter(&self, title: Option<&str>) -> String {
+        let mut out = String::new();
+        match self.front_matter_format {
+            HugoFrontMatterFormat::Toml => {
+                out.push_str("+++\n");
+                if let Some(t) = title {
+                    out.push_str(&format!("title = \"{t}\"\n"));
+                };
+                out.push_str("+++");
+            }
+            HugoFrontMatterFormat::Yaml => {
+                out.push_str("---\n");
+                if let Some(t) = title {
+                    out.push_str(&format!("title: \"{t}\"\n"));
+                };
+                out.push_str("---");
+            }
+        }
+        out
+    }
+
+    fn render_reference(
+        &self,
+        display_text: Option<String>,
+        target_prefix: &Path,
+        target: String,
+    ) -> Result<String> {
+        let t = if Url::parse(&target).is_ok() {
+            target
+        } else {
+            // hugo resolves page references relative to the content dir
+            // and always expects forward slashes, regardless of platform
+            let page = url_path(&target_prefix.join(target).with_added_extension("md"));
+            format!("{{{{< relref \"/{page}\" >}}}}")
+        };
+        let rendered = match display_text {
+            Some(text) => format!("[{text}]({t})"),
+            None => format!("[{t}]({t})"),
+        };
+        Ok(rendered)
+    }
+
+    fn content_path(&self) -> Option<PathBuf> {
+        Some(PathBuf::from("content"))
+    }
+
+    fn index_file(&self, title: Option<String>) -> Option<(PathBuf, String)> {
+        let index_front_matter = self.render_front_matter(title.as_deref());
+
+        Some((PathBuf::from("_index.md"), index_front_matter))
+    }
+
+    fn page_uri(&self, target_prefix: &Path, target: &str) -> String {
+        // hugo lowercases paths unless told otherwise with `disablePathToLower`
+        format!("{}/", url_path(&target_prefix.join(target)).to_lowercase())
+    }
+
+    fn static_path(&self) -> Option<PathBuf> {
+        Some(PathBuf::from("static"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_hugo_header() -> Result<()> {
+        let renderer = HugoRenderer::default();
+        assert_eq!(renderer.render_header("foo.bar", 2), "## foo.bar");
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_hugo_toml_front_matter() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::new(HugoFrontMatterFormat::Toml).render_front_matter(None),
+            r"+++
++++"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hugo_toml_front_matter_with_title() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::new(HugoFrontMatterFormat::Toml).render_front_matter(Some("foo")),
+            r#"+++
+title = "foo"
++++"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hugo_yaml_front_matter_with_title() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::new(HugoFrontMatterFormat::Yaml).render_front_matter(Some("foo")),
+            r#"---
+title: "foo"
+---"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hugo_internal_link() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::default().render_reference(
+                Some("Baz".to_string()),
+                &PathBuf::from("api"),
+                String::from("foo.bar.baz")
+            )?,
+            r#"[Baz]({{< relref "/api/foo.bar.baz.md" >}})"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hugo_external_link() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::default().render_reference(
+                Some("Dataset".to_string()),
+                &PathBuf::from("api"),
+                "https://docs.xarray.dev/en/stable/generated/xarray.Dataset.html#xarray.Dataset"
+                    .to_string(),
+            )?,
+            r#"[Dataset](https://docs.xarray.dev/en/stable/generated/xarray.Dataset.html#xarray.Dataset)"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hugo_index_file() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::default().index_file(Some("API".to_string())),
+            Some((
+                PathBuf::from("_index.md"),
+                String::from("+++\ntitle = \"API\"\n+++")
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hugo_page_uri() -> Result<()> {
+        assert_eq!(
+            HugoRenderer::default().page_uri(&PathBuf::from("api"), "foo.Bar.baz"),
+            "api/foo.bar.baz/"
+        );
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@
 use color_eyre::Result;
 use url::Url;
 
-use crate::render::formats::Renderer;
+use crate::render::formats::{Renderer, url_path};
 
 #[derive(Default)]
 pub struct MdRenderer {}
@@ -50,6 +50,14 @@ fn content_path(&self) -> Option<PathBuf> {
     fn index_file(&self, _title: Option<String>) -> Option<(PathBuf, String)> {
         None
     }
+
+    fn page_uri(&self, target_prefix: &Path, target: &str) -> String {
+        url_path(&target_prefix.join(target).with_added_extension("md"))
+    }
+
+    fn static_path(&self) -> Option<PathBuf> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +97,12 @@ fn test_render_internal_ref() -> Result<()> {
         assert_eq!(out, String::from("[foo](foo/bar/index.md)"));
         Ok(())
     }
+    #[test]
+    fn md_page_uri() -> Result<()> {
+        assert_eq!(
+            MdRenderer::new().page_uri(&PathBuf::from("api"), "foo.Bar.baz"),
+            "api/foo.Bar.baz.md"
+        );
+        Ok(())
+    }
 }
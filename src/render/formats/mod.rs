@@ -2,10 +2,11 @@
 
 use color_eyre::Result;
 
+pub mod hugo;
 pub mod md;
 pub mod zola;
 
-pub trait Renderer {
+pub trait Renderer: Send + Sync {
     fn render_header(&self, content: &str, level: usize) -> String;
     fn render_front_matter(&self, title: Option<&str>) -> String;
     fn render_reference(
@@ -21,6 +22,23 @@ fn render_reference(
     fn content_path(&self) -> Option<PathBuf>;
 
     fn index_file(&self, title: Option<String>) -> Option<(PathBuf, String)>;
+
+    /// The url of the page of `target` relative to the root of the built site,
+    /// as the ssg will publish it
+    fn page_uri(&self, target_prefix: &Path, target: &str) -> String;
+
+    // Where files that should be served as is from the root of the site go,
+    // relative to the site root.
+    fn static_path(&self) -> Option<PathBuf>;
+}
+
+/// Joins the components of a path with forward slashes, like urls and most
+/// ssgs expect them regardless of platform
+pub(crate) fn url_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 impl<T: Renderer + ?Sized> Renderer for &T {
@@ -46,6 +64,12 @@ fn content_path(&self) -> Option<PathBuf> {
     fn index_file(&self, title: Option<String>) -> Option<(PathBuf, String)> {
         (**self).index_file(title)
     }
+    fn page_uri(&self, target_prefix: &Path, target: &str) -> String {
+        (**self).page_uri(target_prefix, target)
+    }
+    fn static_path(&self) -> Option<PathBuf> {
+        (**self).static_path()
+    }
 }
 
 impl Renderer for Box<dyn Renderer> {
@@ -69,4 +93,10 @@ fn content_path(&self) -> Option<PathBuf> {
     fn index_file(&self, title: Option<String>) -> Option<(PathBuf, String)> {
         (**self).index_file(title)
     }
+    fn page_uri(&self, target_prefix: &Path, target: &str) -> String {
+        (**self).page_uri(target_prefix, target)
+    }
+    fn static_path(&self) -> Option<PathBuf> {
+        (**self).static_path()
+    }
 }
@@ -56,6 +56,31 @@ fn index_file(&self, title: Option<String>) -> Option<(PathBuf, String)> {
 
         Some((PathBuf::from("_index.md"), index_front_matter))
     }
+
+    fn page_uri(&self, target_prefix: &Path, target: &str) -> String {
+        // zola slugifies paths by default, see `slugify.paths` in its config
+        let mut uri = target_prefix
+            .components()
+            .filter_map(|c| c.as_os_str().to_str())
+            .map(slugify)
+            .collect::<Vec<_>>();
+        uri.push(slugify(target));
+        format!("{}/", uri.join("/"))
+    }
+
+    fn static_path(&self) -> Option<PathBuf> {
+        Some(PathBuf::from("static"))
+    }
+}
+
+/// Lowercases the text and replaces everything that isn't alphanumeric with
+/// a single `-`, the way zola turns file names into urls
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
 #[cfg(test)]
@@ -126,4 +151,13 @@ fn test_zola_front_matter_with_title() -> Result<()> {
         );
         Ok(())
     }
+
+    #[test]
+    fn zola_page_uri() -> Result<()> {
+        assert_eq!(
+            ZolaRenderer {}.page_uri(&PathBuf::from("api"), "foo_bar.Baz.__init__"),
+            "api/foo-bar-baz-init/"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,200 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use color_eyre::Result;
+use tera::Context;
+
+use crate::render::{
+    formats::Renderer,
+    templates::{CLASS_HIERARCHY_TEMPLATE, Templates},
+};
+
+/// The name of the class hierarchy page. The dash makes sure it can't clash
+/// with the fully qualified name of a python object.
+pub const CLASS_HIERARCHY_PAGE: &str = "class-hierarchy";
+
+/// Renders a page with all the classes in the package as a nested list, where
+/// each class is listed under the classes of the package it inherits from.
+/// `classes` maps every class to those bases, see [`crate::indexing::index::RawIndex::class_bases`].
+/// Classes whose bases aren't listed themselves, e.g. because they are private,
+/// are shown at the top level. Returns `None` if the package doesn't contain any classes.
+pub fn render_class_hierarchy<R: Renderer>(
+    classes: &BTreeMap<String, Vec<String>>,
+    renderer: &R,
+    templates: &Templates,
+    site_rel_api_path: &Path,
+    ctx: &Context,
+) -> Result<Option<String>> {
+    if classes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut roots = vec![];
+    let mut subclasses: HashMap<String, Vec<String>> = HashMap::new();
+    for (class, bases) in classes {
+        if !bases.iter().any(|base| classes.contains_key(base)) {
+            roots.push(class.clone());
+        }
+        for base in bases {
+            subclasses
+                .entry(base.clone())
+                .or_default()
+                .push(class.clone());
+        }
+    }
+
+    let mut lines = vec![];
+    for root in &roots {
+        render_hierarchy_node(
+            root,
+            &subclasses,
+            renderer,
+            site_rel_api_path,
+            &mut vec![],
+            &mut lines,
+        )?;
+    }
+
+    let mut local_ctx = ctx.clone();
+    local_ctx.insert(
+        "SNAKEDOWN_FRONT_MATTER",
+        &renderer.render_front_matter(Some("Class Hierarchy")),
+    );
+    local_ctx.insert("SNAKEDOWN_CLASS_HIERARCHY", &lines.join("\n"));
+
+    Ok(Some(
+        templates.render(CLASS_HIERARCHY_TEMPLATE, &local_ctx)?,
+    ))
+}
+
+fn render_hierarchy_node<R: Renderer>(
+    class: &str,
+    subclasses: &HashMap<String, Vec<String>>,
+    renderer: &R,
+    site_rel_api_path: &Path,
+    ancestors: &mut Vec<String>,
+    lines: &mut Vec<String>,
+) -> Result<()> {
+    // valid python can't have cyclic inheritance, but our name resolution is best effort
+    if ancestors.iter().any(|a| a == class) {
+        return Ok(());
+    }
+    let link = renderer.render_reference(
+        Some(class.to_string()),
+        site_rel_api_path,
+        class.to_string(),
+    )?;
+    lines.push(format!("{}- {}", "  ".repeat(ancestors.len()), link));
+
+    ancestors.push(class.to_string());
+    for subclass in subclasses.get(class).into_iter().flatten() {
+        render_hierarchy_node(
+            subclass,
+            subclasses,
+            renderer,
+            site_rel_api_path,
+            ancestors,
+            lines,
+        )?;
+    }
+    ancestors.pop();
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    use assert_fs::prelude::*;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use tera::Context;
+
+    use super::render_class_hierarchy;
+    use crate::{
+        indexing::index::RawIndex,
+        render::{
+            formats::{md::MdRenderer, zola::ZolaRenderer},
+            templates::Templates,
+        },
+    };
+
+    // zola links use platform specific separators
+    #[cfg(not(windows))]
+    #[test]
+    fn render_zola_class_hierarchy() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let pkg = temp_dir.child("shapes");
+        let module = pkg.child("base.py");
+        module.write_str(
+            r#"
+class Shape: ...
+class Polygon(Shape): ...
+class Square(Polygon): ...
+class Circle(Shape): ...
+class Unrelated(object): ...
+"#,
+        )?;
+        let mut index = RawIndex::new(pkg.to_path_buf(), false, false)?;
+        index.index_file(module.to_path_buf())?;
+        index.resolve_class_bases();
+
+        let rendered = render_class_hierarchy(
+            &index.class_bases(),
+            &ZolaRenderer {},
+            &Templates::builtin()?,
+            &PathBuf::from("api"),
+            &Context::new(),
+        )?;
+
+        assert_eq!(
+            rendered,
+            Some(
+                r#"+++
+title = "Class Hierarchy"
++++
+
+- [shapes.base.Shape](@/api/shapes.base.Shape.md)
+  - [shapes.base.Circle](@/api/shapes.base.Circle.md)
+  - [shapes.base.Polygon](@/api/shapes.base.Polygon.md)
+    - [shapes.base.Square](@/api/shapes.base.Square.md)
+- [shapes.base.Unrelated](@/api/shapes.base.Unrelated.md)
+"#
+                .to_string()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn classes_with_filtered_bases_are_roots() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        temp_dir
+            .child("class_hierarchy.md")
+            .write_str("# Classes\n\n{{ SNAKEDOWN_CLASS_HIERARCHY }}\n")?;
+        // the private base isn't documented, so it isn't listed itself
+        let classes = BTreeMap::from([
+            ("pkg.Public".to_string(), vec!["pkg._Private".to_string()]),
+            ("pkg.Child".to_string(), vec!["pkg.Public".to_string()]),
+        ]);
+
+        let rendered = render_class_hierarchy(
+            &classes,
+            &MdRenderer::new(),
+            &Templates::new(Some(temp_dir.path()))?,
+            &PathBuf::from("api"),
+            &Context::new(),
+        )?;
+
+        assert_eq!(
+            rendered,
+            Some(
+                "# Classes\n\n- [pkg.Public](pkg.Public.md)\n  - [pkg.Child](pkg.Child.md)"
+                    .to_string()
+            )
+        );
+        Ok(())
+    }
+}
@@ -6,8 +6,12 @@
 use jupyter_protocol::Media;
 use jupyter_protocol::MediaType;
 use nbformat::v4::{Cell, Output};
+use tera::Context;
 
-use crate::render::formats::Renderer;
+use crate::render::{
+    formats::Renderer,
+    templates::{NOTEBOOK_TEMPLATE, Templates},
+};
 
 pub struct RenderedNotebook {
     pub text: String,
@@ -111,9 +115,10 @@ pub fn render_notebook<R: Renderer>(
     name: Option<&str>,
     notebook: &[Cell],
     renderer: &R,
+    templates: &Templates,
+    ctx: &Context,
 ) -> Result<RenderedNotebook> {
-    let front_matter = renderer.render_front_matter(name);
-    let mut rendered_cells = vec![front_matter];
+    let mut rendered_cells = vec![];
     let mut rendered_notebook = RenderedNotebook {
         text: String::new(),
         images: Vec::new(),
@@ -179,7 +184,14 @@ pub fn render_notebook<R: Renderer>(
         }
     }
 
-    rendered_notebook.text = rendered_cells.join("\n\n");
+    let mut local_ctx = ctx.clone();
+    local_ctx.insert(
+        "SNAKEDOWN_FRONT_MATTER",
+        &renderer.render_front_matter(name),
+    );
+    local_ctx.insert("SNAKEDOWN_NAME", &name);
+    local_ctx.insert("SNAKEDOWN_NOTEBOOK_CELLS", &rendered_cells);
+    rendered_notebook.text = templates.render(NOTEBOOK_TEMPLATE, &local_ctx)?;
 
     Ok(rendered_notebook)
 }
@@ -1,26 +1,51 @@
 pub mod args;
+pub mod docstring;
 pub mod expr;
 pub mod formats;
+pub mod hierarchy;
 pub mod jupyter;
+pub mod signature;
+pub mod templates;
 use color_eyre::Result;
 
 use clap::ValueEnum;
+use rustpython_parser::ast::{ArgWithDefault, Arguments, Constant, Expr, ExprConstant};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{BTreeMap, HashMap},
     ffi::OsStr,
     path::{Path, PathBuf},
 };
 use strum::Display;
-use tera::{Context, Tera};
+use tera::Context;
 
-use args::render_args;
-use expr::render_expr;
+use args::{parameters, render_args};
+use docstring::{render_docstring, render_table};
+use expr::{escape_markdown, link_names, render_expr, unquote_forward_refs};
+use signature::{
+    attribute_badges, function_badges, function_signature, render_attribute_signature,
+    render_badges, render_class_signature, render_function_signature, render_type_alias_signature,
+    render_variable_signature,
+};
+use templates::{
+    ATTRIBUTE_TEMPLATE, CLASS_TEMPLATE, FUNCTION_TEMPLATE, MODULE_TEMPLATE, TYPE_ALIAS_TEMPLATE,
+    Templates, VARIABLE_TEMPLATE,
+};
 
 use crate::{
     parsing::{
         ObjectDocumentation,
+        docstring::{
+            Docstring, DocstringParameter, DocstringReturn, DocstringStyle, parse_docstring,
+        },
         python::{
-            class::ClassDocumentation, function::FunctionDocumentation, module::ModuleDocumentation,
+            attribute::AttributeDocumentation,
+            class::{ClassDocumentation, InheritedMembers},
+            function::FunctionDocumentation,
+            module::ModuleDocumentation,
+            object::{Member, MemberKind},
+            type_alias::TypeAliasDocumentation,
+            variable::VariableDocumentation,
         },
     },
     render::formats::Renderer,
@@ -31,6 +56,7 @@
 pub enum SSG {
     Markdown,
     Zola,
+    Hugo,
 }
 
 pub fn translate_filename(path: &Path) -> PathBuf {
@@ -58,13 +84,36 @@ pub fn fully_qualified_object_name(object: &ObjectDocumentation, prefix: Option<
         (ObjectDocumentation::Function(function_documentation), Some(p)) => {
             format!("{}.{}", p, function_documentation.name.to_string().trim())
         }
+        (ObjectDocumentation::Attribute(attribute_documentation), None) => {
+            attribute_documentation.name.trim().to_string()
+        }
+        (ObjectDocumentation::Attribute(attribute_documentation), Some(p)) => {
+            format!("{}.{}", p, attribute_documentation.name.trim())
+        }
+        (ObjectDocumentation::Variable(variable_documentation), None) => {
+            variable_documentation.name.trim().to_string()
+        }
+        (ObjectDocumentation::Variable(variable_documentation), Some(p)) => {
+            format!("{}.{}", p, variable_documentation.name.trim())
+        }
+        (ObjectDocumentation::TypeAlias(type_alias_documentation), None) => {
+            type_alias_documentation.name.trim().to_string()
+        }
+        (ObjectDocumentation::TypeAlias(type_alias_documentation), Some(p)) => {
+            format!("{}.{}", p, type_alias_documentation.name.trim())
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_object<R: Renderer>(
     object: &ObjectDocumentation,
     fully_qualified_name: String,
     renderer: &R,
+    templates: &Templates,
+    site_rel_api_path: &Path,
+    docstring_style: DocstringStyle,
+    signature_width: usize,
     ctx: &Context,
 ) -> Result<String> {
     match object {
@@ -72,20 +121,73 @@ pub fn render_object<R: Renderer>(
             class_documentation,
             &fully_qualified_name,
             renderer,
+            templates,
+            site_rel_api_path,
+            docstring_style,
             ctx,
         )?),
         ObjectDocumentation::Module(module_documentation) => Ok(render_module(
             module_documentation,
             fully_qualified_name,
             renderer,
+            templates,
+            site_rel_api_path,
+            docstring_style,
             ctx,
         )?),
         ObjectDocumentation::Function(function_documentation) => Ok(render_function_docs(
             function_documentation,
             &fully_qualified_name,
             renderer,
+            templates,
+            site_rel_api_path,
+            docstring_style,
+            signature_width,
+            ctx,
+        )?),
+        ObjectDocumentation::Attribute(attribute_documentation) => Ok(render_attribute_docs(
+            attribute_documentation,
+            &fully_qualified_name,
+            renderer,
+            templates,
+            docstring_style,
             ctx,
         )?),
+        ObjectDocumentation::Variable(variable_documentation) => Ok(render_variable_docs(
+            variable_documentation,
+            &fully_qualified_name,
+            renderer,
+            templates,
+            docstring_style,
+            ctx,
+        )?),
+        ObjectDocumentation::TypeAlias(type_alias_documentation) => Ok(render_type_alias_docs(
+            type_alias_documentation,
+            &fully_qualified_name,
+            renderer,
+            templates,
+            docstring_style,
+            ctx,
+        )?),
+    }
+}
+
+/// The variables every page gets, whatever kind of object it documents
+fn insert_object_context(
+    ctx: &mut Context,
+    fully_qualified_name: &str,
+    docstring: Docstring,
+    line: Option<usize>,
+) {
+    let name = fully_qualified_name
+        .rsplit('.')
+        .next()
+        .unwrap_or(fully_qualified_name);
+    ctx.insert("SNAKEDOWN_NAME", name);
+    ctx.insert("SNAKEDOWN_FULLY_QUALIFIED_NAME", fully_qualified_name);
+    ctx.insert("SNAKEDOWN_DOCSTRING", &docstring);
+    if let Some(line) = line {
+        ctx.insert("SNAKEDOWN_SOURCE_LINE", &line);
     }
 }
 
@@ -93,6 +195,9 @@ pub fn render_module<R: Renderer>(
     mod_doc: &ModuleDocumentation,
     fully_qualified_name: String,
     renderer: &R,
+    templates: &Templates,
+    site_rel_api_path: &Path,
+    docstring_style: DocstringStyle,
     ctx: &Context,
 ) -> Result<String> {
     let mut local_ctx = ctx.clone();
@@ -100,46 +205,610 @@ pub fn render_module<R: Renderer>(
     let front_matter = &renderer.render_front_matter(Some(&fully_qualified_name));
     local_ctx.insert("SNAKEDOWN_FRONT_MATTER", &front_matter);
 
-    if let Some(docstring) = mod_doc.docstring.clone() {
-        local_ctx.insert("SNAKEDOWN_MODULE_DOCSTRING", docstring.trim());
+    let parsed = mod_doc
+        .docstring
+        .as_ref()
+        .map(|d| parse_docstring(d.trim(), docstring_style));
+    if let Some(parsed) = &parsed {
+        local_ctx.insert(
+            "SNAKEDOWN_MODULE_DOCSTRING",
+            &render_docstring(parsed, renderer),
+        );
+    }
+    insert_object_context(
+        &mut local_ctx,
+        &fully_qualified_name,
+        parsed.unwrap_or_default(),
+        Some(1),
+    );
+    local_ctx.insert("SNAKEDOWN_MEMBERS", &mod_doc.members);
+    if let Some(members) = render_members(
+        &mod_doc.members,
+        &[
+            (MemberKind::Module, "Modules"),
+            (MemberKind::Class, "Classes"),
+            (MemberKind::Function, "Functions"),
+            (MemberKind::Constant, "Constants"),
+            (MemberKind::Variable, "Variables"),
+            (MemberKind::TypeAlias, "Type aliases"),
+        ],
+        renderer,
+        site_rel_api_path,
+    )? {
+        local_ctx.insert("SNAKEDOWN_MODULE_MEMBERS", &members);
     }
 
-    let function_template = r#"{{ SNAKEDOWN_FRONT_MATTER }}
-{%if SNAKEDOWN_MODULE_DOCSTRING%}
-{{SNAKEDOWN_MODULE_DOCSTRING}}
-{%endif%}"#;
-
-    Ok(Tera::one_off(function_template, &local_ctx, false)?)
+    templates.render(MODULE_TEMPLATE, &local_ctx)
 }
 
 fn render_class_docs<R: Renderer>(
     class_docs: &ClassDocumentation,
     fully_qualified_name: &str,
     renderer: &R,
+    templates: &Templates,
+    site_rel_api_path: &Path,
+    docstring_style: DocstringStyle,
     ctx: &Context,
 ) -> Result<String> {
     let mut local_ctx = ctx.clone();
 
     let front_matter = &renderer.render_front_matter(Some(fully_qualified_name));
     local_ctx.insert("SNAKEDOWN_FRONT_MATTER", &front_matter);
+    local_ctx.insert(
+        "SNAKEDOWN_CLASS_SIGNATURE",
+        &render_class_signature(class_docs),
+    );
+    if let Some(bases) = render_class_bases(class_docs, renderer, site_rel_api_path)? {
+        local_ctx.insert("SNAKEDOWN_CLASS_BASES", &bases);
+    }
+
+    let mut parsed = class_docs
+        .docstring
+        .as_ref()
+        .map(|d| parse_docstring(d.trim(), docstring_style))
+        .unwrap_or_default();
+
+    insert_object_context(
+        &mut local_ctx,
+        fully_qualified_name,
+        parsed.clone(),
+        class_docs.line,
+    );
+    local_ctx.insert("SNAKEDOWN_MEMBERS", &class_docs.members);
+    // attributes get a table of their own below
+    if let Some(members) = render_members(
+        &class_docs.members,
+        &[
+            (MemberKind::Class, "Classes"),
+            (MemberKind::Method, "Methods"),
+        ],
+        renderer,
+        site_rel_api_path,
+    )? {
+        local_ctx.insert("SNAKEDOWN_CLASS_MEMBERS", &members);
+    }
+
+    // attributes documented in the class docstring are rendered together with
+    // the ones we found in the code, so take them out of the docstring
+    let docstring_attributes = std::mem::take(&mut parsed.attributes);
+
+    if parsed != Docstring::default() {
+        local_ctx.insert(
+            "SNAKEDOWN_CLASS_DOCSTRING",
+            &render_docstring(&parsed, renderer),
+        );
+    }
+
+    if let Some(attributes) = render_class_attributes(
+        class_docs,
+        &docstring_attributes,
+        docstring_style,
+        renderer,
+        site_rel_api_path,
+    )? {
+        local_ctx.insert(
+            "SNAKEDOWN_CLASS_ATTRIBUTES",
+            &format!(
+                "{}\n\n{}",
+                renderer.render_header("Attributes", 2),
+                attributes
+            ),
+        );
+    }
+
+    if let Some(inherited) =
+        render_inherited_members(&class_docs.inherited_members, renderer, site_rel_api_path)?
+    {
+        local_ctx.insert(
+            "SNAKEDOWN_CLASS_INHERITED_MEMBERS",
+            &format!(
+                "{}\n\n{}",
+                renderer.render_header("Inherited members", 2),
+                inherited
+            ),
+        );
+    }
+
+    templates.render(CLASS_TEMPLATE, &local_ctx)
+}
+
+/// Renders a table with a link to and the summary of every member, for each
+/// of the `sections` that has any members
+fn render_members<R: Renderer>(
+    members: &[Member],
+    sections: &[(MemberKind, &str)],
+    renderer: &R,
+    site_rel_api_path: &Path,
+) -> Result<Option<String>> {
+    let mut rendered = vec![];
+    for (kind, title) in sections {
+        let mut rows = vec![];
+        for member in members.iter().filter(|m| m.kind == *kind) {
+            rows.push(vec![
+                renderer.render_reference(
+                    Some(format!("`{}`", member.name)),
+                    site_rel_api_path,
+                    member.fully_qualified_name.clone(),
+                )?,
+                member.summary.clone().unwrap_or_default(),
+            ]);
+        }
+        if !rows.is_empty() {
+            rendered.push(format!(
+                "{}\n\n{}",
+                renderer.render_header(title, 2),
+                render_table(&["Name", "Description"], rows)
+            ));
+        }
+    }
+    if rendered.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(rendered.join("\n\n")))
+}
 
-    if let Some(docstring) = class_docs.docstring.clone() {
-        local_ctx.insert("SNAKEDOWN_CLASS_DOCSTRING", docstring.trim());
+/// Links the bases we could resolve, the others are shown as code
+fn render_class_bases<R: Renderer>(
+    class_docs: &ClassDocumentation,
+    renderer: &R,
+    site_rel_api_path: &Path,
+) -> Result<Option<String>> {
+    if class_docs.bases.is_empty() {
+        return Ok(None);
     }
+    let mut bases = vec![];
+    for base in &class_docs.bases {
+        let display = format!("`{}`", render_expr(base.expr.clone()));
+        bases.push(match &base.target {
+            Some(target) => {
+                renderer.render_reference(Some(display), site_rel_api_path, target.clone())?
+            }
+            None => display,
+        });
+    }
+    Ok(Some(format!("Bases: {}", bases.join(", "))))
+}
 
-    let function_template = r#"
-        {{ SNAKEDOWN_FRONT_MATTER }}
-{%if SNAKEDOWN_CLASS_DOCSTRING%}
-{{SNAKEDOWN_CLASS_DOCSTRING}}
-{%endif%}"#;
+fn render_inherited_members<R: Renderer>(
+    inherited_members: &[InheritedMembers],
+    renderer: &R,
+    site_rel_api_path: &Path,
+) -> Result<Option<String>> {
+    if inherited_members.is_empty() {
+        return Ok(None);
+    }
+    let mut lines = vec![];
+    for inherited in inherited_members {
+        let base = renderer.render_reference(
+            Some(inherited.base.clone()),
+            site_rel_api_path,
+            inherited.base.clone(),
+        )?;
+        let mut methods = vec![];
+        for method in &inherited.methods {
+            methods.push(renderer.render_reference(
+                Some(format!("`{method}`")),
+                site_rel_api_path,
+                format!("{}.{}", inherited.base, method),
+            )?);
+        }
+        lines.push(format!("- From {}: {}", base, methods.join(", ")));
+    }
+    Ok(Some(lines.join("\n")))
+}
 
-    Ok(Tera::one_off(function_template, &local_ctx, false)?)
+/// Renders an annotation or a default with the names that could be resolved,
+/// see [`crate::indexing::index::RawIndex::resolve_annotations`], linked.
+/// Forward references in annotations lose their quotes. If nothing could be
+/// linked, annotations are rendered as escaped text and defaults as code.
+fn render_linked_expr<R: Renderer>(
+    expr: Expr,
+    is_annotation: bool,
+    targets: &HashMap<String, String>,
+    renderer: &R,
+    site_rel_api_path: &Path,
+) -> Result<String> {
+    let expr = if is_annotation {
+        unquote_forward_refs(expr)
+    } else {
+        expr
+    };
+    let linked = link_names(expr.clone(), |name| {
+        targets
+            .get(name)
+            .map(|target| {
+                renderer.render_reference(Some(name.to_string()), site_rel_api_path, target.clone())
+            })
+            .transpose()
+    })?;
+    Ok(match linked {
+        Some(linked) => linked,
+        None if is_annotation => escape_markdown(&render_expr(expr)),
+        None => format!("`{}`", render_expr(expr)),
+    })
 }
 
+/// name, type, default and description of an attribute
+type AttributeRow = (String, Option<String>, Option<String>, Option<String>);
+
+/// Renders a table of the attributes of a class. Information from the code
+/// takes precedence, but we fall back on what is documented in the
+/// `Attributes` section of the class docstring.
+fn render_class_attributes<R: Renderer>(
+    class_docs: &ClassDocumentation,
+    docstring_attributes: &[DocstringParameter],
+    docstring_style: DocstringStyle,
+    renderer: &R,
+    site_rel_api_path: &Path,
+) -> Result<Option<String>> {
+    let targets = &class_docs.annotation_targets;
+    let mut rows: Vec<AttributeRow> = vec![];
+    for attr in &class_docs.attributes {
+        let documented = docstring_attributes.iter().find(|d| d.name == attr.name);
+        let type_annotation = match attr.type_annotation.clone() {
+            Some(annotation) => Some(render_linked_expr(
+                annotation,
+                true,
+                targets,
+                renderer,
+                site_rel_api_path,
+            )?),
+            None => documented.and_then(|d| d.type_annotation.clone()),
+        };
+        let default = attr
+            .default
+            .clone()
+            .map(|default| render_linked_expr(default, false, targets, renderer, site_rel_api_path))
+            .transpose()?;
+        rows.push((
+            attr.name.clone(),
+            type_annotation,
+            default,
+            attr.docstring
+                .as_ref()
+                .and_then(|d| parse_docstring(d.trim(), docstring_style).summary)
+                .or_else(|| documented.and_then(|d| d.description.clone())),
+        ));
+    }
+
+    rows.extend(
+        docstring_attributes
+            .iter()
+            .filter(|d| !class_docs.attributes.iter().any(|a| a.name == d.name))
+            .map(|d| {
+                (
+                    d.name.clone(),
+                    d.type_annotation.clone(),
+                    None,
+                    d.description.clone(),
+                )
+            }),
+    );
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let any_default = rows.iter().any(|r| r.2.is_some());
+    let headers: &[&str] = if any_default {
+        &["Name", "Type", "Default", "Description"]
+    } else {
+        &["Name", "Type", "Description"]
+    };
+
+    Ok(Some(render_table(
+        headers,
+        rows.into_iter()
+            .map(|(name, ty, default, description)| {
+                let mut row = vec![format!("`{name}`"), ty.unwrap_or_default()];
+                if any_default {
+                    row.push(default.unwrap_or_default());
+                }
+                row.push(description.unwrap_or_default());
+                row
+            })
+            .collect(),
+    )))
+}
+
+/// A parameter in a signature: its name the way it would be documented,
+/// i.e. with the stars of `*args` and `**kwargs`, its annotation and default
+type SignatureParameter<'a> = (String, Option<&'a Expr>, Option<&'a Expr>);
+
+fn signature_parameters(args: &Arguments) -> Vec<SignatureParameter<'_>> {
+    fn with_default(arg: &ArgWithDefault) -> SignatureParameter<'_> {
+        (
+            arg.def.arg.to_string(),
+            arg.def.annotation.as_deref(),
+            arg.default.as_deref(),
+        )
+    }
+    let mut params: Vec<SignatureParameter> = args
+        .posonlyargs
+        .iter()
+        .chain(&args.args)
+        .map(with_default)
+        .collect();
+    if let Some(vararg) = &args.vararg {
+        params.push((
+            format!("*{}", vararg.arg),
+            vararg.annotation.as_deref(),
+            None,
+        ));
+    }
+    params.extend(args.kwonlyargs.iter().map(with_default));
+    if let Some(kwarg) = &args.kwarg {
+        params.push((
+            format!("**{}", kwarg.arg),
+            kwarg.annotation.as_deref(),
+            None,
+        ));
+    }
+    params
+}
+
+/// The types in a return annotation like `tuple[int, str]`, one for each value
+fn tuple_elements(annotation: &Expr) -> Option<&[Expr]> {
+    let Expr::Subscript(subscript) = annotation else {
+        return None;
+    };
+    let generic = match subscript.value.as_ref() {
+        Expr::Name(name) => name.id.as_str(),
+        Expr::Attribute(attr) => attr.attr.as_str(),
+        _ => return None,
+    };
+    match subscript.slice.as_ref() {
+        Expr::Tuple(tuple)
+            if matches!(generic, "tuple" | "Tuple")
+                && !tuple.elts.iter().any(|e| {
+                    matches!(
+                        e,
+                        Expr::Constant(ExprConstant {
+                            value: Constant::Ellipsis,
+                            ..
+                        })
+                    )
+                }) =>
+        {
+            Some(&tuple.elts)
+        }
+        _ => None,
+    }
+}
+
+/// Builds the parameters and return values of a function from its signature,
+/// so the names in their annotations and defaults can be linked. Descriptions
+/// come from the docstring, as do the types of anything that isn't annotated.
+fn insert_annotated_types<R: Renderer>(
+    docstring: &mut Docstring,
+    fn_docs: &FunctionDocumentation,
+    renderer: &R,
+    site_rel_api_path: &Path,
+) -> Result<()> {
+    let targets = &fn_docs.annotation_targets;
+
+    let mut documented = std::mem::take(&mut docstring.parameters);
+    for (name, annotation, default) in signature_parameters(&fn_docs.args) {
+        // docstrings may document `*args` and `**kwargs` with or without their stars
+        let position = documented
+            .iter()
+            .position(|d| d.name.trim_start_matches('*') == name.trim_start_matches('*'));
+        let mut param = match (position, annotation, default) {
+            (Some(i), _, _) => documented.remove(i),
+            // there is nothing to say about e.g. `self`
+            (None, None, None) => continue,
+            (None, _, _) => DocstringParameter {
+                name,
+                type_annotation: None,
+                description: None,
+                default: None,
+            },
+        };
+        if let Some(annotation) = annotation {
+            param.type_annotation = Some(render_linked_expr(
+                annotation.clone(),
+                true,
+                targets,
+                renderer,
+                site_rel_api_path,
+            )?);
+        }
+        param.default = default
+            .cloned()
+            .map(|default| render_linked_expr(default, false, targets, renderer, site_rel_api_path))
+            .transpose()?;
+        docstring.parameters.push(param);
+    }
+    // whatever is documented but isn't in the signature
+    docstring.parameters.extend(documented);
+
+    let Some(return_type) = fn_docs.return_type.clone().map(unquote_forward_refs) else {
+        return Ok(());
+    };
+    let link = |annotation: &Expr| {
+        render_linked_expr(
+            annotation.clone(),
+            true,
+            targets,
+            renderer,
+            site_rel_api_path,
+        )
+    };
+    match docstring.returns.as_mut_slice() {
+        // generators document what they yield, and `None` isn't worth a table
+        [] if !docstring.yields.is_empty()
+            || matches!(
+                return_type,
+                Expr::Constant(ExprConstant {
+                    value: Constant::None,
+                    ..
+                })
+            ) => {}
+        [] => docstring.returns.push(DocstringReturn {
+            name: None,
+            type_annotation: Some(link(&return_type)?),
+            description: None,
+        }),
+        [returns] => returns.type_annotation = Some(link(&return_type)?),
+        // several values get the types of a tuple annotation one by one, if
+        // we can't tell which part of the annotation belongs to which we
+        // keep the documented types
+        several => {
+            if let Some(types) = tuple_elements(&return_type)
+                && types.len() == several.len()
+            {
+                for (returns, annotation) in several.iter_mut().zip(types) {
+                    returns.type_annotation = Some(link(annotation)?);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn render_attribute_docs<R: Renderer>(
+    attr_docs: &AttributeDocumentation,
+    fully_qualified_name: &str,
+    renderer: &R,
+    templates: &Templates,
+    docstring_style: DocstringStyle,
+    ctx: &Context,
+) -> Result<String> {
+    let mut local_ctx = ctx.clone();
+
+    let front_matter = &renderer.render_front_matter(Some(fully_qualified_name));
+    local_ctx.insert("SNAKEDOWN_FRONT_MATTER", &front_matter);
+    local_ctx.insert(
+        "SNAKEDOWN_ATTRIBUTE_SIGNATURE",
+        &render_attribute_signature(attr_docs),
+    );
+    if let Some(badges) = render_badges(&attribute_badges(attr_docs)) {
+        local_ctx.insert("SNAKEDOWN_ATTRIBUTE_BADGES", &badges);
+    }
+
+    let parsed = attr_docs
+        .docstring
+        .as_ref()
+        .map(|d| parse_docstring(d.trim(), docstring_style));
+    if let Some(parsed) = &parsed {
+        local_ctx.insert(
+            "SNAKEDOWN_ATTRIBUTE_DOCSTRING",
+            &render_docstring(parsed, renderer),
+        );
+    }
+    insert_object_context(
+        &mut local_ctx,
+        fully_qualified_name,
+        parsed.unwrap_or_default(),
+        None,
+    );
+
+    templates.render(ATTRIBUTE_TEMPLATE, &local_ctx)
+}
+
+fn render_variable_docs<R: Renderer>(
+    var_docs: &VariableDocumentation,
+    fully_qualified_name: &str,
+    renderer: &R,
+    templates: &Templates,
+    docstring_style: DocstringStyle,
+    ctx: &Context,
+) -> Result<String> {
+    let mut local_ctx = ctx.clone();
+
+    let front_matter = &renderer.render_front_matter(Some(fully_qualified_name));
+    local_ctx.insert("SNAKEDOWN_FRONT_MATTER", &front_matter);
+    local_ctx.insert(
+        "SNAKEDOWN_VARIABLE_SIGNATURE",
+        &render_variable_signature(var_docs),
+    );
+
+    let parsed = var_docs
+        .docstring
+        .as_ref()
+        .map(|d| parse_docstring(d.trim(), docstring_style));
+    if let Some(parsed) = &parsed {
+        local_ctx.insert(
+            "SNAKEDOWN_VARIABLE_DOCSTRING",
+            &render_docstring(parsed, renderer),
+        );
+    }
+    insert_object_context(
+        &mut local_ctx,
+        fully_qualified_name,
+        parsed.unwrap_or_default(),
+        None,
+    );
+
+    templates.render(VARIABLE_TEMPLATE, &local_ctx)
+}
+
+fn render_type_alias_docs<R: Renderer>(
+    alias_docs: &TypeAliasDocumentation,
+    fully_qualified_name: &str,
+    renderer: &R,
+    templates: &Templates,
+    docstring_style: DocstringStyle,
+    ctx: &Context,
+) -> Result<String> {
+    let mut local_ctx = ctx.clone();
+
+    let front_matter = &renderer.render_front_matter(Some(fully_qualified_name));
+    local_ctx.insert("SNAKEDOWN_FRONT_MATTER", &front_matter);
+    local_ctx.insert(
+        "SNAKEDOWN_TYPE_ALIAS_SIGNATURE",
+        &render_type_alias_signature(alias_docs),
+    );
+
+    let parsed = alias_docs
+        .docstring
+        .as_ref()
+        .map(|d| parse_docstring(d.trim(), docstring_style));
+    if let Some(parsed) = &parsed {
+        local_ctx.insert(
+            "SNAKEDOWN_TYPE_ALIAS_DOCSTRING",
+            &render_docstring(parsed, renderer),
+        );
+    }
+    insert_object_context(
+        &mut local_ctx,
+        fully_qualified_name,
+        parsed.unwrap_or_default(),
+        None,
+    );
+
+    templates.render(TYPE_ALIAS_TEMPLATE, &local_ctx)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_function_docs<R: Renderer>(
     fn_docs: &FunctionDocumentation,
     fully_qualified_name: &str,
     renderer: &R,
+    templates: &Templates,
+    site_rel_api_path: &Path,
+    docstring_style: DocstringStyle,
+    signature_width: usize,
     ctx: &Context,
 ) -> Result<String> {
     let mut local_ctx = ctx.clone();
@@ -151,39 +820,71 @@ fn render_function_docs<R: Renderer>(
         "SNAKEDOWN_FUNCTION_ARGS",
         &render_args(fn_docs.args.clone()),
     );
+    local_ctx.insert("SNAKEDOWN_FUNCTION_PARAMETERS", &parameters(&fn_docs.args));
     if let Some(ret) = fn_docs.return_type.clone() {
         local_ctx.insert("SNAKEDOWN_FUNCTION_RET", &render_expr(ret));
     }
-
-    if let Some(docstring) = fn_docs.docstring.clone() {
-        local_ctx.insert("SNAKEDOWN_FUNCTION_DOCSTRING", docstring.trim());
+    local_ctx.insert(
+        "SNAKEDOWN_FUNCTION_SIGNATURE",
+        &render_function_signature(fn_docs, signature_width),
+    );
+    local_ctx.insert("SNAKEDOWN_SIGNATURE", &function_signature(fn_docs));
+    // links can't go in the code block of the signature, but templates that
+    // build it themselves can use them for the `annotation_names`
+    let annotation_links = fn_docs
+        .annotation_targets
+        .iter()
+        .map(|(name, target)| {
+            renderer
+                .render_reference(Some(name.clone()), site_rel_api_path, target.clone())
+                .map(|link| (name.clone(), link))
+        })
+        .collect::<Result<BTreeMap<String, String>>>()?;
+    local_ctx.insert("SNAKEDOWN_ANNOTATION_LINKS", &annotation_links);
+    if let Some(badges) = render_badges(&function_badges(fn_docs)) {
+        local_ctx.insert("SNAKEDOWN_FUNCTION_BADGES", &badges);
     }
 
-    let function_template = r#"
-        {{ SNAKEDOWN_FRONT_MATTER }}
-
-{{ SNAKEDOWN_FUNCTION_NAME }}({{ SNAKEDOWN_FUNCTION_ARGS }}){% if SNAKEDOWN_FUNCTION_RET %} -> {{ SNAKEDOWN_FUNCTION_RET }}{%endif%}
-{%if SNAKEDOWN_FUNCTION_DOCSTRING%}
-{{SNAKEDOWN_FUNCTION_DOCSTRING}}
-{%endif%}"#;
+    let mut parsed = fn_docs
+        .docstring
+        .as_ref()
+        .map(|d| parse_docstring(d.trim(), docstring_style));
+    if let Some(parsed) = &mut parsed {
+        insert_annotated_types(parsed, fn_docs, renderer, site_rel_api_path)?;
+        local_ctx.insert(
+            "SNAKEDOWN_FUNCTION_DOCSTRING",
+            &render_docstring(parsed, renderer),
+        );
+    }
+    insert_object_context(
+        &mut local_ctx,
+        fully_qualified_name,
+        parsed.unwrap_or_default(),
+        fn_docs.line,
+    );
 
-    Ok(Tera::one_off(function_template, &local_ctx, false)?)
+    templates.render(FUNCTION_TEMPLATE, &local_ctx)
 }
 
 #[cfg(test)]
 mod test {
 
-    use std::path::PathBuf;
+    use std::{collections::HashMap, path::PathBuf};
 
     use color_eyre::Result;
     use pretty_assertions::assert_eq;
     use tera::Context;
 
     use crate::{
-        parsing::{python::module::extract_module_documentation, python::utils::parse_python_str},
+        parsing::{
+            docstring::DocstringStyle, python::module::extract_module_documentation,
+            python::utils::parse_python_str,
+        },
         render::{
             formats::{md::MdRenderer, zola::ZolaRenderer},
-            render_module, translate_filename,
+            render_class_docs, render_function_docs, render_module,
+            templates::Templates,
+            translate_filename,
         },
     };
     fn test_dirty_module_str() -> &'static str {
@@ -255,6 +956,9 @@ fn render_module_documentation() -> Result<()> {
             &mod_documentation,
             String::from("snakedown.testing.test_module"),
             &MdRenderer::new(),
+            &Templates::builtin()?,
+            &PathBuf::from(""),
+            DocstringStyle::Auto,
             &ctx,
         )?;
 
@@ -282,6 +986,9 @@ fn render_module_documentation_zola() -> Result<()> {
             &mod_documentation,
             String::from("snakedown"),
             &ZolaRenderer {},
+            &Templates::builtin()?,
+            &PathBuf::from(""),
+            DocstringStyle::Auto,
             &ctx,
         )?;
 
@@ -289,6 +996,47 @@ fn render_module_documentation_zola() -> Result<()> {
 
         Ok(())
     }
+    fn expected_class_docs_rendered() -> &'static str {
+        r#"# snakedown.testing.test_module.Greeter
+
+```python
+class Greeter
+```
+
+this is a class docstring.
+
+## Attributes
+
+| Name | Type | Default | Description |
+| ---- | ---- | ------- | ----------- |
+| `class_var` |  | `"whatever"` |  |
+"#
+    }
+
+    #[test]
+    fn render_class_documentation_with_attributes() -> Result<()> {
+        let parsed = parse_python_str(test_dirty_module_str())?;
+        let mod_documentation = extract_module_documentation(&parsed, false, false);
+        let ctx = Context::new();
+
+        #[allow(clippy::unwrap_used)]
+        let class_documentation = mod_documentation.classes.first().unwrap();
+
+        let rendered = render_class_docs(
+            class_documentation,
+            "snakedown.testing.test_module.Greeter",
+            &MdRenderer::new(),
+            &Templates::builtin()?,
+            &PathBuf::from(""),
+            DocstringStyle::Auto,
+            &ctx,
+        )?;
+
+        assert_eq!(rendered.trim_start(), expected_class_docs_rendered());
+
+        Ok(())
+    }
+
     #[test]
     fn test_translate_filename_init() -> Result<()> {
         let input = PathBuf::from("foo/bar/__init__.py");
@@ -303,4 +1051,72 @@ fn test_translate_filename_module() -> Result<()> {
         assert_eq!(translate_filename(&input), expected);
         Ok(())
     }
+
+    #[test]
+    fn render_function_documentation_from_signature() -> Result<()> {
+        let parsed = parse_python_str(
+            r#"
+def split(
+    node: "Node",
+    sizes: list["Node"],
+    *rest: int,
+    fallback=Color.RED,
+    verbose=False,
+) -> tuple["Node", int]:
+    """Split a node.
+
+    Parameters
+    ----------
+    node
+        The node to split.
+    verbose
+        Print what happens.
+
+    Returns
+    -------
+    first : Node
+        The first part.
+    left : int
+        How many parts are left.
+    """
+"#,
+        )?;
+        let mod_documentation = extract_module_documentation(&parsed, false, false);
+        #[allow(clippy::unwrap_used)]
+        let mut fn_docs = mod_documentation.functions.first().unwrap().clone();
+        fn_docs.annotation_targets = HashMap::from([
+            ("Node".to_string(), "geo.Node".to_string()),
+            ("Color.RED".to_string(), "geo.Color.RED".to_string()),
+        ]);
+
+        let rendered = render_function_docs(
+            &fn_docs,
+            "geo.split",
+            &MdRenderer::new(),
+            &Templates::builtin()?,
+            &PathBuf::from(""),
+            DocstringStyle::Numpy,
+            88,
+            &Context::new(),
+        )?;
+
+        let expected = r#"## Parameters
+
+| Name | Type | Default | Description |
+| ---- | ---- | ------- | ----------- |
+| `node` | [Node](geo.Node.md) |  | The node to split. |
+| `sizes` | list\[[Node](geo.Node.md)\] |  |  |
+| `*rest` | int |  |  |
+| `fallback` |  | [Color.RED](geo.Color.RED.md) |  |
+| `verbose` |  | `False` | Print what happens. |
+
+## Returns
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| `first` | [Node](geo.Node.md) | The first part. |
+| `left` | int | How many parts are left. |"#;
+        assert!(rendered.contains(expected), "{rendered}");
+        Ok(())
+    }
 }
@@ -0,0 +1,339 @@
+use crate::parsing::python::{
+    attribute::AttributeDocumentation, class::ClassDocumentation, decorator::Decorator,
+    function::FunctionDocumentation, type_alias::TypeAliasDocumentation,
+    variable::VariableDocumentation,
+};
+
+use serde::Serialize;
+
+use super::{
+    args::{Parameter, parameters, render_parameters},
+    expr::{annotation_names, render_expr, render_type_params},
+};
+
+/// Decorators without special meaning are shown above the signature,
+/// the others are rendered as badges instead.
+fn render_decorators(decorators: &[Decorator]) -> Vec<String> {
+    decorators
+        .iter()
+        .filter_map(|d| match d {
+            Decorator::Other(expr) => Some(format!("@{}", render_expr(expr.clone()))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How far the parameters of a wrapped signature are indented
+const INDENT: &str = "    ";
+
+/// Renders a `def` the way black would format it: on one line if it fits in
+/// `width`, otherwise with all parameters on one indented line, and if that
+/// doesn't fit either, with every parameter on its own line.
+fn render_def(fn_docs: &FunctionDocumentation, width: usize) -> String {
+    let mut head = String::new();
+    if fn_docs.is_async {
+        head.push_str("async ");
+    }
+    head.push_str("def ");
+    head.push_str(&fn_docs.name);
+    head.push_str(&render_type_params(fn_docs.generics.clone()));
+    head.push('(');
+
+    let mut tail = String::from(")");
+    if let Some(ret) = fn_docs.return_type.clone() {
+        tail.push_str(" -> ");
+        tail.push_str(&render_expr(ret));
+    }
+
+    let params = render_parameters(fn_docs.args.clone());
+    let joined = params.join(", ");
+    let one_line = format!("{head}{joined}{tail}");
+    if params.is_empty() || one_line.chars().count() <= width {
+        return one_line;
+    }
+
+    let indented = format!("{INDENT}{joined}");
+    if indented.chars().count() <= width {
+        return format!("{head}\n{indented}\n{tail}");
+    }
+
+    let mut lines = vec![head];
+    lines.extend(params.iter().map(|p| format!("{INDENT}{p},")));
+    lines.push(tail);
+    lines.join("\n")
+}
+
+pub(crate) fn render_function_signature(fn_docs: &FunctionDocumentation, width: usize) -> String {
+    let mut lines = vec![];
+    for overload in &fn_docs.overloads {
+        lines.push(String::from("@overload"));
+        lines.extend(render_decorators(&overload.decorators));
+        lines.push(render_def(overload, width));
+    }
+    lines.extend(render_decorators(&fn_docs.decorators));
+    lines.push(render_def(fn_docs, width));
+    lines.join("\n")
+}
+
+/// The pieces of a function signature, for templates that want to
+/// put them together themselves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Signature {
+    pub name: String,
+    pub is_async: bool,
+    pub decorators: Vec<String>,
+    pub type_params: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub return_annotation: Option<String>,
+    /// The dotted names used in the return annotation, see [`Parameter::annotation_names`]
+    pub return_annotation_names: Vec<String>,
+}
+
+pub(crate) fn function_signature(fn_docs: &FunctionDocumentation) -> Signature {
+    let type_params = render_type_params(fn_docs.generics.clone());
+    Signature {
+        name: fn_docs.name.clone(),
+        is_async: fn_docs.is_async,
+        decorators: render_decorators(&fn_docs.decorators),
+        type_params: (!type_params.is_empty()).then_some(type_params),
+        parameters: parameters(&fn_docs.args),
+        return_annotation: fn_docs.return_type.clone().map(render_expr),
+        return_annotation_names: fn_docs
+            .return_type
+            .as_ref()
+            .map(annotation_names)
+            .unwrap_or_default(),
+    }
+}
+
+pub(crate) fn render_class_signature(class_docs: &ClassDocumentation) -> String {
+    let mut lines = render_decorators(&class_docs.decorators);
+    let arguments: Vec<String> = class_docs
+        .bases
+        .iter()
+        .map(|b| render_expr(b.expr.clone()))
+        .chain(class_docs.keywords.iter().map(|k| match &k.arg {
+            Some(arg) => format!("{}={}", arg, render_expr(k.value.clone())),
+            None => format!("**{}", render_expr(k.value.clone())),
+        }))
+        .collect();
+    if arguments.is_empty() {
+        lines.push(format!("class {}", class_docs.name));
+    } else {
+        lines.push(format!(
+            "class {}({})",
+            class_docs.name,
+            arguments.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+pub(crate) fn render_attribute_signature(attr_docs: &AttributeDocumentation) -> String {
+    render_assignment(
+        &attr_docs.name,
+        &attr_docs.type_annotation,
+        &attr_docs.default,
+    )
+}
+
+pub(crate) fn render_variable_signature(var_docs: &VariableDocumentation) -> String {
+    render_assignment(&var_docs.name, &var_docs.type_annotation, &var_docs.value)
+}
+
+pub(crate) fn render_type_alias_signature(alias_docs: &TypeAliasDocumentation) -> String {
+    format!(
+        "type {}{} = {}",
+        alias_docs.name,
+        render_type_params(alias_docs.generics.clone()),
+        render_expr(alias_docs.value.clone())
+    )
+}
+
+fn render_assignment(
+    name: &str,
+    type_annotation: &Option<rustpython_parser::ast::Expr>,
+    value: &Option<rustpython_parser::ast::Expr>,
+) -> String {
+    let mut out = name.to_string();
+    if let Some(annotation) = type_annotation.clone() {
+        out.push_str(": ");
+        out.push_str(&render_expr(annotation));
+    }
+    if let Some(value) = value.clone() {
+        out.push_str(" = ");
+        out.push_str(&render_expr(value));
+    }
+    out
+}
+
+/// Labels for the decorators that have a special meaning
+pub(crate) fn function_badges(fn_docs: &FunctionDocumentation) -> Vec<&'static str> {
+    let mut badges = vec![];
+    for (decorator, label) in [
+        (Decorator::StaticMethod, "staticmethod"),
+        (Decorator::ClassMethod, "classmethod"),
+        (Decorator::AbstractMethod, "abstractmethod"),
+    ] {
+        if fn_docs.has_decorator(&decorator) {
+            badges.push(label);
+        }
+    }
+    if fn_docs.is_async {
+        badges.push("async");
+    }
+    badges
+}
+
+pub(crate) fn attribute_badges(attr_docs: &AttributeDocumentation) -> Vec<&'static str> {
+    let mut badges = vec![];
+    if let Some(accessors) = attr_docs.property {
+        badges.push("property");
+        if accessors.cached {
+            badges.push("cached");
+        }
+        if accessors.setter {
+            badges.push("writable");
+        }
+        if accessors.deleter {
+            badges.push("deletable");
+        }
+    }
+    badges
+}
+
+pub(crate) fn render_badges(badges: &[&str]) -> Option<String> {
+    if badges.is_empty() {
+        None
+    } else {
+        Some(
+            badges
+                .iter()
+                .map(|b| format!("`{b}`"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parsing::python::{module::extract_module_documentation, utils::parse_python_str};
+
+    fn test_python_decorated() -> &'static str {
+        r#"
+@dataclass(frozen=True)
+class Point(Base, Generic[T], metaclass=Meta):
+    @classmethod
+    @functools.cache
+    def origin(cls) -> "Point":
+        ...
+
+@overload
+def double(x: int) -> int: ...
+@overload
+def double(x: str) -> str: ...
+async def double(x):
+    '''Double the input.'''
+    return x * 2
+"#
+    }
+
+    #[test]
+    fn render_decorated_signatures() -> Result<()> {
+        let program = parse_python_str(test_python_decorated())?;
+        let documentation = extract_module_documentation(&program, false, false);
+
+        #[allow(clippy::unwrap_used)]
+        let class = documentation.classes.first().unwrap();
+        assert_eq!(
+            render_class_signature(class),
+            "@dataclass(frozen=True)\nclass Point(Base, Generic[T], metaclass=Meta)"
+        );
+
+        #[allow(clippy::unwrap_used)]
+        let method = class.methods.first().unwrap();
+        assert_eq!(
+            render_function_signature(method, 88),
+            "@functools.cache\ndef origin(cls) -> \"Point\""
+        );
+        assert_eq!(
+            render_badges(&function_badges(method)),
+            Some("`classmethod`".to_string())
+        );
+
+        #[allow(clippy::unwrap_used)]
+        let function = documentation.functions.first().unwrap();
+        assert_eq!(
+            render_function_signature(function, 88),
+            "@overload\ndef double(x: int) -> int\n@overload\ndef double(x: str) -> str\nasync def double(x)"
+        );
+        Ok(())
+    }
+
+    fn render_def_of(source: &str, width: usize) -> Result<String> {
+        let program = parse_python_str(source)?;
+        let documentation = extract_module_documentation(&program, false, false);
+        #[allow(clippy::unwrap_used)]
+        let function = documentation.functions.first().unwrap();
+        Ok(render_function_signature(function, width))
+    }
+
+    #[test]
+    fn render_positional_and_keyword_only_markers() -> Result<()> {
+        assert_eq!(
+            render_def_of("def f(a, b=1, /, c=2, *, d, e=3): ...", 88)?,
+            "def f(a, b=1, /, c=2, *, d, e=3)"
+        );
+        assert_eq!(
+            render_def_of("def f(a, /, *args, b, **kwargs): ...", 88)?,
+            "def f(a, /, *args, b, **kwargs)"
+        );
+        assert_eq!(
+            render_def_of("def f(*args, **kwargs): ...", 88)?,
+            "def f(*args, **kwargs)"
+        );
+        assert_eq!(render_def_of("def f(): ...", 88)?, "def f()");
+        Ok(())
+    }
+
+    #[test]
+    fn long_signatures_are_wrapped_like_black() -> Result<()> {
+        let source = "def function(first: int, second: str = 'x', *, third: bool = False) -> dict[str, int]: ...";
+        assert_eq!(
+            render_def_of(source, 88)?,
+            "def function(first: int, second: str = \"x\", *, third: bool = False) -> dict[str, int]"
+        );
+        assert_eq!(
+            render_def_of(source, 60)?,
+            "def function(\n    first: int, second: str = \"x\", *, third: bool = False\n) -> dict[str, int]"
+        );
+        assert_eq!(
+            render_def_of(source, 40)?,
+            "def function(\n    first: int,\n    second: str = \"x\",\n    *,\n    third: bool = False,\n) -> dict[str, int]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn structured_signature() -> Result<()> {
+        let program = parse_python_str(
+            "def f(x: 'Point', /, *ys: np.ndarray) -> Dict[str, Optional[T]]: ...",
+        )?;
+        let documentation = extract_module_documentation(&program, false, false);
+        #[allow(clippy::unwrap_used)]
+        let signature = function_signature(documentation.functions.first().unwrap());
+
+        assert_eq!(signature.parameters[0].annotation_names, vec!["Point"]);
+        assert_eq!(signature.parameters[1].annotation_names, vec!["np.ndarray"]);
+        assert_eq!(
+            signature.return_annotation_names,
+            vec!["Dict", "str", "Optional", "T"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use std::{collections::HashMap, error::Error, fs::read_to_string, path::Path};
+
+use color_eyre::{
+    Report, Result,
+    eyre::{WrapErr, eyre},
+};
+use lazy_regex::regex_captures;
+use tera::{Context, Tera};
+
+pub const MODULE_TEMPLATE: &str = "module.md";
+pub const CLASS_TEMPLATE: &str = "class.md";
+pub const FUNCTION_TEMPLATE: &str = "function.md";
+pub const ATTRIBUTE_TEMPLATE: &str = "attribute.md";
+pub const VARIABLE_TEMPLATE: &str = "variable.md";
+pub const TYPE_ALIAS_TEMPLATE: &str = "type_alias.md";
+pub const NOTEBOOK_TEMPLATE: &str = "notebook.md";
+pub const CLASS_HIERARCHY_TEMPLATE: &str = "class_hierarchy.md";
+
+const BUILTIN_TEMPLATES: [(&str, &str); 8] = [
+    (MODULE_TEMPLATE, include_str!("templates/module.md")),
+    (CLASS_TEMPLATE, include_str!("templates/class.md")),
+    (FUNCTION_TEMPLATE, include_str!("templates/function.md")),
+    (ATTRIBUTE_TEMPLATE, include_str!("templates/attribute.md")),
+    (VARIABLE_TEMPLATE, include_str!("templates/variable.md")),
+    (TYPE_ALIAS_TEMPLATE, include_str!("templates/type_alias.md")),
+    (NOTEBOOK_TEMPLATE, include_str!("templates/notebook.md")),
+    (
+        CLASS_HIERARCHY_TEMPLATE,
+        include_str!("templates/class_hierarchy.md"),
+    ),
+];
+
+/// The templates pages are rendered with. Templates in the templates directory
+/// take precedence over the ones that ship with snakedown.
+pub struct Templates {
+    tera: Tera,
+    /// Where each template came from and what it contained, so we can point
+    /// users to the right place when something goes wrong
+    sources: HashMap<&'static str, (String, String)>,
+}
+
+impl Templates {
+    pub fn builtin() -> Result<Self> {
+        Self::new(None)
+    }
+
+    pub fn new(templates_dir: Option<&Path>) -> Result<Self> {
+        if let Some(dir) = templates_dir
+            && !dir.is_dir()
+        {
+            return Err(eyre!(
+                "templates directory {} does not exist",
+                dir.display()
+            ));
+        }
+
+        let mut tera = Tera::default();
+        let mut sources = HashMap::new();
+        for (name, builtin) in BUILTIN_TEMPLATES {
+            let user_template = templates_dir
+                .map(|dir| dir.join(name))
+                .filter(|path| path.is_file());
+            let (origin, source) = match user_template {
+                Some(path) => {
+                    tracing::debug!("using template {}", path.display());
+                    let source = read_to_string(&path)
+                        .wrap_err_with(|| format!("could not read {}", path.display()))?;
+                    (path.display().to_string(), source)
+                }
+                None => (format!("<built-in {name}>"), builtin.to_string()),
+            };
+            // editors like to end files with a newline, which would otherwise end up in every page
+            let source = source
+                .strip_suffix('\n')
+                .map(|s| s.strip_suffix('\r').unwrap_or(s))
+                .unwrap_or(&source)
+                .to_string();
+
+            tera.add_raw_template(name, &source)
+                .map_err(|e| template_error(&origin, &source, &e))?;
+            sources.insert(name, (origin, source));
+        }
+
+        if let Some(dir) = templates_dir {
+            for entry in dir.read_dir()? {
+                let path = entry?.path();
+                let known = path
+                    .file_name()
+                    .and_then(|f| f.to_str())
+                    .is_some_and(|f| sources.contains_key(f));
+                if !known {
+                    tracing::warn!(
+                        "ignoring {} as it doesn't override any of the templates",
+                        path.display()
+                    );
+                }
+            }
+        }
+
+        Ok(Self { tera, sources })
+    }
+
+    pub fn render(&self, name: &str, ctx: &Context) -> Result<String> {
+        self.tera
+            .render(name, ctx)
+            .map_err(|e| match self.sources.get(name) {
+                Some((origin, source)) => template_error(origin, source, &e),
+                None => eyre!("{}", error_chain(&e).join(": ")),
+            })
+    }
+}
+
+fn error_chain(error: &tera::Error) -> Vec<String> {
+    let mut messages = vec![error.to_string()];
+    let mut cause = error.source();
+    while let Some(c) = cause {
+        messages.push(c.to_string());
+        cause = c.source();
+    }
+    messages
+}
+
+/// Tera knows which template went wrong, but we'd like to tell the user where
+fn template_error(origin: &str, source: &str, error: &tera::Error) -> Report {
+    let messages = error_chain(error);
+    let message = messages.join(": ");
+    if let Some((line, column)) = locate_template_error(source, &messages) {
+        eyre!("{origin}:{line}:{column}: {message}")
+    } else {
+        eyre!("{origin}: {message}")
+    }
+}
+
+fn locate_template_error(source: &str, messages: &[String]) -> Option<(usize, usize)> {
+    // parse errors tell us where they are e.g. ` --> 3:14`
+    for message in messages {
+        if let Some((_, line, column)) = regex_captures!(r"--> (\d+):(\d+)", message) {
+            return Some((line.parse().ok()?, column.parse().ok()?));
+        }
+    }
+    // render errors only name the variable or filter that went wrong,
+    // so the best we can do is point at the first place it is used
+    for message in messages {
+        if let Some((_, name)) = regex_captures!(r"`([^`]+)`", message) {
+            return source
+                .lines()
+                .enumerate()
+                .find_map(|(i, line)| line.find(name).map(|column| (i + 1, column + 1)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::prelude::*;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+    use tera::Context;
+
+    use super::{FUNCTION_TEMPLATE, MODULE_TEMPLATE, Templates};
+
+    #[test]
+    fn builtin_templates_parse() -> Result<()> {
+        let templates = Templates::builtin()?;
+        let mut ctx = Context::new();
+        ctx.insert("SNAKEDOWN_FRONT_MATTER", "# foo");
+        assert_eq!(templates.render(MODULE_TEMPLATE, &ctx)?, "# foo\n");
+        Ok(())
+    }
+
+    #[test]
+    fn user_templates_override_builtin_ones() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        temp_dir
+            .child("module.md")
+            .write_str("# {{ SNAKEDOWN_NAME }}\n")?;
+        let templates = Templates::new(Some(temp_dir.path()))?;
+
+        let mut ctx = Context::new();
+        ctx.insert("SNAKEDOWN_NAME", "foo");
+        ctx.insert("SNAKEDOWN_FRONT_MATTER", "");
+        ctx.insert("SNAKEDOWN_FUNCTION_SIGNATURE", "def foo()");
+        // the trailing newline of the file is not part of the template
+        assert_eq!(templates.render(MODULE_TEMPLATE, &ctx)?, "# foo");
+        // the other templates are left alone
+        assert!(
+            templates
+                .render(FUNCTION_TEMPLATE, &ctx)?
+                .contains("def foo()")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn template_syntax_errors_point_to_file_and_line() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let template = temp_dir.child("class.md");
+        template.write_str("# title\n\n{% if SNAKEDOWN_NAME %}\nno endif\n")?;
+
+        let error = Templates::new(Some(temp_dir.path()))
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(
+            error.starts_with(&format!("{}:4:9:", template.path().display())),
+            "{error}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn template_render_errors_point_to_file_and_line() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let template = temp_dir.child("function.md");
+        template.write_str("# title\n\nthis is {{ SNAKEDOWN_UNKNOWN }}\n")?;
+        let templates = Templates::new(Some(temp_dir.path()))?;
+
+        let error = templates
+            .render(FUNCTION_TEMPLATE, &Context::new())
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(
+            error.starts_with(&format!("{}:3:12:", template.path().display())),
+            "{error}"
+        );
+        assert!(error.contains("SNAKEDOWN_UNKNOWN"), "{error}");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_templates_dir_is_an_error() {
+        assert!(Templates::new(Some(std::path::Path::new("does/not/exist"))).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::Result;
+use notify_debouncer_mini::{
+    DebounceEventResult, Debouncer, new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode},
+};
+use rayon::ThreadPool;
+use tokio::sync::mpsc::unbounded_channel;
+
+use crate::{
+    config::{Config, ConfigBuilder},
+    fetch_externals,
+    fs::should_index_path,
+    indexing::index::RawIndex,
+    load_index,
+    manifest::{BuildReport, hash_config},
+    rebuild_index, render_pages, thread_pool,
+};
+
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Everything we need to re-render the docs after a change without
+/// re-parsing the files that didn't change.
+pub struct WatchState {
+    config: Config,
+    pool: ThreadPool,
+    /// The index as it was crawled, before any references were resolved
+    index: RawIndex,
+    /// Recorded in the build manifest, see [`crate::manifest::hash_config`]
+    config_hash: String,
+}
+
+/// What has to happen after a batch of files changed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub reload_config: bool,
+    pub templates: bool,
+    pub modules: Vec<PathBuf>,
+    pub notebooks: Vec<PathBuf>,
+}
+
+impl WatchState {
+    pub async fn load(config_builder: ConfigBuilder) -> Result<Self> {
+        let config_hash = hash_config(&config_builder)?;
+        let config = config_builder.build()?;
+        fetch_externals(&config).await?;
+        let pool = thread_pool(&config)?;
+        let index = pool.install(|| load_index(&config))?;
+        Ok(Self {
+            config,
+            pool,
+            index,
+            config_hash,
+        })
+    }
+
+    pub fn pkg_path(&self) -> &Path {
+        &self.index.pkg_root
+    }
+
+    pub fn notebook_path(&self) -> Option<PathBuf> {
+        self.config
+            .notebook_path
+            .as_ref()
+            .and_then(|p| p.canonicalize().ok())
+    }
+
+    pub fn templates_dir(&self) -> Option<PathBuf> {
+        self.config
+            .templates_dir
+            .as_ref()
+            .and_then(|p| p.canonicalize().ok())
+    }
+
+    /// Sorts the changed paths into things we have to do
+    pub fn classify(&self, paths: &[PathBuf], config_files: &[PathBuf]) -> Changes {
+        let notebook_path = self.notebook_path();
+        let templates_dir = self.templates_dir();
+        let mut changes = Changes::default();
+        for path in paths {
+            if config_files.iter().any(|c| c == path) {
+                changes.reload_config = true;
+            } else if templates_dir.as_ref().is_some_and(|t| path.starts_with(t)) {
+                changes.templates = true;
+            } else if path.starts_with(self.pkg_path()) {
+                changes.modules.push(path.clone());
+            } else if notebook_path.as_ref().is_some_and(|n| path.starts_with(n)) {
+                changes.notebooks.push(path.clone());
+            }
+        }
+        changes
+    }
+
+    /// Re-indexes the modules and notebooks that changed. Paths that no longer
+    /// exist are removed from the index, together with everything under them.
+    pub fn update(&mut self, changes: &Changes) {
+        for path in &changes.modules {
+            let removed: Vec<PathBuf> = self
+                .index
+                .module_paths
+                .values()
+                .filter(|p| p.starts_with(path))
+                .cloned()
+                .collect();
+            for module_path in removed {
+                self.index.remove_file(&module_path);
+            }
+
+            if path.is_file()
+                && should_index_path(
+                    self.pkg_path(),
+                    path,
+                    self.config.skip_private && !self.config.public_api,
+                    &self.config.exclude,
+                )
+                && let Err(e) = self.index.index_file(path.clone())
+            {
+                tracing::error!("could not index {}: {e:#}", path.display());
+            }
+        }
+
+        for path in &changes.notebooks {
+            if path.extension().and_then(|e| e.to_str()) != Some("ipynb") {
+                continue;
+            }
+            self.index.remove_notebook(path);
+            if path.is_file()
+                && let Err(e) = self.index.index_notebook(path)
+            {
+                tracing::error!("could not index {}: {e:#}", path.display());
+            }
+        }
+    }
+
+    /// Renders the docs, writes the files that changed and removes the ones that
+    /// are no longer rendered. The build manifest is kept up to date as well, so
+    /// a later `snakedown build` knows what is on disk.
+    pub fn render(&mut self) -> Result<BuildReport> {
+        if self.config.skip_write {
+            self.pool
+                .install(|| render_pages(&self.config, self.index.clone()))?;
+            return Ok(BuildReport::default());
+        }
+
+        let report = self.pool.install(|| {
+            rebuild_index(&self.config, self.config_hash.clone(), self.index.clone())
+        })?;
+        tracing::info!(
+            "updated {} file(s), removed {} file(s)",
+            report.written.len(),
+            report.removed.len()
+        );
+        Ok(report)
+    }
+
+    fn watched_paths(&self, config_files: &[PathBuf]) -> HashSet<(PathBuf, RecursiveMode)> {
+        let mut paths = HashSet::new();
+        paths.insert((self.pkg_path().to_path_buf(), RecursiveMode::Recursive));
+        if let Some(notebook_path) = self.notebook_path() {
+            paths.insert((notebook_path, RecursiveMode::Recursive));
+        }
+        if let Some(templates_dir) = self.templates_dir() {
+            paths.insert((templates_dir, RecursiveMode::NonRecursive));
+        }
+        // editors often replace files instead of writing to them, which
+        // a watch on the file itself doesn't survive, so we watch the directory
+        for config_file in config_files {
+            if let Some(parent) = config_file.parent() {
+                paths.insert((parent.to_path_buf(), RecursiveMode::NonRecursive));
+            }
+        }
+        paths
+    }
+}
+
+fn update_watches(
+    debouncer: &mut Debouncer<RecommendedWatcher>,
+    old: &HashSet<(PathBuf, RecursiveMode)>,
+    new: &HashSet<(PathBuf, RecursiveMode)>,
+) -> Result<()> {
+    for (path, _) in old.difference(new) {
+        debouncer.watcher().unwatch(path)?;
+    }
+    for (path, mode) in new.difference(old) {
+        tracing::info!("watching {}", path.display());
+        debouncer.watcher().watch(path, *mode)?;
+    }
+    Ok(())
+}
+
+/// Renders the docs and keeps re-rendering them whenever something changes,
+/// until the process is stopped. `resolve_config` is called again whenever
+/// one of the `config_files` changes. Errors are reported, but don't stop
+/// the watcher so they can be fixed while it is running.
+pub async fn watch_docs<F>(resolve_config: F, config_files: Vec<PathBuf>) -> Result<()>
+where
+    F: Fn() -> Result<ConfigBuilder>,
+{
+    let config_files: Vec<PathBuf> = config_files
+        .iter()
+        .filter_map(|p| p.canonicalize().ok())
+        .collect();
+
+    let mut state = WatchState::load(resolve_config()?).await?;
+    if let Err(e) = state.render() {
+        tracing::error!("{e:#}");
+    }
+
+    let (tx, mut rx) = unbounded_channel();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, move |res: DebounceEventResult| {
+        let _ = tx.send(res);
+    })?;
+    let mut watched = state.watched_paths(&config_files);
+    update_watches(&mut debouncer, &HashSet::new(), &watched)?;
+
+    while let Some(res) = rx.recv().await {
+        let events = match res {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::error!("error while watching files: {e}");
+                continue;
+            }
+        };
+        let paths: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+        let changes = state.classify(&paths, &config_files);
+
+        if changes.reload_config {
+            tracing::info!("configuration changed, rebuilding everything");
+            let reloaded = match resolve_config() {
+                Ok(config_builder) => WatchState::load(config_builder).await,
+                Err(e) => Err(e),
+            };
+            match reloaded {
+                Ok(new_state) => {
+                    state = new_state;
+                    let new_watched = state.watched_paths(&config_files);
+                    update_watches(&mut debouncer, &watched, &new_watched)?;
+                    watched = new_watched;
+                }
+                Err(e) => {
+                    tracing::error!("could not reload configuration: {e:#}");
+                    continue;
+                }
+            }
+        } else if changes.modules.is_empty() && changes.notebooks.is_empty() {
+            if !changes.templates {
+                continue;
+            }
+            // templates are read on every render, so there is nothing to re-index
+            tracing::info!("templates changed, re-rendering");
+        } else {
+            tracing::info!(
+                "{} file(s) changed, re-rendering",
+                changes.modules.len() + changes.notebooks.len()
+            );
+            state.update(&changes);
+        }
+
+        if let Err(e) = state.render() {
+            tracing::error!("{e:#}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{read_to_string, write};
+
+    use assert_fs::prelude::*;
+    use color_eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{Changes, WatchState};
+    use crate::{config::ConfigBuilder, render::SSG, render_docs};
+
+    fn config_builder(temp_dir: &assert_fs::TempDir) -> ConfigBuilder {
+        ConfigBuilder::default()
+            .with_pkg_path(Some(temp_dir.child("pkg").to_path_buf()))
+            .with_site_root(Some(temp_dir.child("site").to_path_buf()))
+            .with_manifest_dir(Some(temp_dir.child("manifests").to_path_buf()))
+            .with_skip_undoc(Some(false))
+            .with_offline(Some(true))
+            .with_externals(Some(Default::default()))
+            .with_ssg(Some(SSG::Markdown))
+    }
+
+    async fn watch_state(temp_dir: &assert_fs::TempDir) -> Result<WatchState> {
+        let pkg = temp_dir.child("pkg");
+        pkg.child("__init__.py").write_str("'''The package'''")?;
+        pkg.child("a.py")
+            .write_str("def f():\n    '''See [[pkg.b.g]]'''\n")?;
+        pkg.child("b.py").write_str("def g():\n    '''g'''\n")?;
+
+        let mut state = WatchState::load(config_builder(temp_dir)).await?;
+        state.render()?;
+        Ok(state)
+    }
+
+    #[tokio::test]
+    async fn classify_changes() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let state = watch_state(&temp_dir).await?;
+        let config_file = temp_dir.child("snakedown.toml").to_path_buf();
+        let module = state.pkg_path().join("a.py");
+
+        let changes = state.classify(
+            &[
+                module.clone(),
+                config_file.clone(),
+                temp_dir.child("unrelated.txt").to_path_buf(),
+            ],
+            &[config_file],
+        );
+        assert_eq!(
+            changes,
+            Changes {
+                reload_config: true,
+                templates: false,
+                modules: vec![module],
+                notebooks: vec![],
+            }
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_changed_modules_are_rewritten() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let mut state = watch_state(&temp_dir).await?;
+        let api = temp_dir.child("site").child("api");
+        let g_page = api.child("pkg.b.g.md");
+        assert!(g_page.exists());
+
+        let b = state.pkg_path().join("b.py");
+        write(&b, "def g():\n    '''g has changed'''\n")?;
+        state.update(&Changes {
+            modules: vec![b],
+            ..Default::default()
+        });
+        let report = state.render()?;
+
+        assert!(read_to_string(g_page.path())?.contains("g has changed"));
+        assert!(report.written.contains(&g_page.to_path_buf()));
+        assert!(
+            !report
+                .written
+                .contains(&api.child("pkg.a.f.md").to_path_buf())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deleted_modules_are_removed() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let mut state = watch_state(&temp_dir).await?;
+        let api = temp_dir.child("site").child("api");
+
+        let a = state.pkg_path().join("a.py");
+        std::fs::remove_file(&a)?;
+        state.update(&Changes {
+            modules: vec![a],
+            ..Default::default()
+        });
+        state.render()?;
+
+        assert!(!api.child("pkg.a.md").exists());
+        assert!(!api.child("pkg.a.f.md").exists());
+        assert!(api.child("pkg.b.g.md").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn builds_after_watching_agree_with_the_manifest() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let mut state = watch_state(&temp_dir).await?;
+        let api = temp_dir.child("site").child("api");
+
+        let a = state.pkg_path().join("a.py");
+        let b = state.pkg_path().join("b.py");
+        std::fs::remove_file(&a)?;
+        write(&b, "def g():\n    '''g has changed'''\n")?;
+        state.update(&Changes {
+            modules: vec![a, b],
+            ..Default::default()
+        });
+        state.render()?;
+
+        let report = render_docs(config_builder(&temp_dir)).await?;
+        assert!(report.written.is_empty());
+        assert!(report.removed.is_empty());
+        assert!(report.parsed.is_empty());
+
+        // outputs watch created are cleaned up by later builds
+        std::fs::remove_file(state.pkg_path().join("b.py"))?;
+        let report = render_docs(config_builder(&temp_dir)).await?;
+        assert!(
+            report
+                .removed
+                .contains(&api.child("pkg.b.g.md").to_path_buf())
+        );
+        assert!(!api.child("pkg.b.g.md").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn broken_references_keep_previous_output() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let mut state = watch_state(&temp_dir).await?;
+        let api = temp_dir.child("site").child("api");
+
+        let b = state.pkg_path().join("b.py");
+        write(&b, "def renamed():\n    '''g'''\n")?;
+        state.update(&Changes {
+            modules: vec![b],
+            ..Default::default()
+        });
+
+        assert!(state.render().is_err());
+        assert!(api.child("pkg.b.g.md").exists());
+        Ok(())
+    }
+}
@@ -37,6 +37,35 @@ fn test_skip_write_does_not_write() -> Result<()> {
     Ok(())
 }
 #[test]
+fn test_reference_policy_sets_exit_status() -> Result<()> {
+    let tempdir = tempdir()?;
+
+    let run_with_policy = |policy: Option<&str>| {
+        let mut cmd = cargo_bin_cmd!();
+        cmd.arg("-p")
+            .arg("tests/test_pkg")
+            .arg("-s")
+            .arg(tempdir.path())
+            .arg("-e")
+            .arg("test_pkg/excluded_file.py")
+            .arg("--exclude")
+            .arg("test_pkg/excluded_module")
+            .arg("--skip-write");
+        if let Some(policy) = policy {
+            cmd.arg("--reference-policy").arg(policy);
+        }
+        cmd.assert()
+    };
+
+    // test_pkg/miss_spelled_ref.py is not excluded this time
+    run_with_policy(None).failure();
+    run_with_policy(Some("error")).failure();
+    run_with_policy(Some("warn")).success();
+    run_with_policy(Some("ignore")).success();
+
+    Ok(())
+}
+#[test]
 fn test_cli_with_all_options() -> Result<()> {
     let tempdir = tempdir()?;
 
@@ -53,6 +82,8 @@ fn test_cli_with_all_options() -> Result<()> {
         .arg("test_pkg/excluded_file.py")
         .arg("--exclude")
         .arg("test_pkg/excluded_module")
+        .arg("--manifest-dir")
+        .arg(tempdir.path().join("manifests"))
         .arg("-vv");
     let assertion = cmd.assert();
 
@@ -86,6 +117,8 @@ fn test_cli_with_zola() -> Result<()> {
         .arg("test_pkg/excluded_module")
         .arg("--ssg")
         .arg("zola")
+        .arg("--manifest-dir")
+        .arg(tempdir.path().join("manifests"))
         .arg("-vv");
     let snakedown_assertion = cmd.assert();
 
@@ -101,6 +134,42 @@ fn test_cli_with_zola() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_with_hugo() -> Result<()> {
+    let tempdir = tempdir()?;
+    let site_root = tempdir.path();
+
+    let mut cmd = cargo_bin_cmd!();
+    cmd.arg("-p")
+        .arg("tests/test_pkg")
+        .arg("-s")
+        .arg(site_root)
+        .arg("--skip-undoc")
+        .arg("--skip-private")
+        .arg("-e")
+        .arg("test_pkg/miss_spelled_ref.py")
+        .arg("-e")
+        .arg("test_pkg/excluded_file.py")
+        .arg("--exclude")
+        .arg("test_pkg/excluded_module")
+        .arg("--ssg")
+        .arg("hugo")
+        .arg("--manifest-dir")
+        .arg(tempdir.path().join("manifests"))
+        .arg("-vv");
+    cmd.assert().success();
+
+    let api_path = site_root.join("content").join("api");
+    let index = fs::read_to_string(api_path.join("_index.md"))?;
+    assert_eq!(index, "+++\ntitle = \"API\"\n+++");
+
+    let bar = fs::read_to_string(api_path.join("test_pkg.bar.md"))?;
+    assert!(bar.starts_with("+++\ntitle = \"test_pkg.bar\"\n+++"));
+    assert!(bar.contains(r#"[test_pkg.bar.greet]({{< relref "/api/test_pkg.bar.greet.md" >}})"#));
+
+    Ok(())
+}
+
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     fs::create_dir_all(dst)?;
 
@@ -117,3 +186,78 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_cache_subcommand() -> Result<()> {
+    let cache_dir = tempdir()?;
+    let sphinx_dir = cache_dir.path().join("sphinx");
+    fs::create_dir_all(&sphinx_dir)?;
+    fs::copy(
+        "tests/sphinx_objects/numpy.inv",
+        sphinx_dir.join("numpy.inv"),
+    )?;
+
+    let cache = |args: &[&str]| {
+        let mut cmd = cargo_bin_cmd!();
+        cmd.env("SNAKEDOWN_CACHE_DIR", cache_dir.path())
+            .arg("cache")
+            .args(args);
+        cmd.assert().success()
+    };
+
+    let path = cache(&["path"]).get_output().stdout.clone();
+    assert_eq!(
+        String::from_utf8(path)?.trim(),
+        cache_dir.path().display().to_string()
+    );
+
+    let listed = String::from_utf8(cache(&["list"]).get_output().stdout.clone())?;
+    assert!(listed.starts_with("numpy: "), "{listed}");
+
+    let cleared = String::from_utf8(cache(&["clear"]).get_output().stdout.clone())?;
+    assert_eq!(cleared.trim(), "removed numpy");
+    assert!(!sphinx_dir.join("numpy.inv").exists());
+
+    let listed = String::from_utf8(cache(&["list"]).get_output().stdout.clone())?;
+    assert_eq!(listed.trim(), "the cache is empty");
+    Ok(())
+}
+
+#[test]
+fn test_import_sphinx_subcommand() -> Result<()> {
+    let project_dir = tempdir()?;
+    fs::create_dir_all(project_dir.path().join("docs"))?;
+    fs::write(
+        project_dir.path().join("docs/conf.py"),
+        r#"
+project = "test_pkg"
+intersphinx_mapping = {"numpy": ("https://numpy.org/doc/stable/", "objects/numpy.inv")}
+autodoc_default_options = {"members": True, "private-members": True}
+"#,
+    )?;
+
+    let mut cmd = cargo_bin_cmd!();
+    cmd.current_dir(project_dir.path()).arg("import-sphinx");
+    cmd.assert().success();
+
+    let imported = fs::read_to_string(project_dir.path().join("snakedown.toml"))?;
+    let config: toml::Table = toml::from_str(&imported)?;
+    assert_eq!(config["skip_private"].as_bool(), Some(false), "{imported}");
+    assert_eq!(config["skip_undoc"].as_bool(), Some(true), "{imported}");
+    assert_eq!(
+        config["externals"]["numpy"]["inventory"].as_str(),
+        Some(
+            Path::new("docs")
+                .join("objects/numpy.inv")
+                .to_string_lossy()
+                .as_ref()
+        ),
+        "{imported}"
+    );
+
+    // an existing config is never overwritten
+    let mut cmd = cargo_bin_cmd!();
+    cmd.current_dir(project_dir.path()).arg("import-sphinx");
+    cmd.assert().failure();
+    Ok(())
+}
@@ -3,3 +3,8 @@ Root package initialization.
 
 This file marks the root as a Python package.
 """
+
+from ._private.internals import InternalHelper
+from .bar import greet
+
+__all__ = ["InternalHelper", "greet"]
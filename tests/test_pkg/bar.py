@@ -9,7 +9,13 @@ See Also:
     [[test_pkg.bar.Greeter.greet]]
 """
 
-DEFAULT_GREETING = "Hello"
+from typing import Callable
+
+DEFAULT_GREETING: str = "Hello"
+"""The greeting used by [[test_pkg.bar.greet]]."""
+
+type GreetingFormatter = Callable[[str], str]
+"""Anything that can turn a name into a greeting."""
 
 
 def greet(name: str) -> str:
@@ -55,7 +61,8 @@ class Greeter:
         name (str): The name to greet.
     """
 
-    DEFAULT_PERSONAL_GREETING = "Hi"
+    DEFAULT_PERSONAL_GREETING: str = "Hi"
+    """The greeting used by [[test_pkg.bar.Greeter.greet]]."""
 
     def __init__(self, name: str):
         """
@@ -66,6 +73,16 @@ class Greeter:
         """
         self.name = _format_name(name)
 
+    @classmethod
+    def anonymous(cls) -> "Greeter":
+        """
+        Create a greeter for someone whose name we don't know.
+
+        Returns:
+            Greeter: A greeter for strangers.
+        """
+        return cls("stranger")
+
     def greet(self) -> str:
         """
         Generate a greeting message. This is a method on the [[test_pkg.bar.Greeter]] class.
@@ -75,3 +92,20 @@ class Greeter:
             str: Greeting message.
         """
         return f"{self.DEFAULT_PERSONAL_GREETING}, {self.name}!"
+
+
+class LoudGreeter(Greeter):
+    """
+    A [[test_pkg.bar.Greeter]] that really wants to be heard.
+    """
+
+    def greet(self) -> str:
+        """
+        Generate a greeting message in capitals.
+
+        Shouts whatever :meth:`~Greeter.greet` says, using :func:`!str.upper`.
+
+        Returns:
+            str: Greeting message.
+        """
+        return super().greet().upper()
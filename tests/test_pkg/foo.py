@@ -8,7 +8,7 @@ See Also:
     [[numpy.linspace]]
 """
 
-__all__ = ["add", "multiply"]
+__all__ = ["add", "multiply", "clamp"]
 
 
 def add(a: float, b: float) -> float:
@@ -43,6 +43,33 @@ def multiply(a: float, b: float) -> float:
     return a * b
 
 
+def clamp(
+    value: float,
+    /,
+    lower: float = 0.0,
+    upper: float = 1.0,
+    *,
+    strict: bool = False,
+    message=None,
+) -> float:
+    """
+    Limit a number to a range.
+
+    Args:
+        value (float): The number to limit.
+        lower (float): The smallest allowed value.
+        upper (float): The largest allowed value.
+        strict (bool): Raise instead of clamping.
+        message (str): What to raise with.
+
+    Returns:
+        float: The clamped value.
+    """
+    if strict and not lower <= value <= upper:
+        raise ValueError(message)
+    return min(max(value, lower), upper)
+
+
 def _subtract(a: float, b: float) -> float:
     """
     Subtract b from a (private helper).
@@ -4,6 +4,8 @@ mid.py
 Example module inside sub1.
 """
 
+from ..bar import Greeter
+
 
 def square(x: int) -> int:
     """
@@ -22,6 +24,23 @@ def square(x: int) -> int:
     return x * x
 
 
+def greet_squared(greeter: Greeter, x: int) -> str:
+    """
+    Greet the square of a number.
+
+    The greeting comes from [[Greeter.greet]], the number from
+    [[square]]. To just say hello use [[..bar.greet|greet]] instead.
+
+    Args:
+        greeter (Greeter): Who does the greeting.
+        x (int): Number to square.
+
+    Returns:
+        str: The greeting.
+    """
+    return f"{greeter.greet()} {square(x)}"
+
+
 class Squarer:
     """
     Class to square numbers.
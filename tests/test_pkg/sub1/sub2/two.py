@@ -4,6 +4,8 @@ two.py
 Module with a counter class.
 """
 
+import numpy
+
 
 class Counter:
     """
@@ -22,6 +24,13 @@ class Counter:
         """
         self.count = start
 
+    @property
+    def is_zero(self) -> bool:
+        """
+        Whether the count is currently zero.
+        """
+        return self.count == 0
+
     def increment(self) -> int:
         """
         Increment the count by 1.
@@ -37,3 +46,9 @@ class Counter:
         Reset the count to zero.
         """
         self.count = 0
+
+
+class CountHistory(numpy.ndarray):
+    """
+    All the values a [[test_pkg.sub1.sub2.two.Counter]] has had.
+    """